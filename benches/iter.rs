@@ -0,0 +1,22 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tension::{Shape, HostTensor};
+
+fn iter(c: &mut Criterion) {
+    let shape = Shape::from([256, 256, 4].as_ref());
+    let values = (0..shape.content()).map(|i| (i % 7) as f32).collect::<Vec<_>>();
+    let a = HostTensor::from_vec(&shape, values.clone());
+
+    c.bench_function("slice", |b| b.iter(|| black_box(values.as_slice()).iter().sum::<f32>()));
+    c.bench_function("iter", |b| b.iter(|| black_box(&a).iter().sum::<f32>()));
+    c.bench_function("iter_rev", |b| b.iter(|| black_box(&a).iter().rev().sum::<f32>()));
+    c.bench_function("indexed_iter", |b| b.iter(|| black_box(&a).indexed_iter().map(|(_, x)| x).sum::<f32>()));
+    c.bench_function("lanes", |b| b.iter(|| {
+        black_box(&a).lanes(1).map(|lane| lane.iter().sum::<f32>()).sum::<f32>()
+    }));
+    c.bench_function("axis_iter", |b| b.iter(|| {
+        black_box(&a).axis_iter(0).map(|view| view.iter().sum::<f32>()).sum::<f32>()
+    }));
+}
+
+criterion_group!(benches, iter);
+criterion_main!(benches);
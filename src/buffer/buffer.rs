@@ -1,6 +1,32 @@
 use crate::{Prm};
 
 
+/// Equally spaced blocks of values to copy between buffers.
+///
+/// Block `k` starts at `src_offset + k*src_stride` in the source buffer
+/// and at `dst_offset + k*dst_stride` in the destination one.
+#[derive(Clone, Copy, Debug)]
+pub struct Blocks {
+    /// Number of values in each block.
+    pub len: usize,
+    /// Number of blocks.
+    pub count: usize,
+    pub src_offset: usize,
+    pub src_stride: usize,
+    pub dst_offset: usize,
+    pub dst_stride: usize,
+}
+
+impl Blocks {
+    /// Copies blocks between slices.
+    pub fn copy<T: Copy>(&self, dst: &mut [T], src: &[T]) {
+        for k in 0..self.count {
+            let (s, d) = (self.src_offset + k*self.src_stride, self.dst_offset + k*self.dst_stride);
+            dst[d..(d + self.len)].copy_from_slice(&src[s..(s + self.len)]);
+        }
+    }
+}
+
 /// Flat contiguous array.
 pub trait Buffer<T: Prm>: Sized + Clone {
     /// Context for buffer allocation.
@@ -26,4 +52,6 @@ pub trait Buffer<T: Prm>: Sized + Clone {
     fn copy_from(&mut self, src: &Self);
     /// Copies content from `self` to another buffer.
     fn copy_to(&self, dst: &mut Self);
+    /// Copies blocks of values to `self` from another buffer.
+    fn copy_blocks_from(&mut self, src: &Self, blocks: &Blocks);
 }
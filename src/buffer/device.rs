@@ -1,9 +1,17 @@
+use std::{
+    mem::size_of,
+    sync::{Arc, Mutex},
+    collections::HashMap,
+};
 use crate::{
-    Prm, Interop,
-    Buffer, HostBuffer,
+    Prm, Interop, SizeRepr, OverflowError,
+    Buffer, Blocks, HostBuffer,
 };
 
-use ocl::{Buffer as OclBuffer, Queue, MemFlags};
+use ocl::{
+    Buffer as OclBuffer, Queue, MemFlags, Program,
+    enums::{DeviceInfo, DeviceInfoResult},
+};
 
 
 /// Buffer context.
@@ -11,18 +19,66 @@ use ocl::{Buffer as OclBuffer, Queue, MemFlags};
 #[derive(Clone, Debug)]
 pub struct DeviceContext {
     queue: Queue,
+    size_repr: SizeRepr,
+    extensions: String,
+    programs: Arc<Mutex<HashMap<String, Program>>>,
 }
 impl DeviceContext {
+    /// Creates context for the queue.
+    /// Device representation of sizes is selected according to `CL_DEVICE_ADDRESS_BITS`,
+    /// 32-bit representation is used if the device fails to report it.
     pub fn new(queue: Queue) -> Self {
-        Self { queue }
+        let size_repr = match queue.device().info(DeviceInfo::AddressBits) {
+            Ok(DeviceInfoResult::AddressBits(bits)) => SizeRepr::from_address_bits(bits),
+            _ => SizeRepr::U32,
+        };
+        Self::with_size_repr(queue, size_repr)
+    }
+    /// Creates context for the queue with explicitly specified device representation of sizes.
+    /// If the device fails to report its extensions, none of them are considered supported.
+    pub fn with_size_repr(queue: Queue, size_repr: SizeRepr) -> Self {
+        let extensions = match queue.device().info(DeviceInfo::Extensions) {
+            Ok(DeviceInfoResult::Extensions(extensions)) => extensions,
+            _ => String::new(),
+        };
+        Self { queue, size_repr, extensions, programs: Arc::new(Mutex::new(HashMap::new())) }
     }
     pub fn queue(&self) -> &Queue {
-        return &self.queue
+        &self.queue
+    }
+    /// Device representation of `usize` and `isize`.
+    pub fn size_repr(&self) -> SizeRepr {
+        self.size_repr
+    }
+    /// Checks whether the device supports specified OpenCL extension.
+    pub fn has_extension(&self, name: &str) -> bool {
+        self.extensions.split_whitespace().any(|ext| ext == name)
+    }
+    /// Returns program with specified key, builds it from source if it is not built yet.
+    pub(crate) fn program<F: FnOnce() -> String>(&self, key: &str, source: F) -> Program {
+        if let Some(program) = self.programs.lock().unwrap().get(key) {
+            return program.clone();
+        }
+        // Lock is not held while building, so if another thread builds the same program the first one is kept.
+        let program = Program::builder()
+            .src(source())
+            .devices(self.queue.device())
+            .build(&self.queue.context())
+            .unwrap();
+        self.programs.lock().unwrap().entry(key.to_string()).or_insert(program).clone()
+    }
+    /// Checks whether device kernels can operate on values of type `T`.
+    pub fn supports<T: Interop>(&self) -> bool {
+        match T::dev_type(self.size_repr).extension() {
+            Some(ext) => self.has_extension(ext),
+            None => true,
+        }
     }
 }
 impl PartialEq for DeviceContext {
     fn eq(&self, other: &Self) -> bool {
-        self.queue.as_ptr() == other.queue.as_ptr()
+        self.queue.as_ptr() == other.queue.as_ptr() &&
+        self.size_repr == other.size_repr
     }
 }
 
@@ -39,53 +95,100 @@ impl<T: Prm + Interop> Buffer<T> for DeviceBuffer<T> {
         OclBuffer::builder()
         .queue(context.queue().clone())
         .flags(MemFlags::READ_WRITE)
-        .len(len)
+        .len(len * T::dev_len(context.size_repr()))
         .build()
         .map(|mem| DeviceBuffer { mem, ctx: context.clone() })
         .unwrap()
     }
 
     fn new_filled_in(context: &DeviceContext, len: usize, value: T) -> Self {
-        OclBuffer::builder()
-        .queue(context.queue().clone())
-        .flags(MemFlags::READ_WRITE)
-        .len(len)
-        .fill_val(value.to_dev())
-        .build()
-        .map(|mem| DeviceBuffer { mem, ctx: context.clone() })
-        .unwrap()
+        let repr = context.size_repr();
+        let mut dev_value = vec![T::Dev::default(); T::dev_len(repr)];
+        T::to_dev(&[value], &mut dev_value, repr).unwrap();
+        if dev_value.len() == 1 {
+            OclBuffer::builder()
+            .queue(context.queue().clone())
+            .flags(MemFlags::READ_WRITE)
+            .len(len)
+            .fill_val(dev_value[0])
+            .build()
+            .map(|mem| DeviceBuffer { mem, ctx: context.clone() })
+            .unwrap()
+        } else {
+            let mut buffer = unsafe { Self::new_uninit_in(context, len) };
+            buffer.store(&vec![value; len]);
+            buffer
+        }
     }
 
     fn len(&self) -> usize {
-        self.mem.len()
+        self.mem.len() / T::dev_len(self.ctx.size_repr())
     }
     fn context(&self) -> &DeviceContext {
         &self.ctx
     }
 
+    /// Panics if some value cannot be represented on the host, use [`DeviceBuffer::try_load`] to handle this.
     fn load(&self, dst: &mut [T]) {
-        T::load_from_buffer(dst, &self.mem);
+        self.try_load(dst).unwrap();
     }
+    /// Panics if some value cannot be represented on the device, use [`DeviceBuffer::try_store`] to handle this.
     fn store(&mut self, src: &[T]) {
-        T::store_to_buffer(&mut self.mem, src);
+        self.try_store(src).unwrap();
     }
     fn copy_from(&mut self, src: &Self) {
         assert_eq!(self.len(), src.len());
         if self.context() == src.context() {
             src.mem.copy(&mut self.mem, None, None).enq().unwrap();
-        } else {
-            let mut tmp = Vec::<T::Dev>::new();
+        } else if self.context().size_repr() == src.context().size_repr() {
+            let mut tmp = vec![T::Dev::default(); src.mem.len()];
             src.mem.read(&mut tmp).enq()
             .and_then(|_| self.mem.write(tmp.as_slice()).enq())
             .unwrap();
+        } else {
+            let mut tmp = vec![T::zero(); src.len()];
+            src.load(&mut tmp);
+            self.store(&tmp);
         }
     }
     fn copy_to(&self, dst: &mut Self) {
         dst.copy_from(self);
     }
+    /// Blocks are copied by a single rectangular copy on the device, buffers must share the context.
+    fn copy_blocks_from(&mut self, src: &Self, blocks: &Blocks) {
+        assert!(self.context() == src.context(), "Buffers must be in the same context");
+        if blocks.len == 0 || blocks.count == 0 {
+            return;
+        }
+        let n = T::dev_len(self.ctx.size_repr());
+        let (src_pitch, dst_pitch) = (blocks.src_stride * n * size_of::<T::Dev>(), blocks.dst_stride * n * size_of::<T::Dev>());
+        src.mem.cmd().copy(&self.mem, None, None).rect(
+            [blocks.src_offset * n, 0, 0],
+            [blocks.dst_offset * n, 0, 0],
+            [blocks.len * n, blocks.count, 1],
+            src_pitch, src_pitch * blocks.count,
+            dst_pitch, dst_pitch * blocks.count,
+        ).enq().unwrap();
+    }
 }
 
 impl<T: Prm + Interop> DeviceBuffer<T> {
+    /// Underlying OpenCL buffer.
+    pub(crate) fn mem(&self) -> &OclBuffer<T::Dev> {
+        &self.mem
+    }
+
+    /// Loads data from buffer to slice.
+    /// Fails if some value cannot be represented on the host.
+    pub fn try_load(&self, dst: &mut [T]) -> Result<(), OverflowError> {
+        T::load_from_buffer(dst, &self.mem, self.ctx.size_repr())
+    }
+    /// Stores data from slice to buffer.
+    /// Fails if some value cannot be represented on the device, in that case buffer content is left unchanged.
+    pub fn try_store(&mut self, src: &[T]) -> Result<(), OverflowError> {
+        T::store_to_buffer(&mut self.mem, src, self.ctx.size_repr())
+    }
+
     /// Copies content to `self` from host buffer.
     pub fn copy_from_host(&mut self, src: &HostBuffer<T>) {
         assert_eq!(self.len(), src.len());
@@ -110,3 +213,17 @@ impl<T: Prm + Interop> Clone for DeviceBuffer<T> {
         self.clone_to(&self.context())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn send_sync() {
+        assert_send_sync::<DeviceContext>();
+        assert_send_sync::<DeviceBuffer<f32>>();
+        assert_send_sync::<DeviceBuffer<usize>>();
+    }
+}
@@ -1,13 +1,27 @@
 use crate::{
     Prm,
-    Buffer
+    Buffer, Blocks,
 };
+#[cfg(feature = "mmap")]
+use crate::MappedBuffer;
 
 
+/// Storage of the host buffer.
+#[derive(Clone)]
+enum Storage<T: Prm> {
+    /// Owned vector.
+    Vec(Vec<T>),
+    /// Memory-mapped file.
+    #[cfg(feature = "mmap")]
+    Mapped(MappedBuffer<T>),
+}
+
 /// Buffer that stores data on the host. Simply a wrapper around `Vec`.
+///
+/// Buffer may also refer to a memory-mapped file without reading it, see [`MappedBuffer`].
 #[derive(Clone)]
 pub struct HostBuffer<T: Prm> {
-    vec: Vec<T>,
+    storage: Storage<T>,
 }
 
 impl<T: Prm> Buffer<T> for HostBuffer<T> {
@@ -16,16 +30,16 @@ impl<T: Prm> Buffer<T> for HostBuffer<T> {
     unsafe fn new_uninit_in(_: &(), len: usize) -> Self {
         let mut vec = Vec::<T>::with_capacity(len);
         vec.set_len(len);
-        Self { vec }
+        Self::from(vec)
     }
     fn new_filled_in(_: &(), len: usize, value: T) -> Self {
         let mut vec = Vec::<T>::new();
         vec.resize(len, value);
-        Self { vec }
+        Self::from(vec)
     }
 
     fn len(&self) -> usize {
-        self.vec.len()
+        self.as_slice().len()
     }
     fn context(&self) -> &() {
         &()
@@ -44,6 +58,22 @@ impl<T: Prm> Buffer<T> for HostBuffer<T> {
     fn copy_to(&self, dst: &mut Self) {
         dst.copy_from(self);
     }
+    fn copy_blocks_from(&mut self, src: &Self, blocks: &Blocks) {
+        blocks.copy(self.as_mut_slice(), src.as_slice());
+    }
+}
+
+impl<T: Prm> From<Vec<T>> for HostBuffer<T> {
+    fn from(vec: Vec<T>) -> Self {
+        Self { storage: Storage::Vec(vec) }
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl<T: Prm> From<MappedBuffer<T>> for HostBuffer<T> {
+    fn from(buffer: MappedBuffer<T>) -> Self {
+        Self { storage: Storage::Mapped(buffer) }
+    }
 }
 
 impl<T: Prm> HostBuffer<T> {
@@ -57,12 +87,38 @@ impl<T: Prm> HostBuffer<T> {
         <Self as Buffer<T>>::new_filled_in(&(), len, value)
     }
 
+    /// Whether buffer refers to memory-mapped file.
+    pub fn is_mapped(&self) -> bool {
+        match &self.storage {
+            Storage::Vec(_) => false,
+            #[cfg(feature = "mmap")]
+            Storage::Mapped(buffer) => buffer.is_file_backed(),
+        }
+    }
+
+    /// Extracts underlying vector.
+    pub fn into_vec(self) -> Vec<T> {
+        match self.storage {
+            Storage::Vec(vec) => vec,
+            #[cfg(feature = "mmap")]
+            Storage::Mapped(buffer) => buffer.as_slice().to_vec(),
+        }
+    }
+
     /// Provideas access to underlying memory.
     pub fn as_slice(&self) -> &[T] {
-        self.vec.as_slice()
+        match &self.storage {
+            Storage::Vec(vec) => vec.as_slice(),
+            #[cfg(feature = "mmap")]
+            Storage::Mapped(buffer) => buffer.as_slice(),
+        }
     }
     /// Provideas mutable access to underlying memory.
     pub fn as_mut_slice(&mut self) -> &mut [T] {
-        self.vec.as_mut_slice()
+        match &mut self.storage {
+            Storage::Vec(vec) => vec.as_mut_slice(),
+            #[cfg(feature = "mmap")]
+            Storage::Mapped(buffer) => buffer.as_mut_slice(),
+        }
     }
 }
@@ -0,0 +1,265 @@
+use std::{
+    fs::File,
+    io,
+    mem,
+    sync::Arc,
+    slice,
+    marker::PhantomData,
+};
+use memmap2::{Mmap, MmapMut, MmapOptions};
+use crate::{
+    Prm,
+    Buffer, Blocks,
+};
+
+
+/// Mode of mapping file into memory.
+///
+/// In both modes the file itself is never modified.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapMode {
+    /// Mapped memory is read-only, the whole region is copied to anonymous memory on first modification.
+    ReadOnly,
+    /// Mapped memory is private and writable, only modified pages are copied by the operating system.
+    CopyOnWrite,
+}
+
+/// Memory mapping shared between buffers.
+enum Mapping {
+    ReadOnly(Mmap),
+    CopyOnWrite(MmapMut),
+    Anonymous(MmapMut),
+}
+
+impl Mapping {
+    fn as_ptr(&self) -> *const u8 {
+        match self {
+            Mapping::ReadOnly(map) => map.as_ptr(),
+            Mapping::CopyOnWrite(map) | Mapping::Anonymous(map) => map.as_ptr(),
+        }
+    }
+    fn as_mut_ptr(&mut self) -> Option<*mut u8> {
+        match self {
+            Mapping::ReadOnly(_) => None,
+            Mapping::CopyOnWrite(map) | Mapping::Anonymous(map) => Some(map.as_mut_ptr()),
+        }
+    }
+}
+
+/// Buffer that stores data in memory-mapped file.
+///
+/// Data is not read from file up front, it is loaded by the operating system on access.
+/// Buffers created by [`Buffer`] methods are backed by anonymous memory mapping.
+pub struct MappedBuffer<T: Prm> {
+    map: Arc<Mapping>,
+    /// Offset of the first value in bytes from the beginning of the mapping.
+    offset: usize,
+    len: usize,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Prm> Clone for MappedBuffer<T> {
+    /// Clone shares the mapping, it is copied only on modification.
+    fn clone(&self) -> Self {
+        Self { map: self.map.clone(), offset: self.offset, len: self.len, phantom: PhantomData }
+    }
+}
+
+impl<T: Prm> MappedBuffer<T> {
+    /// Maps `len` values starting from `offset` bytes of the file.
+    /// Offset must be a multiple of the `T` alignment.
+    ///
+    /// # Safety
+    ///
+    /// The region must contain valid values of type `T` in native byte order,
+    /// and the file must not be modified while it is mapped, see [`memmap2::Mmap::map`].
+    /// The region of `offset + len * size_of::<T>()` bytes must lie within the file,
+    /// otherwise accessing the part of mapping beyond the end of file causes `SIGBUS`.
+    pub unsafe fn map(file: &File, offset: u64, len: usize, mode: MapMode) -> io::Result<Self> {
+        if offset % mem::align_of::<T>() as u64 != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "offset is not aligned to the value type"));
+        }
+        let size = len.checked_mul(mem::size_of::<T>())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "mapped region size overflows"))?;
+        let mut options = MmapOptions::new();
+        options.offset(offset).len(size);
+        let map = match mode {
+            MapMode::ReadOnly => Mapping::ReadOnly(options.map(file)?),
+            MapMode::CopyOnWrite => Mapping::CopyOnWrite(options.map_copy(file)?),
+        };
+        Ok(Self { map: Arc::new(map), offset: 0, len, phantom: PhantomData })
+    }
+    /// Maps the whole file, its size must be a multiple of the `T` size.
+    ///
+    /// # Safety
+    ///
+    /// See [`MappedBuffer::map`].
+    pub unsafe fn map_file(file: &File, mode: MapMode) -> io::Result<Self> {
+        let size = file.metadata()?.len() as usize;
+        if size % mem::size_of::<T>() != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "file size is not a multiple of the value size"));
+        }
+        Self::map(file, 0, size / mem::size_of::<T>(), mode)
+    }
+
+    /// Buffer of `len` zeroed bytes in anonymous memory.
+    fn new_anon(len: usize) -> Self {
+        let map = MmapMut::map_anon(len * mem::size_of::<T>()).unwrap();
+        Self { map: Arc::new(Mapping::Anonymous(map)), offset: 0, len, phantom: PhantomData }
+    }
+
+    /// Buffer that shares the mapping and refers to `len` values of type `U` starting from `offset` bytes of `self`.
+    ///
+    /// The region must be properly aligned and contain valid values of type `U`.
+    #[cfg(feature = "safetensors")]
+    pub(crate) unsafe fn view<U: Prm>(&self, offset: usize, len: usize) -> MappedBuffer<U> {
+        assert!(offset + len * mem::size_of::<U>() <= self.len * mem::size_of::<T>());
+        let offset = self.offset + offset;
+        assert_eq!((self.map.as_ptr() as usize + offset) % mem::align_of::<U>(), 0);
+        MappedBuffer { map: self.map.clone(), offset, len, phantom: PhantomData }
+    }
+
+    /// Whether buffer refers to the mapped file, i.e. it was not created in or copied to anonymous memory.
+    pub fn is_file_backed(&self) -> bool {
+        !matches!(self.map.as_ref(), Mapping::Anonymous(_))
+    }
+
+    /// Provides access to mapped memory.
+    pub fn as_slice(&self) -> &[T] {
+        if self.len == 0 {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.map.as_ptr().add(self.offset) as *const T, self.len) }
+    }
+    /// Provides mutable access to mapped memory.
+    ///
+    /// Data is copied to anonymous memory first if the mapping is read-only or shared with other buffers.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        if self.len == 0 {
+            return &mut [];
+        }
+        if Arc::get_mut(&mut self.map).and_then(Mapping::as_mut_ptr).is_none() {
+            let mut copy = Self::new_anon(self.len);
+            copy.as_mut_slice().copy_from_slice(self.as_slice());
+            *self = copy;
+        }
+        let ptr = Arc::get_mut(&mut self.map).and_then(Mapping::as_mut_ptr).unwrap();
+        unsafe { slice::from_raw_parts_mut(ptr.add(self.offset) as *mut T, self.len) }
+    }
+}
+
+impl<T: Prm> Buffer<T> for MappedBuffer<T> {
+    type Context = ();
+
+    unsafe fn new_uninit_in(_: &(), len: usize) -> Self {
+        Self::new_anon(len)
+    }
+    fn new_filled_in(_: &(), len: usize, value: T) -> Self {
+        let mut buffer = Self::new_anon(len);
+        buffer.as_mut_slice().iter_mut().for_each(|x| *x = value);
+        buffer
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn context(&self) -> &() {
+        &()
+    }
+
+    fn load(&self, dst: &mut [T]) {
+        dst.copy_from_slice(self.as_slice());
+    }
+    fn store(&mut self, src: &[T]) {
+        self.as_mut_slice().copy_from_slice(src);
+    }
+
+    fn copy_from(&mut self, src: &Self) {
+        self.store(src.as_slice());
+    }
+    fn copy_to(&self, dst: &mut Self) {
+        dst.copy_from(self);
+    }
+    fn copy_blocks_from(&mut self, src: &Self, blocks: &Blocks) {
+        blocks.copy(self.as_mut_slice(), src.as_slice());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, io::Write, path::PathBuf};
+    use crate::{shape, Tensor, CommonTensor};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    fn temp_file(name: &str, data: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("tension-{}-{}", std::process::id(), name));
+        File::create(&path).unwrap().write_all(data).unwrap();
+        path
+    }
+
+    fn raw_data() -> Vec<u8> {
+        (0..12i32).flat_map(|x| x.to_ne_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn read_only() {
+        let path = temp_file("read_only", &raw_data());
+        let buffer = unsafe { MappedBuffer::<i32>::map_file(&File::open(&path).unwrap(), MapMode::ReadOnly).unwrap() };
+        let mut tensor = CommonTensor::from_buffer(buffer, &shape![4, 3]);
+        let mut values = vec![0; 12];
+        tensor.load(&mut values);
+        assert_eq!(values, (0..12).collect::<Vec<_>>());
+        assert!(tensor.buffer().is_file_backed());
+
+        tensor.store(&[1; 12]);
+        assert!(!tensor.buffer().is_file_backed());
+        assert_eq!(tensor.buffer().as_slice(), &[1; 12]);
+        assert_eq!(fs::read(&path).unwrap(), raw_data());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn copy_on_write() {
+        let path = temp_file("copy_on_write", &raw_data());
+        let mut buffer = unsafe { MappedBuffer::<i32>::map(&File::open(&path).unwrap(), 8, 4, MapMode::CopyOnWrite).unwrap() };
+        assert_eq!(buffer.as_slice(), &[2, 3, 4, 5]);
+        let copy = buffer.clone();
+
+        buffer.as_mut_slice()[0] = -1;
+        assert_eq!(buffer.as_slice(), &[-1, 3, 4, 5]);
+        assert_eq!(copy.as_slice(), &[2, 3, 4, 5]);
+        assert!(copy.is_file_backed());
+
+        let mut copy = copy;
+        copy.as_mut_slice()[1] = -1;
+        assert!(copy.is_file_backed());
+        assert_eq!(copy.as_slice(), &[2, -1, 4, 5]);
+        assert_eq!(fs::read(&path).unwrap(), raw_data());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn invalid_region() {
+        let path = temp_file("invalid_region", &raw_data());
+        assert!(unsafe { MappedBuffer::<i32>::map(&File::open(&path).unwrap(), 2, 1, MapMode::ReadOnly) }.is_err());
+        let err = unsafe { MappedBuffer::<i32>::map(&File::open(&path).unwrap(), 0, usize::MAX, MapMode::ReadOnly) }.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn anonymous() {
+        let buffer = MappedBuffer::new_filled_in(&(), 5, 1.5f64);
+        assert!(!buffer.is_file_backed());
+        assert_eq!(buffer.as_slice(), &[1.5; 5]);
+        assert!(MappedBuffer::<u8>::new_filled_in(&(), 0, 0).as_slice().is_empty());
+    }
+
+    #[test]
+    fn send_sync() {
+        assert_send_sync::<MappedBuffer<f32>>();
+        assert_send_sync::<crate::HostBuffer<f32>>();
+    }
+}
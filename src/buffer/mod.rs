@@ -4,6 +4,11 @@ pub use buffer::*;
 mod host;
 pub use host::*;
 
+#[cfg(feature = "mmap")]
+mod mapped;
+#[cfg(feature = "mmap")]
+pub use mapped::*;
+
 #[cfg(feature = "device")]
 mod device;
 #[cfg(feature = "device")]
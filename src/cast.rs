@@ -0,0 +1,181 @@
+use num_complex::Complex;
+#[cfg(feature = "half")]
+use half::{f16, bf16};
+use crate::{Prm, Float};
+
+
+/// Conversion of tensor element from one type to another.
+///
+/// Conversion follows the semantics of Rust `as` operator:
+///
+/// + Integer to integer conversion is *wrapping*, i.e. the value is truncated to the bits of destination type
+///   (e.g. `300u32` becomes `44u8`, `-1i32` becomes `255u8`).
+/// + Float to integer conversion is *saturating* and rounds towards zero, `NaN` becomes `0`
+///   (e.g. `-1.5f32` becomes `0u8`, `1e10f32` becomes `i32::MAX`).
+/// + Integer to float and float to float conversions round to the nearest representable value.
+/// + `bool` is converted to `0` or `1`, any non-zero value (including `NaN`) is converted to `true`.
+/// + Real value is converted to complex one with zero imaginary part,
+///   complex value is converted to real one by taking its real part.
+pub trait Cast<U: Prm>: Prm {
+    /// Convert value to type `U`.
+    fn cast(self) -> U;
+}
+
+macro_rules! impl_cast_prim {
+    ($($src:ty),* $(,)?) => {
+        $( impl_cast_prim!(@src $src; u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64); )*
+    };
+    (@src $src:ty; $($dst:ty),*) => {
+        $(
+            impl Cast<$dst> for $src {
+                fn cast(self) -> $dst {
+                    self as $dst
+                }
+            }
+        )*
+        impl Cast<bool> for $src {
+            fn cast(self) -> bool {
+                self != 0 as $src
+            }
+        }
+        impl Cast<$src> for bool {
+            fn cast(self) -> $src {
+                self as u8 as $src
+            }
+        }
+        impl<T: Float> Cast<Complex<T>> for $src where $src: Cast<T> {
+            fn cast(self) -> Complex<T> {
+                Complex::new(self.cast(), T::zero())
+            }
+        }
+        impl<T: Float + Cast<$src>> Cast<$src> for Complex<T> {
+            fn cast(self) -> $src {
+                self.re.cast()
+            }
+        }
+    };
+}
+
+impl_cast_prim!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+impl Cast<bool> for bool {
+    fn cast(self) -> bool {
+        self
+    }
+}
+impl<T: Float> Cast<Complex<T>> for bool where bool: Cast<T> {
+    fn cast(self) -> Complex<T> {
+        Complex::new(self.cast(), T::zero())
+    }
+}
+impl<T: Float> Cast<bool> for Complex<T> {
+    fn cast(self) -> bool {
+        self.re != T::zero() || self.im != T::zero()
+    }
+}
+impl<T: Float + Cast<U>, U: Float> Cast<Complex<U>> for Complex<T> {
+    fn cast(self) -> Complex<U> {
+        Complex::new(self.re.cast(), self.im.cast())
+    }
+}
+
+/// Half-precision values are converted through `f64` that can represent all of them exactly.
+#[cfg(feature = "half")]
+macro_rules! impl_cast_half {
+    ($($src:ty),* $(,)?) => {
+        $( impl_cast_half!(@src $src; u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64); )*
+    };
+    (@src $src:ty; $($dst:ty),*) => {
+        $(
+            impl Cast<$dst> for $src {
+                fn cast(self) -> $dst {
+                    self.to_f64() as $dst
+                }
+            }
+            impl Cast<$src> for $dst {
+                fn cast(self) -> $src {
+                    <$src>::from_f64(self as f64)
+                }
+            }
+        )*
+        impl Cast<f16> for $src {
+            fn cast(self) -> f16 {
+                f16::from_f64(self.to_f64())
+            }
+        }
+        impl Cast<bf16> for $src {
+            fn cast(self) -> bf16 {
+                bf16::from_f64(self.to_f64())
+            }
+        }
+        impl Cast<bool> for $src {
+            fn cast(self) -> bool {
+                self.to_f32() != 0.0
+            }
+        }
+        impl Cast<$src> for bool {
+            fn cast(self) -> $src {
+                <$src>::from_f32(self as u8 as f32)
+            }
+        }
+        impl<T: Float> Cast<Complex<T>> for $src where $src: Cast<T> {
+            fn cast(self) -> Complex<T> {
+                Complex::new(self.cast(), T::zero())
+            }
+        }
+        impl<T: Float + Cast<$src>> Cast<$src> for Complex<T> {
+            fn cast(self) -> $src {
+                self.re.cast()
+            }
+        }
+    };
+}
+
+#[cfg(feature = "half")]
+impl_cast_half!(f16, bf16);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer() {
+        assert_eq!(Cast::<u8>::cast(300u32), 44);
+        assert_eq!(Cast::<u8>::cast(-1i32), 255);
+        assert_eq!(Cast::<i64>::cast(-1i8), -1);
+    }
+
+    #[test]
+    fn float() {
+        assert_eq!(Cast::<u8>::cast(-1.5f32), 0);
+        assert_eq!(Cast::<i32>::cast(1e10f32), i32::MAX);
+        assert_eq!(Cast::<i32>::cast(f64::NAN), 0);
+        assert_eq!(Cast::<i32>::cast(-2.7f64), -2);
+        assert_eq!(Cast::<f32>::cast(16777217i32), 16777216.0);
+    }
+
+    #[test]
+    fn bool_() {
+        assert_eq!(Cast::<i32>::cast(true), 1);
+        assert_eq!(Cast::<f64>::cast(false), 0.0);
+        assert!(Cast::<bool>::cast(-3i16));
+        assert!(Cast::<bool>::cast(f32::NAN));
+        assert!(!Cast::<bool>::cast(-0.0f32));
+    }
+
+    #[test]
+    fn complex() {
+        assert_eq!(Cast::<Complex<f32>>::cast(2u8), Complex::new(2.0, 0.0));
+        assert_eq!(Cast::<i32>::cast(Complex::new(-2.5f64, 1.0)), -2);
+        assert_eq!(Cast::<Complex<f32>>::cast(Complex::new(0.5f64, 1.5)), Complex::new(0.5, 1.5));
+        assert!(Cast::<bool>::cast(Complex::new(0.0f32, 1.0)));
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn half() {
+        assert_eq!(Cast::<f16>::cast(70000i32), f16::INFINITY);
+        assert_eq!(Cast::<u8>::cast(f16::from_f32(300.0)), 255);
+        assert_eq!(Cast::<bf16>::cast(f16::from_f32(0.5)), bf16::from_f32(0.5));
+    }
+}
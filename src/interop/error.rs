@@ -0,0 +1,30 @@
+use std::{
+    fmt,
+    error::Error,
+    any::type_name,
+};
+use crate::Shape;
+
+
+/// Tensor shape does not fit the type it is converted to.
+#[derive(Clone, Debug)]
+pub struct ShapeMismatch {
+    /// Shape of the tensor.
+    pub shape: Shape,
+    /// Name of the target type.
+    pub target: &'static str,
+}
+
+impl ShapeMismatch {
+    pub(crate) fn new<U>(shape: &Shape) -> Self {
+        Self { shape: shape.clone(), target: type_name::<U>() }
+    }
+}
+
+impl fmt::Display for ShapeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Tensor of shape {} cannot be converted to {}", self.shape, self.target)
+    }
+}
+
+impl Error for ShapeMismatch {}
@@ -0,0 +1,8 @@
+#[cfg(feature = "ndarray")]
+mod ndarray;
+#[cfg(feature = "nalgebra")]
+mod nalgebra;
+#[cfg(feature = "nalgebra")]
+mod error;
+#[cfg(feature = "nalgebra")]
+pub use error::ShapeMismatch;
@@ -0,0 +1,154 @@
+use std::{convert::TryFrom, mem};
+use nalgebra::{DMatrix, DVector, SMatrix, Scalar};
+use crate::{Prm, Shape, Tensor, HostTensor, ShapeMismatch};
+
+
+// Matrix element at row `i` and column `j` corresponds to tensor element at position `(i, j)`.
+// Both `nalgebra` matrices and tensors store data in column-major order, so dynamic matrices are converted without copying.
+
+/// Takes the matrix data without copying.
+/// Matrix of `n` rows and `m` columns becomes tensor of shape `(n, m)`.
+impl<T: Prm + Scalar> From<DMatrix<T>> for HostTensor<T> {
+    fn from(matrix: DMatrix<T>) -> Self {
+        let shape = Shape::from(vec![matrix.nrows(), matrix.ncols()]);
+        HostTensor::from_vec(&shape, matrix.data.into())
+    }
+}
+
+/// Takes the tensor data without copying.
+/// Tensor must be of rank 2 or less, tensor of rank 1 becomes a single-column matrix.
+impl<T: Prm + Scalar> TryFrom<HostTensor<T>> for DMatrix<T> {
+    type Error = ShapeMismatch;
+    fn try_from(tensor: HostTensor<T>) -> Result<Self, ShapeMismatch> {
+        let shape = tensor.shape().clone();
+        if shape.len() > 2 {
+            return Err(ShapeMismatch::new::<Self>(&shape));
+        }
+        Ok(DMatrix::from_vec(shape[0], shape[1], tensor.into_vec()))
+    }
+}
+
+/// Takes the vector data without copying.
+impl<T: Prm + Scalar> From<DVector<T>> for HostTensor<T> {
+    fn from(vector: DVector<T>) -> Self {
+        let shape = Shape::from(vec![vector.nrows()]);
+        HostTensor::from_vec(&shape, vector.data.into())
+    }
+}
+
+/// Takes the tensor data without copying, tensor must be of rank 1 or less.
+impl<T: Prm + Scalar> TryFrom<HostTensor<T>> for DVector<T> {
+    type Error = ShapeMismatch;
+    fn try_from(tensor: HostTensor<T>) -> Result<Self, ShapeMismatch> {
+        if tensor.shape().len() > 1 {
+            return Err(ShapeMismatch::new::<Self>(tensor.shape()));
+        }
+        Ok(DVector::from_vec(tensor.into_vec()))
+    }
+}
+
+/// Matrix of `R` rows and `C` columns becomes tensor of shape `(R, C)`.
+impl<T: Prm + Scalar, const R: usize, const C: usize> From<SMatrix<T, R, C>> for HostTensor<T> {
+    fn from(matrix: SMatrix<T, R, C>) -> Self {
+        HostTensor::from_vec(&Shape::from(vec![R, C]), matrix.as_slice().to_vec())
+    }
+}
+
+/// Tensor must be of shape `(R, C)`.
+impl<T: Prm + Scalar, const R: usize, const C: usize> TryFrom<&HostTensor<T>> for SMatrix<T, R, C> {
+    type Error = ShapeMismatch;
+    fn try_from(tensor: &HostTensor<T>) -> Result<Self, ShapeMismatch> {
+        if tensor.shape() != &Shape::from(vec![R, C]) {
+            return Err(ShapeMismatch::new::<Self>(tensor.shape()));
+        }
+        Ok(SMatrix::from_column_slice(tensor.buffer().as_slice()))
+    }
+}
+
+/// Takes the batch data without copying.
+/// Batch of `N` matrices of `R` rows and `C` columns becomes tensor of shape `(R, C, N)`.
+impl<T: Prm + Scalar, const R: usize, const C: usize> From<Vec<SMatrix<T, R, C>>> for HostTensor<T> {
+    fn from(batch: Vec<SMatrix<T, R, C>>) -> Self {
+        let shape = Shape::from(vec![R, C, batch.len()]);
+        // Matrix is stored as a column-major array of `R*C` elements without padding.
+        assert_eq!(mem::size_of::<SMatrix<T, R, C>>(), R * C * mem::size_of::<T>());
+        let mut batch = mem::ManuallyDrop::new(batch);
+        let (ptr, len, cap) = (batch.as_mut_ptr(), batch.len(), batch.capacity());
+        let vec = unsafe { Vec::from_raw_parts(ptr as *mut T, R * C * len, R * C * cap) };
+        HostTensor::from_vec(&shape, vec)
+    }
+}
+
+/// Tensor must be of shape `(R, C, N)` for any `N`.
+/// Data is taken without copying if it is not shared with other tensors.
+impl<T: Prm + Scalar, const R: usize, const C: usize> TryFrom<HostTensor<T>> for Vec<SMatrix<T, R, C>> {
+    type Error = ShapeMismatch;
+    fn try_from(tensor: HostTensor<T>) -> Result<Self, ShapeMismatch> {
+        let shape = tensor.shape();
+        if shape.len() > 3 || shape[0] != R || shape[1] != C {
+            return Err(ShapeMismatch::new::<Self>(shape));
+        }
+        let vec = tensor.into_vec();
+        if R * C == 0 || vec.capacity() % (R * C) != 0 {
+            return Ok(vec.chunks(R * C).map(SMatrix::from_column_slice).collect());
+        }
+        assert_eq!(mem::size_of::<SMatrix<T, R, C>>(), R * C * mem::size_of::<T>());
+        let mut vec = mem::ManuallyDrop::new(vec);
+        let (ptr, len, cap) = (vec.as_mut_ptr(), vec.len(), vec.capacity());
+        Ok(unsafe { Vec::from_raw_parts(ptr as *mut SMatrix<T, R, C>, len / (R * C), cap / (R * C)) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Matrix2x3, Vector3};
+    use crate::{shape, values};
+
+    #[test]
+    fn dynamic() {
+        let a = DMatrix::from_fn(2, 3, |i, j| (i + 10*j) as f64);
+        let ptr = a.as_ptr();
+        let b = HostTensor::from(a);
+        assert_eq!(b.shape(), &shape![2, 3]);
+        assert_eq!(b.buffer().as_slice().as_ptr(), ptr);
+        assert_eq!(values(&b), [0.0, 1.0, 10.0, 11.0, 20.0, 21.0]);
+
+        let c = DMatrix::try_from(b).unwrap();
+        assert_eq!(c.as_ptr(), ptr);
+        assert_eq!(c[(1, 2)], 21.0);
+
+        let v = DVector::try_from(HostTensor::from(DVector::from_vec(vec![1, 2, 3]))).unwrap();
+        assert_eq!(v, DVector::from_vec(vec![1, 2, 3]));
+        assert_eq!(DMatrix::try_from(HostTensor::from_vec(&shape![2], vec![1, 2])).unwrap().shape(), (2, 1));
+        assert!(DVector::try_from(HostTensor::<u8>::new_zeroed(&shape![2, 2])).is_err());
+        assert!(DMatrix::try_from(HostTensor::<u8>::new_zeroed(&shape![2, 2, 2])).is_err());
+    }
+
+    #[test]
+    fn fixed() {
+        let a = Matrix2x3::new(1, 2, 3, 4, 5, 6);
+        let b = HostTensor::from(a);
+        assert_eq!(b.shape(), &shape![2, 3]);
+        assert_eq!(values(&b), [1, 4, 2, 5, 3, 6]);
+        assert_eq!(Matrix2x3::try_from(&b).unwrap(), a);
+        assert!(Vector3::try_from(&b).is_err());
+    }
+
+    #[test]
+    fn batch() {
+        let batch = (0..4).map(|k| Matrix2x3::from_fn(|i, j| i + 10*j + 100*k)).collect::<Vec<_>>();
+        let copy = batch.clone();
+        let ptr = copy.as_ptr() as *const usize;
+        let a = HostTensor::from(copy);
+        assert_eq!(a.shape(), &shape![2, 3, 4]);
+        assert_eq!(a.buffer().as_slice().as_ptr(), ptr);
+        assert!(a.iter().eq(HostTensor::from_fn(&shape![2, 3, 4], |p| p[0] + 10*p[1] + 100*p[2]).iter()));
+
+        let b = Vec::<Matrix2x3<usize>>::try_from(a).unwrap();
+        assert_eq!(b.as_ptr() as *const usize, ptr);
+        assert_eq!(b, batch);
+        assert_eq!(Vec::<Matrix2x3<usize>>::try_from(HostTensor::from(batch[0])).unwrap(), &batch[..1]);
+        assert!(Vec::<Matrix2x3<usize>>::try_from(HostTensor::<usize>::new_zeroed(&shape![3, 2, 4])).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use ndarray::{ArrayD, ArrayViewD, ArrayViewMutD, IxDyn, ShapeBuilder};
+use crate::{Prm, Shape, Tensor, HostTensor};
+
+
+// Tensor element at position `(i, j, ...)` corresponds to array element at index `[i, j, ...]`.
+// Tensor data is stored with axis 0 varying fastest, that is the column-major (Fortran) layout in terms of `ndarray`.
+
+/// Array dimensions in column-major layout for tensor shape.
+fn dims(shape: &Shape) -> ndarray::Shape<IxDyn> {
+    IxDyn(shape.as_slice()).f()
+}
+
+/// Takes the tensor data without copying and wraps it into column-major array.
+///
+/// Trailing axes of size `1` are ignored by `Shape`, so the array does not have them.
+impl<T: Prm> From<HostTensor<T>> for ArrayD<T> {
+    fn from(tensor: HostTensor<T>) -> Self {
+        let shape = tensor.shape().clone();
+        ArrayD::from_shape_vec(dims(&shape), tensor.into_vec()).unwrap()
+    }
+}
+
+/// Takes the array data without copying if the array is contiguous in column-major layout,
+/// otherwise data is copied.
+impl<T: Prm> From<ArrayD<T>> for HostTensor<T> {
+    fn from(array: ArrayD<T>) -> Self {
+        if !array.t().is_standard_layout() {
+            return HostTensor::from(array.view());
+        }
+        let (shape, len) = (Shape::from(array.shape()), array.len());
+        match array.into_raw_vec_and_offset() {
+            (vec, offset) if offset.unwrap_or(0) == 0 && vec.len() == len => HostTensor::from_vec(&shape, vec),
+            (vec, offset) => {
+                let offset = offset.unwrap_or(0);
+                HostTensor::from_vec(&shape, vec[offset..(offset + len)].to_vec())
+            }
+        }
+    }
+}
+
+/// Copies the array data.
+impl<'a, T: Prm> From<ArrayViewD<'a, T>> for HostTensor<T> {
+    fn from(array: ArrayViewD<'a, T>) -> Self {
+        HostTensor::from_vec(&Shape::from(array.shape()), array.t().iter().cloned().collect())
+    }
+}
+
+impl<T: Prm> HostTensor<T> {
+    /// Array view of the tensor data without copying.
+    pub fn as_array(&self) -> ArrayViewD<'_, T> {
+        ArrayViewD::from_shape(dims(self.shape()), self.buffer().as_slice()).unwrap()
+    }
+    /// Mutable array view of the tensor data without copying.
+    pub fn as_array_mut(&mut self) -> ArrayViewMutD<'_, T> {
+        let shape = self.shape().clone();
+        ArrayViewMutD::from_shape(dims(&shape), self.buffer_mut().as_mut_slice()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{Array, Axis, s};
+    use crate::{shape, values};
+
+    #[test]
+    fn to_array() {
+        let a = HostTensor::from_fn(&shape![2, 3, 4], |p| p[0] + 10*p[1] + 100*p[2]);
+        let ptr = a.buffer().as_slice().as_ptr();
+        let b = ArrayD::from(a);
+        assert_eq!(b.shape(), [2, 3, 4]);
+        assert_eq!(b.strides(), [1, 2, 6]);
+        assert_eq!(b.as_ptr(), ptr);
+        assert_eq!(b[[1, 2, 3]], 321);
+    }
+
+    #[test]
+    fn from_array() {
+        let a = Array::from_shape_fn((2, 3).f(), |(i, j)| i + 10*j).into_dyn();
+        let ptr = a.as_ptr();
+        let b = HostTensor::from(a);
+        assert_eq!(b.shape(), &shape![2, 3]);
+        assert_eq!(b.buffer().as_slice().as_ptr(), ptr);
+        assert_eq!(values(&b), [0, 1, 10, 11, 20, 21]);
+
+        // Row-major array is copied.
+        let c = HostTensor::from(Array::from_shape_fn((2, 3), |(i, j)| i + 10*j).into_dyn());
+        assert!(c.iter().eq(b.iter()));
+
+        // Sliced array with negative stride.
+        let d = Array::from_shape_fn((4, 3), |(i, j)| i + 10*j).into_dyn();
+        let e = HostTensor::from(d.slice(s![..;-2, ..]).to_owned().into_dyn());
+        assert_eq!(e.shape(), &shape![2, 3]);
+        assert_eq!(values(&e), [3, 1, 13, 11, 23, 21]);
+        let f = HostTensor::from(d.slice_axis(Axis(0), (1..3).into()));
+        assert_eq!(values(&f), [1, 2, 11, 12, 21, 22]);
+    }
+
+    #[test]
+    fn views() {
+        let mut a = HostTensor::from_fn(&shape![3, 2], |p| (p[0] + 10*p[1]) as f32);
+        assert_eq!(a.as_array()[[2, 1]], 12.0);
+        assert_eq!(a.as_array().strides(), [1, 3]);
+        a.as_array_mut()[[0, 1]] = -1.0;
+        assert_eq!(values(&a), [0.0, 1.0, 2.0, -1.0, 11.0, 12.0]);
+    }
+}
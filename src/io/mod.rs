@@ -0,0 +1,20 @@
+mod npy;
+pub use npy::*;
+
+mod text;
+pub use text::*;
+
+#[cfg(feature = "npz")]
+mod npz;
+#[cfg(feature = "npz")]
+pub use npz::*;
+
+#[cfg(feature = "safetensors")]
+mod safetensors;
+#[cfg(feature = "safetensors")]
+pub use self::safetensors::*;
+
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(all(feature = "serde", feature = "device"))]
+pub use self::serde::*;
@@ -0,0 +1,617 @@
+use std::{
+    fmt,
+    error::Error,
+    io::{self, Read, Write},
+};
+use num_complex::Complex;
+#[cfg(feature = "half")]
+use half::f16;
+#[cfg(feature = "mmap")]
+use std::fs::File;
+use crate::{Prm, Shape, Tensor, HostTensor};
+#[cfg(feature = "mmap")]
+use crate::{MappedBuffer, MapMode};
+
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+/// Total length of the preamble and header is aligned to this value.
+const ALIGN: usize = 64;
+
+/// Error of reading or writing NumPy `.npy` file.
+#[derive(Debug)]
+pub enum NpyError {
+    /// Underlying I/O error.
+    Io(io::Error),
+    /// File does not start with `\x93NUMPY` magic string.
+    Magic,
+    /// Unsupported format version.
+    Version(u8, u8),
+    /// Header is malformed, the message describes the problem.
+    Header(String),
+    /// Element type stored in file does not match the tensor element type.
+    Dtype {
+        /// Type description of the tensor element, e.g. `<f4`.
+        expected: String,
+        /// Type description from the file header.
+        found: String,
+    },
+    /// File ends before all the data is read.
+    Truncated {
+        /// Number of data bytes expected from header.
+        expected: usize,
+        /// Number of data bytes actually read.
+        found: usize,
+    },
+    /// Data cannot be used without copying, the message describes the reason.
+    Layout(String),
+}
+
+impl fmt::Display for NpyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NpyError::Io(err) => write!(f, "I/O error: {}", err),
+            NpyError::Magic => write!(f, "Not a NumPy file: magic string is missing"),
+            NpyError::Version(major, minor) => write!(f, "Unsupported NumPy format version {}.{}", major, minor),
+            NpyError::Header(msg) => write!(f, "Malformed NumPy header: {}", msg),
+            NpyError::Dtype { expected, found } => write!(f, "Element type mismatch: expected '{}', found '{}'", expected, found),
+            NpyError::Truncated { expected, found } => write!(f, "Data is truncated: expected {} bytes, found {}", expected, found),
+            NpyError::Layout(msg) => write!(f, "Data cannot be mapped: {}", msg),
+        }
+    }
+}
+
+impl Error for NpyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NpyError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for NpyError {
+    fn from(err: io::Error) -> Self {
+        NpyError::Io(err)
+    }
+}
+
+/// Type that can be stored in NumPy file.
+///
+/// `bf16` is not supported because NumPy has no such type.
+pub trait NpyType: Prm {
+    /// NumPy type kind character (`b` for boolean, `u` for unsigned, `i` for signed, `f` for float, `c` for complex).
+    const KIND: char;
+    /// Size of value in bytes.
+    const SIZE: usize;
+
+    /// Read value from bytes with specified byte order.
+    fn read_bytes(bytes: &[u8], big_endian: bool) -> Self;
+    /// Write value as little-endian bytes.
+    fn write_bytes(self, bytes: &mut [u8]);
+
+    /// NumPy type description string, e.g. `<f4`.
+    fn descr() -> String {
+        format!("{}{}{}", if Self::SIZE == 1 { '|' } else { '<' }, Self::KIND, Self::SIZE)
+    }
+}
+
+macro_rules! impl_npy_prim {
+    ($($ty:ty: $kind:expr),* $(,)?) => {
+        $(
+            impl NpyType for $ty {
+                const KIND: char = $kind;
+                const SIZE: usize = std::mem::size_of::<$ty>();
+
+                fn read_bytes(bytes: &[u8], big_endian: bool) -> Self {
+                    let mut buf = [0; std::mem::size_of::<$ty>()];
+                    buf.copy_from_slice(bytes);
+                    if big_endian {
+                        <$ty>::from_be_bytes(buf)
+                    } else {
+                        <$ty>::from_le_bytes(buf)
+                    }
+                }
+                fn write_bytes(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&<$ty>::to_le_bytes(self));
+                }
+            }
+        )*
+    };
+}
+
+impl_npy_prim!(
+    u8: 'u', u16: 'u', u32: 'u', u64: 'u', usize: 'u',
+    i8: 'i', i16: 'i', i32: 'i', i64: 'i', isize: 'i',
+    f32: 'f', f64: 'f',
+);
+
+impl NpyType for bool {
+    const KIND: char = 'b';
+    const SIZE: usize = 1;
+
+    fn read_bytes(bytes: &[u8], _: bool) -> Self {
+        bytes[0] != 0
+    }
+    fn write_bytes(self, bytes: &mut [u8]) {
+        bytes[0] = self as u8;
+    }
+}
+
+#[cfg(feature = "half")]
+impl NpyType for f16 {
+    const KIND: char = 'f';
+    const SIZE: usize = 2;
+
+    fn read_bytes(bytes: &[u8], big_endian: bool) -> Self {
+        Self::from_bits(u16::read_bytes(bytes, big_endian))
+    }
+    fn write_bytes(self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.to_bits().to_le_bytes());
+    }
+}
+
+macro_rules! impl_npy_complex {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl NpyType for Complex<$ty> {
+                const KIND: char = 'c';
+                const SIZE: usize = 2*std::mem::size_of::<$ty>();
+
+                fn read_bytes(bytes: &[u8], big_endian: bool) -> Self {
+                    let (re, im) = bytes.split_at(Self::SIZE/2);
+                    Self::new(<$ty>::read_bytes(re, big_endian), <$ty>::read_bytes(im, big_endian))
+                }
+                fn write_bytes(self, bytes: &mut [u8]) {
+                    let (re, im) = bytes.split_at_mut(Self::SIZE/2);
+                    self.re.write_bytes(re);
+                    self.im.write_bytes(im);
+                }
+            }
+        )*
+    };
+}
+
+impl_npy_complex!(f32, f64);
+
+
+/// Parsed header of `.npy` file.
+struct Header {
+    descr: String,
+    fortran_order: bool,
+    shape: Vec<usize>,
+}
+
+impl Header {
+    /// Number of elements and number of data bytes for elements of specified size.
+    fn data_size(&self, size: usize) -> Result<(usize, usize), NpyError> {
+        let overflow = || NpyError::Header(format!("Size of array of shape {:?} overflows", self.shape));
+        let len = self.shape.iter().try_fold(1usize, |len, &n| len.checked_mul(n)).ok_or_else(overflow)?;
+        Ok((len, len.checked_mul(size).ok_or_else(overflow)?))
+    }
+}
+
+/// Minimal parser of Python literals that may occur in `.npy` header.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, pos: 0 }
+    }
+    fn error<T>(&self, msg: &str) -> Result<T, NpyError> {
+        Err(NpyError::Header(format!("{} at position {} in '{}'", msg, self.pos, self.text.trim_end())))
+    }
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+    fn expect(&mut self, token: &str) -> Result<(), NpyError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            self.error(&format!("Expected '{}'", token))
+        }
+    }
+    fn string(&mut self) -> Result<&'a str, NpyError> {
+        self.skip_whitespace();
+        let quote = match self.rest().chars().next() {
+            Some(c) if c == '\'' || c == '"' => c,
+            _ => return self.error("Expected string"),
+        };
+        self.pos += 1;
+        match self.rest().find(quote) {
+            Some(len) => {
+                let s = &self.rest()[..len];
+                self.pos += len + 1;
+                Ok(s)
+            }
+            None => self.error("Unterminated string"),
+        }
+    }
+    fn bool(&mut self) -> Result<bool, NpyError> {
+        if self.eat("True") {
+            Ok(true)
+        } else if self.eat("False") {
+            Ok(false)
+        } else {
+            self.error("Expected 'True' or 'False'")
+        }
+    }
+    fn integer(&mut self) -> Result<usize, NpyError> {
+        self.skip_whitespace();
+        let len = self.rest().find(|c: char| !c.is_ascii_digit()).unwrap_or_else(|| self.rest().len());
+        let digits = &self.rest()[..len];
+        match digits.parse::<usize>() {
+            Ok(value) => {
+                self.pos += len;
+                // Python 2 may write long integers with `L` suffix.
+                self.eat("L");
+                Ok(value)
+            }
+            Err(_) => self.error("Expected non-negative integer"),
+        }
+    }
+    fn tuple(&mut self) -> Result<Vec<usize>, NpyError> {
+        self.expect("(")?;
+        let mut items = Vec::new();
+        while !self.eat(")") {
+            items.push(self.integer()?);
+            if !self.eat(",") {
+                self.expect(")")?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn header(&mut self) -> Result<Header, NpyError> {
+        let (mut descr, mut fortran_order, mut shape) = (None, None, None);
+        self.expect("{")?;
+        while !self.eat("}") {
+            match self.string()? {
+                "descr" => {
+                    self.expect(":")?;
+                    descr = Some(self.string()?.to_string());
+                }
+                "fortran_order" => {
+                    self.expect(":")?;
+                    fortran_order = Some(self.bool()?);
+                }
+                "shape" => {
+                    self.expect(":")?;
+                    shape = Some(self.tuple()?);
+                }
+                key => return self.error(&format!("Unexpected key '{}'", key)),
+            }
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+        match (descr, fortran_order, shape) {
+            (Some(descr), Some(fortran_order), Some(shape)) => Ok(Header { descr, fortran_order, shape }),
+            (None, _, _) => self.error("Key 'descr' is missing"),
+            (_, None, _) => self.error("Key 'fortran_order' is missing"),
+            (_, _, None) => self.error("Key 'shape' is missing"),
+        }
+    }
+}
+
+/// Parses type description and checks that it matches `T`.
+/// Returns whether data is big-endian.
+fn check_descr<T: NpyType>(descr: &str) -> Result<bool, NpyError> {
+    let mismatch = || NpyError::Dtype { expected: T::descr(), found: descr.to_string() };
+    let (big_endian, rest) = match descr.chars().next() {
+        Some('<') | Some('|') => (false, &descr[1..]),
+        Some('>') => (true, &descr[1..]),
+        Some('=') => (cfg!(target_endian = "big"), &descr[1..]),
+        _ => (false, descr),
+    };
+    let mut chars = rest.chars();
+    let kind = chars.next().ok_or_else(mismatch)?;
+    let size = chars.as_str().parse::<usize>().map_err(|_| mismatch())?;
+    if kind == T::KIND && size == T::SIZE {
+        Ok(big_endian)
+    } else {
+        Err(mismatch())
+    }
+}
+
+/// Reads `.npy` preamble and header.
+fn read_header<R: Read>(reader: &mut R) -> Result<Header, NpyError> {
+    let mut magic = [0; 6];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(NpyError::Magic);
+    }
+    let mut version = [0; 2];
+    reader.read_exact(&mut version)?;
+    let header_len = match version {
+        [1, 0] => {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        [2, 0] | [3, 0] => {
+            let mut len = [0; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        [major, minor] => return Err(NpyError::Version(major, minor)),
+    };
+    let mut header = vec![0; header_len];
+    reader.read_exact(&mut header)?;
+    let text = match version[0] {
+        3 => String::from_utf8(header).map_err(|_| NpyError::Header("Header is not valid UTF-8".into()))?,
+        _ => header.into_iter().map(|b| b as char).collect(),
+    };
+    Parser::new(&text).header()
+}
+
+/// Writes `.npy` preamble and header.
+fn write_header<W: Write>(writer: &mut W, descr: &str, shape: &[usize]) -> Result<(), NpyError> {
+    let dims = shape.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    let mut text = format!(
+        "{{'descr': '{}', 'fortran_order': True, 'shape': ({}{}), }}",
+        descr, dims.join(", "), if dims.len() == 1 { "," } else { "" },
+    );
+    let (version, len_size) = if text.len() + 1 + MAGIC.len() + 4 <= u16::MAX as usize {
+        (1, 2)
+    } else {
+        (2, 4)
+    };
+    let preamble = MAGIC.len() + 2 + len_size;
+    let total = (preamble + text.len() + 1).div_ceil(ALIGN) * ALIGN;
+    text += &" ".repeat(total - preamble - text.len() - 1);
+    text += "\n";
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[version, 0])?;
+    if version == 1 {
+        writer.write_all(&(text.len() as u16).to_le_bytes())?;
+    } else {
+        writer.write_all(&(text.len() as u32).to_le_bytes())?;
+    }
+    writer.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+impl<T: NpyType> HostTensor<T> {
+    /// Reads tensor from NumPy `.npy` format.
+    ///
+    /// Tensor has the same shape as NumPy array and the same element at the same index, e.g. `a[i, j]`.
+    /// Because `Shape` treats axis 0 as the fastest-varying one, arrays in Fortran order are read as is,
+    /// while arrays in C order are transposed in memory.
+    pub fn read_npy<R: Read>(mut reader: R) -> Result<Self, NpyError> {
+        let header = read_header(&mut reader)?;
+        let big_endian = check_descr::<T>(&header.descr)?;
+
+        let (_, bytes) = header.data_size(T::SIZE)?;
+        let mut data = Vec::new();
+        reader.take(bytes as u64).read_to_end(&mut data)?;
+        if data.len() < bytes {
+            return Err(NpyError::Truncated { expected: bytes, found: data.len() });
+        }
+        let values = data.chunks_exact(T::SIZE).map(|b| T::read_bytes(b, big_endian)).collect::<Vec<_>>();
+
+        let shape = Shape::from(header.shape.clone());
+        if header.fortran_order {
+            Ok(Self::from_vec(&shape, values))
+        } else {
+            let dims = header.shape;
+            Ok(Self::from_fn(&shape, |pos| {
+                values[pos.iter().zip(dims.iter()).fold(0, |offset, (i, n)| offset*n + i)]
+            }))
+        }
+    }
+
+    /// Writes tensor in NumPy `.npy` format.
+    ///
+    /// Data is stored in Fortran order, so that NumPy array has the same shape as tensor
+    /// and the same element at the same index.
+    pub fn write_npy<W: Write>(&self, mut writer: W) -> Result<(), NpyError> {
+        write_header(&mut writer, &T::descr(), self.shape().as_slice())?;
+        let mut data = vec![0; self.shape().content() * T::SIZE];
+        for (b, &x) in data.chunks_exact_mut(T::SIZE).zip(self.iter()) {
+            x.write_bytes(b);
+        }
+        writer.write_all(&data)?;
+        Ok(())
+    }
+}
+
+impl<T: NpyType> HostTensor<T> {
+    /// Maps NumPy `.npy` file into memory without reading the data.
+    ///
+    /// Array must be stored in Fortran order and native byte order,
+    /// use [`HostTensor::read_npy`] for other files.
+    ///
+    /// Boolean data is checked to contain only `0` and `1` bytes, otherwise [`NpyError::Layout`] is returned.
+    ///
+    /// # Safety
+    ///
+    /// File must not be modified while it is mapped, see [`MappedBuffer::map`].
+    /// In particular, bytes of boolean array must remain `0` or `1`.
+    #[cfg(feature = "mmap")]
+    pub unsafe fn map_npy(file: &File, mode: MapMode) -> Result<Self, NpyError> {
+        let map = MappedBuffer::<u8>::map_file(file, MapMode::ReadOnly)?;
+        let (header, offset) = {
+            let mut bytes = map.as_slice();
+            let header = read_header(&mut bytes)?;
+            (header, map.as_slice().len() - bytes.len())
+        };
+        if check_descr::<T>(&header.descr)? != cfg!(target_endian = "big") {
+            return Err(NpyError::Layout(format!("byte order of '{}' is not native", header.descr)));
+        }
+        if !header.fortran_order && header.shape.iter().filter(|&&n| n != 1).count() > 1 {
+            return Err(NpyError::Layout("array is stored in C order".into()));
+        }
+        let (len, bytes) = header.data_size(T::SIZE)?;
+        let data = &map.as_slice()[offset..];
+        if data.len() < bytes {
+            return Err(NpyError::Truncated { expected: bytes, found: data.len() });
+        }
+        // Any other byte is not a valid `bool`.
+        if T::KIND == 'b' && data[..bytes].iter().any(|&b| b > 1) {
+            return Err(NpyError::Layout("boolean array contains bytes other than 0 and 1".into()));
+        }
+        let buffer = MappedBuffer::map(file, offset as u64, len, mode)?;
+        Ok(Self::from_mapped(buffer, &Shape::from(header.shape)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shape, values};
+
+    fn roundtrip<T: NpyType + std::fmt::Debug>(tensor: &HostTensor<T>) -> HostTensor<T> {
+        let mut bytes = Vec::new();
+        tensor.write_npy(&mut bytes).unwrap();
+        assert_eq!(bytes.len() % ALIGN, (tensor.shape().content() * T::SIZE) % ALIGN);
+        HostTensor::read_npy(bytes.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn write_read() {
+        let a = HostTensor::from_fn(&shape![2, 3, 4], |p| (p[0] + 10*p[1] + 100*p[2]) as i32);
+        let b = roundtrip(&a);
+        assert_eq!(b.shape(), a.shape());
+        assert!(a.iter().zip(b.iter()).all(|(x, y)| x == y));
+
+        let c = HostTensor::from_vec(&shape![3], vec![true, false, true]);
+        assert!(roundtrip(&c).iter().cloned().eq(c.iter().cloned()));
+
+        let d = HostTensor::from_vec(&shape![2], vec![Complex::new(1.0f64, -1.0), Complex::new(0.5, 2.0)]);
+        assert!(roundtrip(&d).iter().cloned().eq(d.iter().cloned()));
+
+        let e = HostTensor::from_vec(&shape![], vec![1.5f32]);
+        assert!(roundtrip(&e).iter().cloned().eq(e.iter().cloned()));
+    }
+
+    #[test]
+    fn header() {
+        let mut bytes = Vec::new();
+        HostTensor::<u16>::new_zeroed(&shape![3, 5]).write_npy(&mut bytes).unwrap();
+        let len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        assert_eq!((10 + len) % ALIGN, 0);
+        assert_eq!(
+            std::str::from_utf8(&bytes[10..(10 + len)]).unwrap().trim_end(),
+            "{'descr': '<u2', 'fortran_order': True, 'shape': (3, 5), }",
+        );
+    }
+
+    fn npy(header: &str, data: &[u8]) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn c_order() {
+        // numpy.arange(6, dtype='>i2').reshape(2, 3)
+        let data = (0..6i16).flat_map(|x| x.to_be_bytes().to_vec()).collect::<Vec<_>>();
+        let bytes = npy("{'descr': '>i2', 'fortran_order': False, 'shape': (2, 3), }\n", &data);
+        let a = HostTensor::<i16>::read_npy(bytes.as_slice()).unwrap();
+        assert_eq!(a.shape(), &shape![2, 3]);
+        // Element `a[i, j]` is equal to `3*i + j`.
+        assert_eq!(values(&a), [0, 3, 1, 4, 2, 5]);
+    }
+
+    #[test]
+    fn errors() {
+        let data = [0; 8];
+        match HostTensor::<f32>::read_npy(npy("{'descr': '<f8', 'fortran_order': True, 'shape': (1,), }", &data).as_slice()) {
+            Err(NpyError::Dtype { expected, found }) => assert_eq!((expected.as_str(), found.as_str()), ("<f4", "<f8")),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        match HostTensor::<f32>::read_npy(npy("{'descr': '<f4', 'shape': (1,), }", &data).as_slice()) {
+            Err(NpyError::Header(msg)) => assert!(msg.contains("fortran_order")),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        match HostTensor::<f32>::read_npy(npy("{'descr': '<f4', 'fortran_order': True, 'shape': (3,), }", &data).as_slice()) {
+            Err(NpyError::Truncated { expected: 12, found: 8 }) => (),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        let huge = format!("{{'descr': '<f4', 'fortran_order': True, 'shape': ({}, {}), }}", usize::MAX / 2, 3);
+        match HostTensor::<f32>::read_npy(npy(&huge, &data).as_slice()) {
+            Err(NpyError::Header(msg)) => assert!(msg.contains("overflows")),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        let huge = format!("{{'descr': '<f4', 'fortran_order': True, 'shape': ({},), }}", usize::MAX / 2);
+        match HostTensor::<f32>::read_npy(npy(&huge, &data).as_slice()) {
+            Err(NpyError::Header(msg)) => assert!(msg.contains("overflows")),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        match HostTensor::<f32>::read_npy(&b"NUMPY"[..]) {
+            Err(NpyError::Io(_)) => (),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        match HostTensor::<f32>::read_npy(&b"\x93NUMPX\x01\x00"[..]) {
+            Err(NpyError::Magic) => (),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn map() {
+        use std::fs;
+        let path = std::env::temp_dir().join(format!("tension-{}-map.npy", std::process::id()));
+        let a = HostTensor::from_fn(&shape![3, 4], |p| (p[0] + 10*p[1]) as f64);
+        a.write_npy(File::create(&path).unwrap()).unwrap();
+
+        let mut b = unsafe { HostTensor::<f64>::map_npy(&File::open(&path).unwrap(), MapMode::CopyOnWrite).unwrap() };
+        assert_eq!(b.shape(), a.shape());
+        assert!(b.buffer().is_mapped());
+        assert!(b.iter().eq(a.iter()));
+        b.iter_mut().for_each(|x| *x = -*x);
+        assert!(HostTensor::<f64>::read_npy(File::open(&path).unwrap()).unwrap().iter().eq(a.iter()));
+
+        match unsafe { HostTensor::<f32>::map_npy(&File::open(&path).unwrap(), MapMode::ReadOnly) } {
+            Err(NpyError::Dtype { .. }) => (),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        // Header is padded so that data is aligned.
+        let padded = |header: &str| format!("{:<1$}\n", header, 2*ALIGN - 11);
+        fs::write(&path, npy(&padded("{'descr': '<f8', 'fortran_order': False, 'shape': (2, 1, 1), }"), &[0; 16])).unwrap();
+        assert_eq!(unsafe { HostTensor::<f64>::map_npy(&File::open(&path).unwrap(), MapMode::ReadOnly) }.unwrap().shape(), &shape![2]);
+        fs::write(&path, npy(&padded("{'descr': '<f8', 'fortran_order': False, 'shape': (2, 1, 3), }"), &[0; 48])).unwrap();
+        match unsafe { HostTensor::<f64>::map_npy(&File::open(&path).unwrap(), MapMode::ReadOnly) } {
+            Err(NpyError::Layout(_)) => (),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        fs::write(&path, npy(&padded("{'descr': '|b1', 'fortran_order': True, 'shape': (3,), }"), &[1, 0, 1])).unwrap();
+        let c = unsafe { HostTensor::<bool>::map_npy(&File::open(&path).unwrap(), MapMode::ReadOnly) }.unwrap();
+        assert_eq!(values(&c), [true, false, true]);
+        fs::write(&path, npy(&padded("{'descr': '|b1', 'fortran_order': True, 'shape': (3,), }"), &[1, 2, 0])).unwrap();
+        match unsafe { HostTensor::<bool>::map_npy(&File::open(&path).unwrap(), MapMode::ReadOnly) } {
+            Err(NpyError::Layout(_)) => (),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        let huge = format!("{{'descr': '<f8', 'fortran_order': True, 'shape': ({},), }}", usize::MAX / 4);
+        fs::write(&path, npy(&padded(&huge), &[0; 8])).unwrap();
+        match unsafe { HostTensor::<f64>::map_npy(&File::open(&path).unwrap(), MapMode::ReadOnly) } {
+            Err(NpyError::Header(_)) => (),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    fmt,
+    error::Error,
+    io::{self, Read, Write, Seek},
+};
+use zip::{
+    ZipArchive, ZipWriter, CompressionMethod,
+    write::FileOptions,
+    result::ZipError,
+};
+use crate::{Tensor, HostTensor, NpyType, NpyError};
+
+
+/// Error of reading or writing NumPy `.npz` archive.
+#[derive(Debug)]
+pub enum NpzError {
+    /// Error of underlying ZIP archive.
+    Zip(ZipError),
+    /// Error of reading or writing array with specified name.
+    Npy(String, NpyError),
+    /// Array with specified name is not found in archive.
+    Missing(String),
+}
+
+impl fmt::Display for NpzError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NpzError::Zip(err) => write!(f, "ZIP archive error: {}", err),
+            NpzError::Npy(name, err) => write!(f, "Array '{}': {}", name, err),
+            NpzError::Missing(name) => write!(f, "Array '{}' is not found in archive", name),
+        }
+    }
+}
+
+impl Error for NpzError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NpzError::Zip(err) => Some(err),
+            NpzError::Npy(_, err) => Some(err),
+            NpzError::Missing(_) => None,
+        }
+    }
+}
+
+impl From<ZipError> for NpzError {
+    fn from(err: ZipError) -> Self {
+        NpzError::Zip(err)
+    }
+}
+
+impl From<io::Error> for NpzError {
+    fn from(err: io::Error) -> Self {
+        NpzError::Zip(ZipError::Io(err))
+    }
+}
+
+/// Reader of NumPy `.npz` archive (produced by `numpy.savez` or `numpy.savez_compressed`).
+pub struct NpzReader<R: Read + Seek> {
+    archive: ZipArchive<R>,
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+    /// Opens archive.
+    pub fn new(reader: R) -> Result<Self, NpzError> {
+        Ok(Self { archive: ZipArchive::new(reader)? })
+    }
+
+    /// Names of arrays stored in archive.
+    pub fn names(&self) -> Vec<String> {
+        self.archive.file_names()
+        .map(|name| name.strip_suffix(".npy").unwrap_or(name).to_string())
+        .collect()
+    }
+
+    /// Reads array with specified name, see [`HostTensor::read_npy`].
+    pub fn read<T: NpyType>(&mut self, name: &str) -> Result<HostTensor<T>, NpzError> {
+        let mut file_name = format!("{}.npy", name);
+        if !self.archive.file_names().any(|n| n == file_name) {
+            file_name = name.to_string();
+        }
+        match self.archive.by_name(&file_name) {
+            Ok(file) => HostTensor::read_npy(file).map_err(|err| NpzError::Npy(name.to_string(), err)),
+            Err(ZipError::FileNotFound) => Err(NpzError::Missing(name.to_string())),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Writer of NumPy `.npz` archive.
+pub struct NpzWriter<W: Write + Seek> {
+    zip: ZipWriter<W>,
+    options: FileOptions,
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+    /// Creates archive without compression (like `numpy.savez`).
+    pub fn new(writer: W) -> Self {
+        Self {
+            zip: ZipWriter::new(writer),
+            options: FileOptions::default().compression_method(CompressionMethod::Stored),
+        }
+    }
+    /// Creates compressed archive (like `numpy.savez_compressed`).
+    pub fn new_compressed(writer: W) -> Self {
+        Self {
+            zip: ZipWriter::new(writer),
+            options: FileOptions::default().compression_method(CompressionMethod::Deflated),
+        }
+    }
+
+    /// Adds array with specified name to archive, see [`HostTensor::write_npy`].
+    pub fn add<T: NpyType>(&mut self, name: &str, tensor: &HostTensor<T>) -> Result<(), NpzError> {
+        let large = (tensor.shape().content() * T::SIZE) as u64 >= u32::MAX as u64;
+        self.zip.start_file(format!("{}.npy", name), self.options.large_file(large))?;
+        tensor.write_npy(&mut self.zip).map_err(|err| NpzError::Npy(name.to_string(), err))
+    }
+
+    /// Writes central directory and returns underlying writer.
+    pub fn finish(mut self) -> Result<W, NpzError> {
+        Ok(self.zip.finish()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::shape;
+
+    #[test]
+    fn write_read() {
+        let a = HostTensor::from_fn(&shape![3, 2], |p| (p[0] + 3*p[1]) as f32);
+        let b = HostTensor::from_vec(&shape![4], vec![true, false, false, true]);
+
+        for &compressed in [false, true].iter() {
+            let cursor = Cursor::new(Vec::new());
+            let mut writer = if compressed { NpzWriter::new_compressed(cursor) } else { NpzWriter::new(cursor) };
+            writer.add("a", &a).unwrap();
+            writer.add("b", &b).unwrap();
+            let bytes = writer.finish().unwrap().into_inner();
+
+            let mut reader = NpzReader::new(Cursor::new(bytes)).unwrap();
+            let mut names = reader.names();
+            names.sort();
+            assert_eq!(names, ["a", "b"]);
+
+            let ra = reader.read::<f32>("a").unwrap();
+            assert_eq!(ra.shape(), a.shape());
+            assert!(ra.iter().eq(a.iter()));
+            assert!(reader.read::<bool>("b").unwrap().iter().eq(b.iter()));
+
+            match reader.read::<f32>("c") {
+                Err(NpzError::Missing(name)) => assert_eq!(name, "c"),
+                other => panic!("{:?}", other.map(|_| ())),
+            }
+            match reader.read::<f64>("a") {
+                Err(NpzError::Npy(name, NpyError::Dtype { .. })) => assert_eq!(name, "a"),
+                other => panic!("{:?}", other.map(|_| ())),
+            }
+        }
+    }
+}
@@ -0,0 +1,592 @@
+use std::{
+    fmt,
+    error::Error,
+    io::{self, Read, Write},
+    collections::BTreeMap,
+    mem,
+};
+#[cfg(feature = "mmap")]
+use std::fs::File;
+use serde_json::{Value, Map};
+#[cfg(feature = "half")]
+use half::{f16, bf16};
+use crate::{Prm, Shape, Tensor, HostBuffer, HostTensor};
+#[cfg(feature = "mmap")]
+use crate::{MappedBuffer, MapMode};
+#[cfg(feature = "device")]
+use crate::{Interop, DeviceContext, DeviceTensor};
+
+
+/// Maximum size of the header in bytes.
+const MAX_HEADER: usize = 100_000_000;
+/// Total length of the header size and the header is aligned to this value.
+const ALIGN: usize = 8;
+/// Header key of the metadata map.
+const METADATA: &str = "__metadata__";
+
+/// Error of reading or writing safetensors file.
+#[derive(Debug)]
+pub enum SafetensorsError {
+    /// Underlying I/O error.
+    Io(io::Error),
+    /// Header is malformed, the message describes the problem.
+    Header(String),
+    /// Element type stored in file does not match the tensor element type.
+    Dtype {
+        /// Type name of the tensor element, e.g. `F32`.
+        expected: String,
+        /// Type name from the file header.
+        found: String,
+    },
+    /// Tensor with specified name is not found in file.
+    Missing(String),
+}
+
+impl fmt::Display for SafetensorsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SafetensorsError::Io(err) => write!(f, "I/O error: {}", err),
+            SafetensorsError::Header(msg) => write!(f, "Malformed safetensors header: {}", msg),
+            SafetensorsError::Dtype { expected, found } => write!(f, "Element type mismatch: expected '{}', found '{}'", expected, found),
+            SafetensorsError::Missing(name) => write!(f, "Tensor '{}' is not found in file", name),
+        }
+    }
+}
+
+impl Error for SafetensorsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SafetensorsError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SafetensorsError {
+    fn from(err: io::Error) -> Self {
+        SafetensorsError::Io(err)
+    }
+}
+
+/// Type that can be stored in safetensors file.
+///
+/// `usize`, `isize` and complex types are not supported because the format has no such types.
+pub trait SafetensorsType: Prm + 'static {
+    /// Type name used in header, e.g. `F32`.
+    const DTYPE: &'static str;
+    /// Whether value in memory is represented by the same bytes as in file,
+    /// so that stored data can be used as is without copying.
+    const PLAIN: bool;
+
+    /// Read value from little-endian bytes.
+    fn read_bytes(bytes: &[u8]) -> Self;
+    /// Write value as little-endian bytes.
+    fn write_bytes(self, bytes: &mut [u8]);
+}
+
+macro_rules! impl_safetensors_prim {
+    ($($ty:ty: $dtype:expr),* $(,)?) => {
+        $(
+            impl SafetensorsType for $ty {
+                const DTYPE: &'static str = $dtype;
+                const PLAIN: bool = cfg!(target_endian = "little");
+
+                fn read_bytes(bytes: &[u8]) -> Self {
+                    let mut buf = [0; mem::size_of::<$ty>()];
+                    buf.copy_from_slice(bytes);
+                    <$ty>::from_le_bytes(buf)
+                }
+                fn write_bytes(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&<$ty>::to_le_bytes(self));
+                }
+            }
+        )*
+    };
+}
+
+impl_safetensors_prim!(
+    u8: "U8", u16: "U16", u32: "U32", u64: "U64",
+    i8: "I8", i16: "I16", i32: "I32", i64: "I64",
+    f32: "F32", f64: "F64",
+);
+
+/// Boolean values are validated on reading, so they are always copied.
+impl SafetensorsType for bool {
+    const DTYPE: &'static str = "BOOL";
+    const PLAIN: bool = false;
+
+    fn read_bytes(bytes: &[u8]) -> Self {
+        bytes[0] != 0
+    }
+    fn write_bytes(self, bytes: &mut [u8]) {
+        bytes[0] = self as u8;
+    }
+}
+
+#[cfg(feature = "half")]
+macro_rules! impl_safetensors_half {
+    ($($ty:ty: $dtype:expr),* $(,)?) => {
+        $(
+            impl SafetensorsType for $ty {
+                const DTYPE: &'static str = $dtype;
+                const PLAIN: bool = cfg!(target_endian = "little");
+
+                fn read_bytes(bytes: &[u8]) -> Self {
+                    Self::from_bits(u16::read_bytes(bytes))
+                }
+                fn write_bytes(self, bytes: &mut [u8]) {
+                    self.to_bits().write_bytes(bytes);
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "half")]
+impl_safetensors_half!(f16: "F16", bf16: "BF16");
+
+/// Size in bytes of the element of specified type, `None` if the type is unknown.
+fn dtype_size(dtype: &str) -> Option<usize> {
+    match dtype {
+        "BOOL" | "U8" | "I8" | "F8_E5M2" | "F8_E4M3" => Some(1),
+        "U16" | "I16" | "F16" | "BF16" => Some(2),
+        "U32" | "I32" | "F32" => Some(4),
+        "U64" | "I64" | "F64" => Some(8),
+        _ => None,
+    }
+}
+
+/// Values of type `T` stored in bytes without copying, if possible.
+fn plain_values<T: SafetensorsType>(bytes: &[u8]) -> Option<&[T]> {
+    if T::PLAIN && bytes.as_ptr() as usize % mem::align_of::<T>() == 0 {
+        Some(unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const T, bytes.len() / mem::size_of::<T>()) })
+    } else {
+        None
+    }
+}
+
+/// Values of type `T` decoded from bytes.
+fn read_values<T: SafetensorsType>(bytes: &[u8]) -> Vec<T> {
+    bytes.chunks_exact(mem::size_of::<T>()).map(T::read_bytes).collect()
+}
+
+/// Description of the tensor stored in file.
+struct Entry {
+    dtype: String,
+    shape: Shape,
+    /// Range of the tensor data in bytes relative to the beginning of the data section.
+    begin: usize,
+    end: usize,
+}
+
+fn header_error<T>(msg: String) -> Result<T, SafetensorsError> {
+    Err(SafetensorsError::Header(msg))
+}
+
+fn parse_entry(name: &str, value: &Value, data_len: usize) -> Result<Entry, SafetensorsError> {
+    let object = match value.as_object() {
+        Some(object) => object,
+        None => return header_error(format!("tensor '{}' description is not an object", name)),
+    };
+    let dtype = match object.get("dtype").and_then(Value::as_str) {
+        Some(dtype) => dtype.to_string(),
+        None => return header_error(format!("tensor '{}' has no 'dtype'", name)),
+    };
+    let numbers = |key: &str| -> Result<Vec<usize>, SafetensorsError> {
+        match object.get(key).and_then(Value::as_array) {
+            Some(array) => array.iter().map(|x| match x.as_u64() {
+                Some(x) if x <= usize::MAX as u64 => Ok(x as usize),
+                _ => header_error(format!("tensor '{}' has invalid '{}'", name, key)),
+            }).collect(),
+            None => header_error(format!("tensor '{}' has no '{}'", name, key)),
+        }
+    };
+    // Safetensors stores data in C order, so reversed axes give the same memory layout.
+    let dims = numbers("shape")?;
+    let shape = Shape::from(dims.iter().rev().cloned().collect::<Vec<_>>());
+    let (begin, end) = match numbers("data_offsets")?.as_slice() {
+        &[begin, end] if begin <= end && end <= data_len => (begin, end),
+        _ => return header_error(format!("tensor '{}' has invalid 'data_offsets'", name)),
+    };
+    if let Some(size) = dtype_size(&dtype) {
+        let bytes = dims.iter().try_fold(size, |bytes, &n| bytes.checked_mul(n));
+        if bytes != Some(end - begin) {
+            return header_error(format!("tensor '{}' data size does not match its shape", name));
+        }
+    }
+    Ok(Entry { dtype, shape, begin, end })
+}
+
+/// Tensor descriptions and metadata parsed from header.
+type Header = (BTreeMap<String, Entry>, BTreeMap<String, String>);
+
+/// Data section of the file.
+enum Data {
+    Vec(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(MappedBuffer<u8>, usize),
+}
+
+impl Data {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Data::Vec(vec) => vec.as_slice(),
+            #[cfg(feature = "mmap")]
+            Data::Mapped(buffer, offset) => &buffer.as_slice()[*offset..],
+        }
+    }
+}
+
+/// Collection of named tensors read from [safetensors](https://github.com/huggingface/safetensors) file.
+///
+/// Safetensors stores data in C order (the last axis is the fastest-varying one),
+/// while `Shape` treats axis 0 as the fastest-varying one.
+/// So tensors are read with reversed shape, e.g. safetensors tensor of shape `[a, b, c]`
+/// becomes tensor of shape `(c, b, a)`, that allows to use the stored data as is.
+/// [`SafetensorsWriter`] reverses shapes back.
+pub struct Safetensors {
+    entries: BTreeMap<String, Entry>,
+    metadata: BTreeMap<String, String>,
+    data: Data,
+}
+
+impl Safetensors {
+    fn parse_header(bytes: &[u8], data_len: usize) -> Result<Header, SafetensorsError> {
+        let header = match serde_json::from_slice::<Value>(bytes) {
+            Ok(Value::Object(header)) => header,
+            Ok(_) => return header_error("header is not an object".to_string()),
+            Err(err) => return header_error(err.to_string()),
+        };
+        let mut entries = BTreeMap::new();
+        let mut metadata = BTreeMap::new();
+        for (name, value) in header.iter() {
+            if name == METADATA {
+                for (key, value) in value.as_object().into_iter().flatten() {
+                    match value.as_str() {
+                        Some(value) => metadata.insert(key.clone(), value.to_string()),
+                        None => return header_error(format!("metadata '{}' is not a string", key)),
+                    };
+                }
+            } else {
+                entries.insert(name.clone(), parse_entry(name, value, data_len)?);
+            }
+        }
+        Ok((entries, metadata))
+    }
+
+    fn header_len(prefix: [u8; 8]) -> Result<usize, SafetensorsError> {
+        let len = u64::from_le_bytes(prefix);
+        if len > MAX_HEADER as u64 {
+            return header_error(format!("header size {} is too large", len));
+        }
+        Ok(len as usize)
+    }
+
+    /// Reads whole file contents into memory.
+    pub fn read<R: Read>(mut reader: R) -> Result<Self, SafetensorsError> {
+        let mut prefix = [0; 8];
+        reader.read_exact(&mut prefix)?;
+        let mut header = vec![0; Self::header_len(prefix)?];
+        reader.read_exact(&mut header)?;
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        let (entries, metadata) = Self::parse_header(&header, data.len())?;
+        Ok(Self { entries, metadata, data: Data::Vec(data) })
+    }
+
+    /// Maps file into memory.
+    /// Tensors obtained from this collection refer to the mapped memory without copying
+    /// as long as they are not modified and their data is properly aligned.
+    ///
+    /// # Safety
+    ///
+    /// File must not be modified while it is mapped, see [`MappedBuffer::map`].
+    #[cfg(feature = "mmap")]
+    pub unsafe fn map(file: &File) -> Result<Self, SafetensorsError> {
+        let buffer = MappedBuffer::<u8>::map_file(file, MapMode::ReadOnly)?;
+        let map = buffer.as_slice();
+        let mut prefix = [0; 8];
+        match map.get(..8) {
+            Some(bytes) => prefix.copy_from_slice(bytes),
+            None => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+        }
+        let offset = 8 + Self::header_len(prefix)?;
+        if map.len() < offset {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        let (entries, metadata) = Self::parse_header(&map[8..offset], map.len() - offset)?;
+        Ok(Self { entries, metadata, data: Data::Mapped(buffer, offset) })
+    }
+
+    /// Names of tensors in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item=&str> {
+        self.entries.keys().map(String::as_str)
+    }
+    /// Type name of the tensor elements, e.g. `F32`.
+    pub fn dtype(&self, name: &str) -> Option<&str> {
+        self.entries.get(name).map(|entry| entry.dtype.as_str())
+    }
+    /// Shape of the tensor (reversed in comparison to the one stored in file).
+    pub fn shape(&self, name: &str) -> Option<&Shape> {
+        self.entries.get(name).map(|entry| &entry.shape)
+    }
+    /// Free-form string metadata stored in file.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    fn entry<T: SafetensorsType>(&self, name: &str) -> Result<(&Entry, &[u8]), SafetensorsError> {
+        let entry = match self.entries.get(name) {
+            Some(entry) => entry,
+            None => return Err(SafetensorsError::Missing(name.to_string())),
+        };
+        if entry.dtype != T::DTYPE {
+            return Err(SafetensorsError::Dtype { expected: T::DTYPE.to_string(), found: entry.dtype.clone() });
+        }
+        Ok((entry, &self.data.as_slice()[entry.begin..entry.end]))
+    }
+
+    /// Get tensor with specified name.
+    pub fn get<T: SafetensorsType>(&self, name: &str) -> Result<HostTensor<T>, SafetensorsError> {
+        let (entry, bytes) = self.entry::<T>(name)?;
+        #[cfg(feature = "mmap")]
+        if let (Data::Mapped(buffer, offset), Some(values)) = (&self.data, plain_values::<T>(bytes)) {
+            let buffer = unsafe { buffer.view::<T>(offset + entry.begin, values.len()) };
+            return Ok(HostTensor::from_buffer(HostBuffer::from(buffer), &entry.shape));
+        }
+        let values = match plain_values::<T>(bytes) {
+            Some(values) => values.to_vec(),
+            None => read_values(bytes),
+        };
+        Ok(HostTensor::from_vec(&entry.shape, values))
+    }
+    /// Get all tensors, they must have the same element type.
+    pub fn load<T: SafetensorsType>(&self) -> Result<BTreeMap<String, HostTensor<T>>, SafetensorsError> {
+        self.names().map(|name| Ok((name.to_string(), self.get(name)?))).collect()
+    }
+
+    /// Get tensor with specified name and upload it to device directly from stored data.
+    #[cfg(feature = "device")]
+    pub fn get_in<T: SafetensorsType + Interop>(&self, name: &str, context: &DeviceContext) -> Result<DeviceTensor<T>, SafetensorsError> {
+        let (entry, bytes) = self.entry::<T>(name)?;
+        let mut tensor = unsafe { DeviceTensor::new_uninit_in(context, &entry.shape) };
+        match plain_values::<T>(bytes) {
+            Some(values) => tensor.store(values),
+            None => tensor.store(&read_values::<T>(bytes)),
+        }
+        Ok(tensor)
+    }
+    /// Get all tensors and upload them to device, they must have the same element type.
+    #[cfg(feature = "device")]
+    pub fn load_into<T: SafetensorsType + Interop>(&self, context: &DeviceContext) -> Result<BTreeMap<String, DeviceTensor<T>>, SafetensorsError> {
+        self.names().map(|name| Ok((name.to_string(), self.get_in(name, context)?))).collect()
+    }
+}
+
+/// Tensor to be written, with erased element type.
+trait Source {
+    fn dtype(&self) -> &'static str;
+    fn dims(&self) -> &Shape;
+    fn item_size(&self) -> usize;
+    fn write_to(&self, writer: &mut dyn Write) -> io::Result<()>;
+}
+
+impl<T: SafetensorsType> Source for HostTensor<T> {
+    fn dtype(&self) -> &'static str {
+        T::DTYPE
+    }
+    fn dims(&self) -> &Shape {
+        self.shape()
+    }
+    fn item_size(&self) -> usize {
+        mem::size_of::<T>()
+    }
+    fn write_to(&self, writer: &mut dyn Write) -> io::Result<()> {
+        let values = self.buffer().as_slice();
+        if T::PLAIN {
+            let bytes = unsafe { std::slice::from_raw_parts(values.as_ptr() as *const u8, mem::size_of_val(values)) };
+            writer.write_all(bytes)
+        } else {
+            let mut bytes = vec![0; mem::size_of_val(values)];
+            for (b, &x) in bytes.chunks_exact_mut(mem::size_of::<T>()).zip(values.iter()) {
+                x.write_bytes(b);
+            }
+            writer.write_all(&bytes)
+        }
+    }
+}
+
+/// Writer of named tensors to [safetensors](https://github.com/huggingface/safetensors) file.
+///
+/// Tensor shapes are reversed, see [`Safetensors`] for details.
+/// Tensors are shared with the writer until it is dropped, so they are not copied.
+#[derive(Default)]
+pub struct SafetensorsWriter {
+    tensors: BTreeMap<String, Box<dyn Source>>,
+    metadata: BTreeMap<String, String>,
+}
+
+impl SafetensorsWriter {
+    /// Creates empty writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds tensor with specified name, replaces previously added tensor with the same name.
+    pub fn add<T: SafetensorsType>(&mut self, name: &str, tensor: &HostTensor<T>) {
+        assert_ne!(name, METADATA, "Tensor name is reserved for metadata");
+        self.tensors.insert(name.to_string(), Box::new(tensor.reshape(tensor.shape())));
+    }
+    /// Adds free-form string metadata.
+    pub fn add_metadata(&mut self, key: &str, value: &str) {
+        self.metadata.insert(key.to_string(), value.to_string());
+    }
+
+    /// Writes all tensors.
+    ///
+    /// Tensors are stored in order of decreasing element size,
+    /// so that data of every tensor is aligned to its element size.
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), SafetensorsError> {
+        let mut tensors = self.tensors.iter().collect::<Vec<_>>();
+        tensors.sort_by_key(|(_, tensor)| std::cmp::Reverse(tensor.item_size()));
+
+        let mut header = Map::new();
+        if !self.metadata.is_empty() {
+            let metadata = self.metadata.iter().map(|(k, v)| (k.clone(), Value::from(v.as_str()))).collect();
+            header.insert(METADATA.to_string(), Value::Object(metadata));
+        }
+        let mut offset = 0;
+        for (name, tensor) in tensors.iter() {
+            let size = tensor.dims().content() * tensor.item_size();
+            let mut entry = Map::new();
+            entry.insert("dtype".to_string(), Value::from(tensor.dtype()));
+            entry.insert("shape".to_string(), Value::from(tensor.dims().iter().rev().cloned().collect::<Vec<_>>()));
+            entry.insert("data_offsets".to_string(), Value::from(vec![offset, offset + size]));
+            header.insert(name.to_string(), Value::Object(entry));
+            offset += size;
+        }
+
+        let mut text = Value::Object(header).to_string();
+        let len = (8 + text.len()).div_ceil(ALIGN) * ALIGN - 8;
+        text.extend(std::iter::repeat(' ').take(len - text.len()));
+        writer.write_all(&(len as u64).to_le_bytes())?;
+        writer.write_all(text.as_bytes())?;
+        for (_, tensor) in tensors {
+            tensor.write_to(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shape, values};
+
+    fn file(header: &str, data: &[u8]) -> Vec<u8> {
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn write_read() {
+        let a = HostTensor::from_fn(&shape![3, 2], |p| (p[0] + 3*p[1]) as f32);
+        let b = HostTensor::from_vec(&shape![5], vec![1u8, 2, 3, 4, 5]);
+        let c = HostTensor::from_vec(&shape![2, 2], vec![true, false, false, true]);
+        let d = HostTensor::from_vec(&shape![], vec![-1i64]);
+
+        let mut writer = SafetensorsWriter::new();
+        writer.add("b", &b);
+        writer.add("a", &a);
+        writer.add("c", &c);
+        writer.add("d", &d);
+        writer.add_metadata("format", "pt");
+        let mut bytes = Vec::new();
+        writer.write(&mut bytes).unwrap();
+        let mut prefix = [0; 8];
+        prefix.copy_from_slice(&bytes[..8]);
+        let len = u64::from_le_bytes(prefix) as usize;
+        assert_eq!((8 + len) % ALIGN, 0);
+
+        let file = Safetensors::read(bytes.as_slice()).unwrap();
+        assert_eq!(file.names().collect::<Vec<_>>(), ["a", "b", "c", "d"]);
+        assert_eq!(file.metadata().get("format").unwrap(), "pt");
+        assert_eq!(file.dtype("a"), Some("F32"));
+        assert_eq!(file.shape("a"), Some(&shape![3, 2]));
+        assert!(file.get::<f32>("a").unwrap().iter().eq(a.iter()));
+        assert!(file.get::<u8>("b").unwrap().iter().eq(b.iter()));
+        assert!(file.get::<bool>("c").unwrap().iter().eq(c.iter()));
+        assert!(file.get::<i64>("d").unwrap().iter().eq(d.iter()));
+    }
+
+    #[test]
+    fn c_order() {
+        // torch.arange(6, dtype=torch.int16).reshape(2, 3)
+        let data = (0..6i16).flat_map(|x| x.to_le_bytes().to_vec()).collect::<Vec<_>>();
+        let bytes = file(r#"{"x":{"dtype":"I16","shape":[2,3],"data_offsets":[0,12]}}"#, &data);
+        let file = Safetensors::read(bytes.as_slice()).unwrap();
+        let x = file.get::<i16>("x").unwrap();
+        // Element `x[i, j]` is at position `(j, i)`.
+        assert_eq!(x.shape(), &shape![3, 2]);
+        assert_eq!(values(&x), [0, 1, 2, 3, 4, 5]);
+
+        let mut writer = SafetensorsWriter::new();
+        writer.add("x", &x);
+        let mut written = Vec::new();
+        writer.write(&mut written).unwrap();
+        assert!(written.ends_with(&data));
+        assert!(std::str::from_utf8(&written[8..]).unwrap().contains(r#""shape":[2,3]"#));
+    }
+
+    #[test]
+    fn map() {
+        let a = HostTensor::from_fn(&shape![4, 3], |p| (p[0] * p[1]) as f64);
+        let mut writer = SafetensorsWriter::new();
+        writer.add("a", &a);
+        writer.add("b", &HostTensor::<u8>::new_ones(&shape![3]));
+        let path = std::env::temp_dir().join(format!("tension-{}.safetensors", std::process::id()));
+        writer.write(File::create(&path).unwrap()).unwrap();
+
+        let file = unsafe { Safetensors::map(&File::open(&path).unwrap()).unwrap() };
+        let mut b = file.get::<f64>("a").unwrap();
+        assert!(b.buffer().is_mapped());
+        assert!(b.iter().eq(a.iter()));
+        b.iter_mut().for_each(|x| *x += 1.0);
+        assert!(!b.buffer().is_mapped());
+        assert!(file.get::<f64>("a").unwrap().iter().eq(a.iter()));
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn errors() {
+        let bytes = file(r#"{"x":{"dtype":"F32","shape":[2],"data_offsets":[0,8]}}"#, &[0; 8]);
+        let st = Safetensors::read(bytes.as_slice()).unwrap();
+        match st.get::<f64>("x") {
+            Err(SafetensorsError::Dtype { expected, found }) => assert_eq!((expected.as_str(), found.as_str()), ("F64", "F32")),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        match st.get::<f32>("y") {
+            Err(SafetensorsError::Missing(name)) => assert_eq!(name, "y"),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        let bytes = file(r#"{"x":{"dtype":"F32","shape":[3],"data_offsets":[0,8]}}"#, &[0; 8]);
+        match Safetensors::read(bytes.as_slice()) {
+            Err(SafetensorsError::Header(msg)) => assert!(msg.contains("size")),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        // Product of sizes wraps around to 8 bytes.
+        let bytes = file(r#"{"x":{"dtype":"F32","shape":[2305843009213693953,2],"data_offsets":[0,8]}}"#, &[0; 8]);
+        match Safetensors::read(bytes.as_slice()) {
+            Err(SafetensorsError::Header(msg)) => assert!(msg.contains("size")),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        let bytes = file(r#"{"x":{"dtype":"F32","shape":[4],"data_offsets":[0,16]}}"#, &[0; 8]);
+        match Safetensors::read(bytes.as_slice()) {
+            Err(SafetensorsError::Header(msg)) => assert!(msg.contains("data_offsets")),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+#[cfg(feature = "device")]
+use std::marker::PhantomData;
+use serde::{
+    Serialize, Serializer, Deserialize, Deserializer,
+};
+#[cfg(feature = "device")]
+use serde::de::{DeserializeSeed, Error as _};
+use crate::{Prm, Shape, Tensor, HostTensor};
+#[cfg(feature = "device")]
+use crate::{Interop, DeviceContext, DeviceTensor};
+
+
+/// Shape is serialized as a sequence of dimensions without trailing `1`s,
+/// so that equal shapes have the same representation.
+impl Serialize for Shape {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for Shape {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let shape = Shape::from(Vec::<usize>::deserialize(deserializer)?);
+        Ok(Shape::from(shape.as_slice()))
+    }
+}
+
+/// Serialized representation of tensor.
+#[derive(Serialize)]
+#[serde(rename = "Tensor")]
+struct TensorRef<'a, T: Serialize> {
+    shape: &'a Shape,
+    /// Flattened elements.
+    data: &'a [T],
+}
+
+/// Deserialized representation of tensor.
+#[derive(Deserialize)]
+#[serde(rename = "Tensor")]
+struct TensorData<T> {
+    shape: Shape,
+    data: Vec<T>,
+}
+
+impl<T> TensorData<T> {
+    fn check<E: serde::de::Error>(&self) -> Result<(), E> {
+        let len = self.shape.iter().try_fold(1usize, |len, &n| len.checked_mul(n)).ok_or_else(|| E::custom(format_args!(
+            "number of elements of tensor of shape {:?} overflows",
+            self.shape.as_slice(),
+        )))?;
+        if self.data.len() == len {
+            Ok(())
+        } else {
+            Err(E::custom(format_args!(
+                "tensor of shape {:?} must have {} elements, got {}",
+                self.shape.as_slice(), len, self.data.len(),
+            )))
+        }
+    }
+}
+
+/// Tensor is serialized as a struct of its shape and flattened elements.
+impl<T: Prm + Serialize> Serialize for HostTensor<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TensorRef { shape: self.shape(), data: self.buffer().as_slice() }.serialize(serializer)
+    }
+}
+
+impl<'de, T: Prm + Deserialize<'de>> Deserialize<'de> for HostTensor<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tensor = TensorData::<T>::deserialize(deserializer)?;
+        tensor.check()?;
+        Ok(HostTensor::from_vec(&tensor.shape, tensor.data))
+    }
+}
+
+/// Tensor is downloaded from device and serialized the same way as [`HostTensor`].
+#[cfg(feature = "device")]
+impl<T: Prm + Interop + Serialize> Serialize for DeviceTensor<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut data = vec![T::zero(); self.shape().content()];
+        self.try_load(&mut data).map_err(serde::ser::Error::custom)?;
+        TensorRef { shape: self.shape(), data: &data }.serialize(serializer)
+    }
+}
+
+/// Deserializer of [`DeviceTensor`] that uploads tensor to specified context.
+/// Accepts the same representation as [`HostTensor`].
+#[cfg(feature = "device")]
+pub struct DeviceTensorSeed<'a, T: Prm + Interop> {
+    context: &'a DeviceContext,
+    phantom: PhantomData<T>,
+}
+
+#[cfg(feature = "device")]
+impl<'a, T: Prm + Interop> DeviceTensorSeed<'a, T> {
+    /// Create deserializer for specified context.
+    pub fn new(context: &'a DeviceContext) -> Self {
+        Self { context, phantom: PhantomData }
+    }
+}
+
+#[cfg(feature = "device")]
+impl<'a, 'de, T: Prm + Interop + Deserialize<'de>> DeserializeSeed<'de> for DeviceTensorSeed<'a, T> {
+    type Value = DeviceTensor<T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let tensor = TensorData::<T>::deserialize(deserializer)?;
+        tensor.check()?;
+        let mut dst = unsafe { DeviceTensor::new_uninit_in(self.context, &tensor.shape) };
+        dst.try_store(&tensor.data).map_err(D::Error::custom)?;
+        Ok(dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex;
+    use crate::{shape, Range, Index};
+
+    #[test]
+    fn shape() {
+        assert_eq!(serde_json::to_string(&shape![2, 1, 3, 1, 1]).unwrap(), "[2,1,3]");
+        let shape = serde_json::from_str::<Shape>("[4, 5, 1]").unwrap();
+        assert_eq!(shape, shape![4, 5]);
+        assert_eq!(shape.as_slice(), [4, 5]);
+    }
+
+    #[test]
+    fn index() {
+        let index = Index::Range(Range { start: 1, end: -1, step: 2 });
+        let text = serde_json::to_string(&index).unwrap();
+        match serde_json::from_str::<Index>(&text).unwrap() {
+            Index::Range(Range { start: 1, end: -1, step: 2 }) => (),
+            other => panic!("{:?}", other),
+        }
+        assert!(matches!(serde_json::from_str::<Index>(r#""NewAxis""#).unwrap(), Index::NewAxis));
+    }
+
+    #[test]
+    fn host_tensor() {
+        let a = HostTensor::from_fn(&shape![2, 3, 1], |p| (p[0] + 2*p[1]) as i32);
+        let text = serde_json::to_string(&a).unwrap();
+        assert_eq!(text, r#"{"shape":[2,3],"data":[0,1,2,3,4,5]}"#);
+        let b = serde_json::from_str::<HostTensor<i32>>(&text).unwrap();
+        assert_eq!(b.shape(), a.shape());
+        assert!(b.iter().eq(a.iter()));
+
+        let c = HostTensor::from_vec(&shape![2], vec![Complex::new(1.0f64, -1.0), Complex::new(0.5, 2.0)]);
+        let d = serde_json::from_str::<HostTensor<Complex<f64>>>(&serde_json::to_string(&c).unwrap()).unwrap();
+        assert!(d.iter().eq(c.iter()));
+    }
+
+    #[test]
+    fn length_mismatch() {
+        let err = serde_json::from_str::<HostTensor<i32>>(r#"{"shape":[2,2],"data":[0,1,2]}"#).err().unwrap();
+        assert!(err.to_string().contains("must have 4 elements, got 3"));
+    }
+
+    #[test]
+    fn size_overflow() {
+        let err = serde_json::from_str::<HostTensor<i32>>(r#"{"shape":[4294967296,4294967296],"data":[]}"#).err().unwrap();
+        assert!(err.to_string().contains("overflows"));
+    }
+}
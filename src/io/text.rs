@@ -0,0 +1,317 @@
+use std::{
+    fmt::{self, Display},
+    error::Error,
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+use crate::{Prm, Shape, Tensor, HostTensor};
+
+
+/// Error of reading tensor from text.
+///
+/// Lines and columns are numbered from 1, column is the number of field in line.
+#[derive(Debug)]
+pub enum TextError {
+    /// Underlying I/O error.
+    Io(io::Error),
+    /// Field cannot be parsed.
+    Parse {
+        line: usize,
+        column: usize,
+        /// Text of the field.
+        text: String,
+        /// Message of parsing error.
+        message: String,
+    },
+    /// Line has different number of fields than previous ones.
+    Columns {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for TextError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TextError::Io(err) => write!(f, "I/O error: {}", err),
+            TextError::Parse { line, column, text, message } => write!(f, "Line {}, column {}: cannot parse '{}': {}", line, column, text, message),
+            TextError::Columns { line, expected, found } => write!(f, "Line {}: expected {} columns, found {}", line, expected, found),
+        }
+    }
+}
+
+impl Error for TextError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TextError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TextError {
+    fn from(err: io::Error) -> Self {
+        TextError::Io(err)
+    }
+}
+
+/// Options of reading and writing CSV.
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+    /// Field delimiter, `None` means any sequence of whitespace characters. Default is `,`.
+    ///
+    /// Delimiter inside double quotes does not split fields, doubled quotes inside quoted field are kept as is.
+    pub delimiter: Option<char>,
+    /// Number of lines to skip at the beginning, e.g. `1` for a single header line. Default is `0`.
+    pub skip_header: usize,
+    /// Lines starting with this prefix are ignored. Default is `None`.
+    pub comments: Option<String>,
+    /// Whitespace around fields is removed before parsing, and double quotes around fields are removed as well.
+    /// Default is `true`.
+    pub trim: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self { delimiter: Some(','), skip_header: 0, comments: None, trim: true }
+    }
+}
+
+/// Format of writing tensor with [`HostTensor::savetxt`].
+#[derive(Clone, Debug)]
+pub struct TextFormat {
+    /// Field delimiter. Default is a single space.
+    pub delimiter: String,
+    /// Number of digits after the decimal point, `None` means the shortest representation. Default is `None`.
+    pub precision: Option<usize>,
+    /// Minimal width of the field, values are aligned to the right. Default is `0`.
+    pub width: usize,
+    /// Text written before the data, every line is prefixed with `comments`. Default is `None`.
+    pub header: Option<String>,
+    /// Prefix of header lines, lines starting with it are ignored by [`HostTensor::loadtxt`]. Default is `# `.
+    pub comments: String,
+}
+
+impl Default for TextFormat {
+    fn default() -> Self {
+        Self { delimiter: " ".to_string(), precision: None, width: 0, header: None, comments: "# ".to_string() }
+    }
+}
+
+/// Splits line by delimiter that is not enclosed in double quotes.
+fn split_quoted(line: &str, delimiter: char) -> Vec<&str> {
+    let (mut fields, mut start, mut quoted) = (Vec::new(), 0, false);
+    for (i, c) in line.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if c == delimiter && !quoted {
+            fields.push(&line[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    fields.push(&line[start..]);
+    fields
+}
+
+/// Reads lines of fields and parses them into tensor.
+/// Field in `i`-th line and `j`-th column becomes element at position `(i, j)`.
+fn read_table<T, R, E, P>(reader: R, options: &CsvOptions, mut parse: P) -> Result<HostTensor<T>, TextError>
+where T: Prm, R: BufRead, E: Display, P: FnMut(&str) -> Result<T, E> {
+    let mut values = Vec::new();
+    let (mut rows, mut cols) = (0, None);
+    for (index, line) in reader.lines().enumerate().skip(options.skip_header) {
+        let line = line?;
+        let comment = options.comments.as_ref().is_some_and(|c| line.trim_start().starts_with(c.as_str()));
+        if comment || line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = match options.delimiter {
+            Some(d) => split_quoted(&line, d),
+            None => line.split_whitespace().collect(),
+        };
+        match cols {
+            Some(n) if n != fields.len() => {
+                return Err(TextError::Columns { line: index + 1, expected: n, found: fields.len() });
+            }
+            _ => cols = Some(fields.len()),
+        }
+        for (column, &field) in fields.iter().enumerate() {
+            let text = if options.trim {
+                let text = field.trim();
+                text.strip_prefix('"').and_then(|t| t.strip_suffix('"')).unwrap_or(text)
+            } else {
+                field
+            };
+            match parse(text) {
+                Ok(x) => values.push(x),
+                Err(err) => return Err(TextError::Parse {
+                    line: index + 1,
+                    column: column + 1,
+                    text: text.to_string(),
+                    message: err.to_string(),
+                }),
+            }
+        }
+        rows += 1;
+    }
+    let cols = cols.unwrap_or(0);
+    let data = (0..(rows*cols)).map(|k| values[(k % rows)*cols + k / rows]).collect();
+    Ok(HostTensor::from_vec(&Shape::from(vec![rows, cols]), data))
+}
+
+/// Checks that tensor of specified shape can be written as text.
+fn check_rank(shape: &Shape) -> io::Result<()> {
+    if shape.len() > 2 {
+        let message = format!("Only tensors of rank 1 or 2 can be written as text, got shape {}", shape);
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+    }
+    Ok(())
+}
+
+/// Writes tensor as lines of fields.
+/// Element at position `(i, j)` is written to `i`-th line and `j`-th column.
+fn write_table<T, W, F>(tensor: &HostTensor<T>, mut writer: W, delimiter: &str, mut item: F) -> io::Result<()>
+where T: Prm, W: Write, F: FnMut(&mut W, &T) -> io::Result<()> {
+    let shape = tensor.shape();
+    check_rank(shape)?;
+    let (rows, cols) = (shape[0], shape[1]);
+    let data = tensor.buffer().as_slice();
+    for i in 0..rows {
+        for j in 0..cols {
+            if j > 0 {
+                writer.write_all(delimiter.as_bytes())?;
+            }
+            item(&mut writer, &data[i + rows*j])?;
+        }
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+impl<T: Prm> HostTensor<T> {
+    /// Reads tensor from CSV parsing fields with specified function.
+    ///
+    /// Field in `i`-th line and `j`-th column becomes element at position `(i, j)`,
+    /// so single-column data gives tensor of rank 1. Empty lines are skipped.
+    pub fn from_csv_with<R, E, P>(reader: R, options: &CsvOptions, parse: P) -> Result<Self, TextError>
+    where R: BufRead, E: Display, P: FnMut(&str) -> Result<T, E> {
+        read_table(reader, options, parse)
+    }
+}
+
+impl<T: Prm + FromStr> HostTensor<T> where T::Err: Display {
+    /// Reads tensor from CSV, see [`HostTensor::from_csv_with`].
+    pub fn from_csv<R: BufRead>(reader: R, options: &CsvOptions) -> Result<Self, TextError> {
+        read_table(reader, options, T::from_str)
+    }
+
+    /// Reads tensor from text written by [`HostTensor::savetxt`] with the same format.
+    /// Lines starting with `format.comments` are skipped.
+    pub fn loadtxt<R: BufRead>(reader: R, format: &TextFormat) -> Result<Self, TextError> {
+        let options = CsvOptions {
+            delimiter: format.delimiter.trim().chars().next(),
+            skip_header: 0,
+            comments: Some(format.comments.trim()).filter(|c| !c.is_empty()).map(String::from),
+            trim: true,
+        };
+        read_table(reader, &options, T::from_str)
+    }
+}
+
+impl<T: Prm + Display> HostTensor<T> {
+    /// Writes tensor of rank 1 or 2 to CSV.
+    /// Tensor of rank 1 is written as a single column, tensor of greater rank gives [`io::ErrorKind::InvalidInput`] error.
+    pub fn to_csv<W: Write>(&self, writer: W, options: &CsvOptions) -> io::Result<()> {
+        let delimiter = options.delimiter.unwrap_or(' ').to_string();
+        write_table(self, writer, &delimiter, |w, x| write!(w, "{}", x))
+    }
+
+    /// Writes tensor of rank 1 or 2 as text with specified format, see [`HostTensor::to_csv`].
+    pub fn savetxt<W: Write>(&self, mut writer: W, format: &TextFormat) -> io::Result<()> {
+        check_rank(self.shape())?;
+        if let Some(header) = &format.header {
+            for line in header.lines() {
+                writeln!(writer, "{}{}", format.comments, line)?;
+            }
+        }
+        let width = format.width;
+        write_table(self, writer, &format.delimiter, |w, x| match format.precision {
+            Some(p) => write!(w, "{:>width$.p$}", x, width = width, p = p),
+            None => write!(w, "{:>width$}", x, width = width),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape;
+
+    #[test]
+    fn csv() {
+        let text = "x,y,z\n1, 2,3\n\n4,\"5\",6\n";
+        let options = CsvOptions { skip_header: 1, ..CsvOptions::default() };
+        let a = HostTensor::<i32>::from_csv(text.as_bytes(), &options).unwrap();
+        assert_eq!(a.shape(), &shape![2, 3]);
+        assert_eq!(format!("{}", a), "[[1 2 3]\n [4 5 6]]");
+
+        let mut bytes = Vec::new();
+        a.to_csv(&mut bytes, &CsvOptions::default()).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), "1,2,3\n4,5,6\n");
+    }
+
+    #[test]
+    fn column() {
+        let a = HostTensor::<f64>::from_csv("0.5\n1.5\n-2\n".as_bytes(), &CsvOptions::default()).unwrap();
+        assert_eq!(a.shape(), &shape![3]);
+        let mut bytes = Vec::new();
+        a.to_csv(&mut bytes, &CsvOptions::default()).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), "0.5\n1.5\n-2\n");
+    }
+
+    #[test]
+    fn custom_parse() {
+        let parse = |s: &str| u8::from_str_radix(s, 16);
+        let a = HostTensor::from_csv_with("ff;10\n0a;00".as_bytes(), &CsvOptions { delimiter: Some(';'), ..CsvOptions::default() }, parse).unwrap();
+        assert!(a.iter().cloned().eq([255, 10, 16, 0].iter().cloned()));
+    }
+
+    #[test]
+    fn txt() {
+        let a = HostTensor::from_fn(&shape![2, 2], |p| (p[0] as f32) - 0.25*(p[1] as f32));
+        let format = TextFormat { precision: Some(2), width: 6, header: Some("a b".to_string()), ..TextFormat::default() };
+        let mut bytes = Vec::new();
+        a.savetxt(&mut bytes, &format).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        assert_eq!(text, "# a b\n  0.00  -0.25\n  1.00   0.75\n");
+        let b = HostTensor::<f32>::loadtxt(text.as_bytes(), &format).unwrap();
+        assert!(b.iter().eq(a.iter()));
+
+        let format = TextFormat { delimiter: ", ".to_string(), ..TextFormat::default() };
+        let mut bytes = Vec::new();
+        a.savetxt(&mut bytes, &format).unwrap();
+        assert!(HostTensor::<f32>::loadtxt(bytes.as_slice(), &format).unwrap().iter().eq(a.iter()));
+    }
+
+    #[test]
+    fn errors() {
+        match HostTensor::<i32>::from_csv("1,2\n3,x\n".as_bytes(), &CsvOptions::default()) {
+            Err(TextError::Parse { line: 2, column: 2, text, .. }) => assert_eq!(text, "x"),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        match HostTensor::<i32>::from_csv("# c\n1,2\n3\n".as_bytes(), &CsvOptions { comments: Some("#".to_string()), ..CsvOptions::default() }) {
+            Err(TextError::Columns { line: 3, expected: 2, found: 1 }) => (),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+        let err = HostTensor::<i32>::new_zeroed(&shape![2, 2, 2]).to_csv(Vec::new(), &CsvOptions::default()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn quoted() {
+        let parse = |s: &str| s.replace(',', "").parse::<i32>();
+        let a = HostTensor::from_csv_with("\"1,000\",2\n3,\"4,5\"\n".as_bytes(), &CsvOptions::default(), parse).unwrap();
+        assert!(a.iter().cloned().eq([1000, 3, 2, 45].iter().cloned()));
+    }
+}
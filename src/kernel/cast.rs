@@ -0,0 +1,51 @@
+use crate::{
+    Prm, Interop, DevType,
+    Buffer, DeviceBuffer,
+};
+use super::{header, builder, enqueue};
+
+
+/// OpenCL expression that converts value `x` of type `src` to type `dst`.
+/// It follows the same semantics as [`Cast`](crate::Cast) does on the host.
+///
+/// Note that `bfloat16` values are converted through `float`,
+/// so the rounding of `double` values may differ from the host one.
+pub(crate) fn cast_expr(src: DevType, dst: DevType, x: &str) -> String {
+    use DevType::*;
+    match (src, dst) {
+        _ if src == dst => x.to_string(),
+        (Complex(_), Complex(_)) => format!("convert_{}({})", dst.name(), x),
+        (Complex(_), Bool) => format!("(uchar)((({0}).x != 0 || ({0}).y != 0) ? 0xFF : 0x00)", x),
+        (Complex(bits), _) => cast_expr(Float(bits), dst, &format!("({}).x", x)),
+        (_, Complex(bits)) => format!("({})({}, 0)", dst.name(), cast_expr(src, Float(bits), x)),
+        (BFloat16, Bool) => format!("(uchar)((({}) & 0x7FFF) != 0 ? 0xFF : 0x00)", x),
+        (BFloat16, _) => cast_expr(Float(32), dst, &format!("bf16_to_float({})", x)),
+        (_, BFloat16) => format!("float_to_bf16({})", cast_expr(src, Float(32), x)),
+        (_, Bool) => format!("(uchar)(({}) != 0 ? 0xFF : 0x00)", x),
+        (Bool, _) => format!("({})(({}) != 0)", dst.name(), x),
+        (Float(_), Uint(_)) | (Float(_), Int(_)) => format!("convert_{}_sat_rtz({})", dst.name(), x),
+        // Conversion to unsigned integer is wrapping in C99, so signed values are converted through it.
+        (_, Int(bits)) => format!("as_{}(convert_{}({}))", dst.name(), Uint(bits).name(), x),
+        (_, Uint(_)) | (_, Float(_)) => format!("convert_{}({})", dst.name(), x),
+    }
+}
+
+/// Converts elements of `src` buffer to another type and writes them to `dst`.
+pub(crate) fn cast<T: Prm + Interop, U: Prm + Interop>(src: &DeviceBuffer<T>, dst: &mut DeviceBuffer<U>) {
+    assert_eq!(src.len(), dst.len());
+    assert!(src.context() == dst.context());
+    let context = src.context();
+    let (st, dt) = (T::dev_type(context.size_repr()), U::dev_type(context.size_repr()));
+    let program = context.program(&format!("cast {:?} {:?}", st, dt), || {
+        header(&[st, dt]) + &format!(r#"
+            __kernel void cast(__global const {} *src, __global {} *dst) {{
+                size_t i = get_global_id(0);
+                dst[i] = {};
+            }}
+        "#, st.name(), dt.name(), cast_expr(st, dt, "src[i]"))
+    });
+    enqueue(
+        builder(context, &program, "cast").arg(src.mem()).arg(dst.mem()),
+        &[src.len()],
+    );
+}
@@ -0,0 +1,76 @@
+use crate::{
+    Prm, Interop, DevType,
+    Buffer, DeviceBuffer,
+};
+use super::{header, builder, enqueue, scalar_type};
+
+
+/// Element-wise operation on complex values.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum ComplexOp {
+    Conj,
+    Abs,
+    Arg,
+    Real,
+    Imag,
+}
+
+impl ComplexOp {
+    /// OpenCL expression of operation on value `x` of type `t`.
+    fn expr(&self, t: DevType, x: &str) -> String {
+        match self {
+            ComplexOp::Conj => format!("({})(({x}).x, -({x}).y)", t.name(), x=x),
+            ComplexOp::Abs => format!("hypot(({x}).x, ({x}).y)", x=x),
+            ComplexOp::Arg => format!("atan2(({x}).y, ({x}).x)", x=x),
+            ComplexOp::Real => format!("({}).x", x),
+            ComplexOp::Imag => format!("({}).y", x),
+        }
+    }
+}
+
+/// Applies operation to complex values of `src` and writes results to `dst`.
+///
+/// Type of `dst` must be either the same complex type or the type of its components depending on operation.
+pub(crate) fn complex_map<T: Prm + Interop, U: Prm + Interop>(src: &DeviceBuffer<T>, dst: &mut DeviceBuffer<U>, op: ComplexOp) {
+    assert_eq!(src.len(), dst.len());
+    assert!(src.context() == dst.context());
+    let context = dst.context().clone();
+    let (st, dt) = (T::dev_type(context.size_repr()), U::dev_type(context.size_repr()));
+    assert!(matches!(st, DevType::Complex(_)), "Source must be of complex type");
+    assert!(dt == st || dt == scalar_type(st));
+    let program = context.program(&format!("complex_map {:?} {:?}", st, op), || {
+        header(&[st]) + &format!(r#"
+            __kernel void complex_map(__global const {st} *src, __global {dt} *dst) {{
+                size_t i = get_global_id(0);
+                dst[i] = {value};
+            }}
+        "#, st=st.name(), dt=dt.name(), value=op.expr(st, "src[i]"))
+    });
+    enqueue(
+        builder(&context, &program, "complex_map").arg(src.mem()).arg(dst.mem()),
+        &[dst.len()],
+    );
+}
+
+/// Builds complex values from pairs of real ones, either as real and imaginary parts or as modulus and argument if `polar`.
+pub(crate) fn complex_join<T: Prm + Interop, U: Prm + Interop>(a: &DeviceBuffer<T>, b: &DeviceBuffer<T>, dst: &mut DeviceBuffer<U>, polar: bool) {
+    assert!(a.len() == dst.len() && b.len() == dst.len());
+    assert!(a.context() == dst.context() && b.context() == dst.context());
+    let context = dst.context().clone();
+    let (st, dt) = (T::dev_type(context.size_repr()), U::dev_type(context.size_repr()));
+    assert!(matches!(dt, DevType::Complex(_)) && scalar_type(dt) == st, "Destination must be complex type with source components");
+    let program = context.program(&format!("complex_join {:?} {}", dt, polar), || {
+        let value = if polar { "(a*cos(b), a*sin(b))" } else { "(a, b)" };
+        header(&[dt]) + &format!(r#"
+            __kernel void complex_join(__global const {st} *src_a, __global const {st} *src_b, __global {dt} *dst) {{
+                size_t i = get_global_id(0);
+                {st} a = src_a[i], b = src_b[i];
+                dst[i] = ({dt}){value};
+            }}
+        "#, st=st.name(), dt=dt.name(), value=value)
+    });
+    enqueue(
+        builder(&context, &program, "complex_join").arg(a.mem()).arg(b.mem()).arg(dst.mem()),
+        &[dst.len()],
+    );
+}
@@ -0,0 +1,166 @@
+use crate::{
+    Prm, Interop, DevType,
+    Buffer, DeviceBuffer,
+    Window, ConvGeometry,
+    num::Zero,
+};
+use super::{header, builder, enqueue, params, cast_expr, acc_type};
+
+
+/// Kind of pooling.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Pool {
+    Max,
+    Avg { count_padding: bool },
+}
+
+/// Window geometry passed to kernels, values `N, K, S, P, D, O` for each of spatial axes.
+///
+/// Geometry is passed at runtime rather than compiled into the kernel,
+/// so that a single program per type is built and cached in context.
+fn window_params(w: &Window) -> Vec<u64> {
+    (0..3).flat_map(|a| [w.input[a], w.kernel[a], w.stride[a], w.padding[a], w.dilation[a], w.output[a]])
+        .map(|x| x as u64).collect()
+}
+
+/// Declarations of window geometry variables read from `geo` kernel argument, see [`window_params`].
+fn window_decls() -> String {
+    let mut src = String::new();
+    for (a, x) in ["X", "Y", "Z"].iter().enumerate() {
+        src += &format!(
+            "const long N{x} = geo[{}], K{x} = geo[{}], S{x} = geo[{}], P{x} = geo[{}], D{x} = geo[{}], O{x} = geo[{}];\n",
+            6*a, 6*a + 1, 6*a + 2, 6*a + 3, 6*a + 4, 6*a + 5, x=x,
+        );
+    }
+    src
+}
+
+/// Loops over kernel window of output position `(ox, oy, oz)`, `body` is executed for input positions `(x, y, z)` inside input.
+fn window_loops(body: &str) -> String {
+    format!(r#"
+        for (long kz = 0; kz < KZ; ++kz) {{
+            long z = (long)(oz*SZ + kz*DZ) - PZ;
+            if (z < 0 || z >= NZ) continue;
+            for (long ky = 0; ky < KY; ++ky) {{
+                long y = (long)(oy*SY + ky*DY) - PY;
+                if (y < 0 || y >= NY) continue;
+                for (long kx = 0; kx < KX; ++kx) {{
+                    long x = (long)(ox*SX + kx*DX) - PX;
+                    if (x < 0 || x >= NX) continue;
+                    {}
+                }}
+            }}
+        }}
+    "#, body)
+}
+
+/// Computes convolution, see [`HostTensor::conv`](crate::HostTensor::conv) for the layout.
+///
+/// This is a direct convolution without local memory tiling: work item computes single output value,
+/// work size is `(output_size, out_channels, batch)`.
+pub(crate) fn conv<T: Prm + Interop>(
+    src: &DeviceBuffer<T>, weight: &DeviceBuffer<T>, bias: Option<&DeviceBuffer<T>>, dst: &mut DeviceBuffer<T>,
+    geometry: &ConvGeometry,
+) {
+    let ConvGeometry { window, in_channels, out_channels, groups, batch } = *geometry;
+    assert_eq!(src.len(), window.input_size() * in_channels * batch);
+    assert_eq!(weight.len(), window.kernel_size() * in_channels / groups * out_channels);
+    assert_eq!(dst.len(), window.output_size() * out_channels * batch);
+    assert!(src.context() == dst.context() && weight.context() == dst.context());
+    let context = dst.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    let ct = acc_type(dt);
+    let program = context.program(&format!("conv {:?}", dt), || {
+        header(&[dt, ct]) + &format!(r#"
+            __kernel void conv(
+                __global const {dt} *src, __global const {dt} *w, __global const {dt} *bias, __global {dt} *dst,
+                __global const ulong *geo
+            ) {{
+                {decls}
+                const ulong CI = geo[18], CO = geo[19], CG = geo[20], OG = geo[21], HAS_BIAS = geo[22];
+                size_t q = get_global_id(0), co = get_global_id(1), b = get_global_id(2);
+                size_t ox = q % OX, oy = (q / OX) % OY, oz = q / (OX*OY);
+                size_t g = co / OG;
+                {ct} acc = HAS_BIAS ? {bias} : {zero};
+                for (size_t c = 0; c < CG; ++c) {{
+                    __global const {dt} *s = src + NX*NY*NZ*(g*CG + c + CI*b);
+                    __global const {dt} *k = w + KX*KY*KZ*(c + CG*co);
+                    {loops}
+                }}
+                dst[q + OX*OY*OZ*(co + CO*b)] = {store};
+            }}
+        "#,
+            dt=dt.name(), ct=ct.name(), decls=window_decls(),
+            bias=cast_expr(dt, ct, "bias[co]"), zero=cast_expr(DevType::Int(32), ct, "0"),
+            loops=window_loops(&format!(
+                "acc += {}*{};",
+                cast_expr(dt, ct, "s[x + NX*(y + NY*z)]"), cast_expr(dt, ct, "k[kx + KX*(ky + KY*kz)]"),
+            )),
+            store=cast_expr(ct, dt, "acc"),
+        )
+    });
+    let has_bias = bias.is_some() as usize;
+    // Kernel argument must be a valid buffer even if there is no bias.
+    let dummy;
+    let bias = match bias {
+        Some(bias) => bias,
+        None => {
+            dummy = params(&context, &[<T as Zero>::zero()]);
+            &dummy
+        }
+    };
+    let mut geo = window_params(&window);
+    geo.extend([in_channels, out_channels, in_channels / groups, out_channels / groups, has_bias].iter().map(|&x| x as u64));
+    let geo = params(&context, &geo);
+    enqueue(
+        builder(&context, &program, "conv").arg(src.mem()).arg(weight.mem()).arg(bias.mem()).arg(dst.mem()).arg(geo.mem()),
+        &[window.output_size(), out_channels, batch],
+    );
+}
+
+/// Computes pooling over sliding window, the rest of axes after spatial ones are processed independently.
+///
+/// Like [`conv`], work item computes single output value, work size is `(output_size, rest_size)`.
+pub(crate) fn pool<T: Prm + Interop>(src: &DeviceBuffer<T>, dst: &mut DeviceBuffer<T>, window: &Window, mode: Pool) {
+    let rest = src.len() / window.input_size().max(1);
+    assert_eq!(dst.len(), window.output_size() * rest);
+    assert!(src.context() == dst.context());
+    let context = dst.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    let ct = acc_type(dt);
+    let program = context.program(&format!("pool {:?} {:?}", dt, mode), || {
+        let value = cast_expr(dt, ct, "s[x + NX*(y + NY*z)]");
+        let (init, body, result) = match mode {
+            Pool::Max => (
+                format!("({})(-INFINITY)", ct.name()),
+                format!("acc = fmax(acc, {});", value),
+                "acc".to_string(),
+            ),
+            Pool::Avg { count_padding } => (
+                cast_expr(DevType::Int(32), ct, "0"),
+                format!("acc += {}; ++count;", value),
+                format!("acc/({})({})", ct.name(), if count_padding { "KX*KY*KZ" } else { "count" }),
+            ),
+        };
+        header(&[dt, ct]) + &format!(r#"
+            __kernel void pool(__global const {dt} *src, __global {dt} *dst, __global const ulong *geo) {{
+                {decls}
+                size_t q = get_global_id(0), c = get_global_id(1);
+                size_t ox = q % OX, oy = (q / OX) % OY, oz = q / (OX*OY);
+                __global const {dt} *s = src + NX*NY*NZ*c;
+                {ct} acc = {init};
+                ulong count = 0;
+                {loops}
+                dst[q + OX*OY*OZ*c] = {store};
+            }}
+        "#,
+            dt=dt.name(), ct=ct.name(), decls=window_decls(), init=init, loops=window_loops(&body),
+            store=cast_expr(ct, dt, &format!("({})", result)),
+        )
+    });
+    let geo = params(&context, &window_params(window));
+    enqueue(
+        builder(&context, &program, "pool").arg(src.mem()).arg(dst.mem()).arg(geo.mem()),
+        &[window.output_size(), rest],
+    );
+}
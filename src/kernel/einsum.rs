@@ -0,0 +1,99 @@
+use crate::{
+    Prm, Interop, DevType,
+    Buffer, DeviceBuffer,
+};
+use super::{header, builder, enqueue, params, cast_expr, acc_type, mul_expr};
+
+
+/// Computes `dst[o] = sum(src[offset(o) + offset(s)])` over all `s`.
+///
+/// Offsets of destination position and summation position are computed from `dims` and `strides`
+/// and from `sum_dims` and `sum_strides` respectively, axis 0 varies fastest.
+pub(crate) fn reduce_strided<T: Prm + Interop>(
+    src: &DeviceBuffer<T>, dst: &mut DeviceBuffer<T>,
+    (dims, strides): (&[usize], &[usize]), (sum_dims, sum_strides): (&[usize], &[usize]),
+) {
+    assert_eq!(dst.len(), dims.iter().product::<usize>());
+    assert!(src.context() == dst.context());
+    let context = src.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    let ct = acc_type(dt);
+    let program = context.program(&format!("reduce_strided {:?}", dt), || {
+        header(&[dt, ct]) + &format!(r#"
+            __kernel void reduce_strided(__global const {dt} *src, __global {dt} *dst, __global const ulong *layout) {{
+                size_t o = get_global_id(0);
+                ulong rank = layout[0], srank = layout[1];
+                __global const ulong *dims = layout + 2, *strides = dims + rank;
+                __global const ulong *sdims = strides + rank, *sstrides = sdims + srank;
+                ulong base = 0, r = o, count = 1;
+                for (ulong a = 0; a < rank; ++a) {{
+                    base += (r % dims[a])*strides[a];
+                    r /= dims[a];
+                }}
+                for (ulong a = 0; a < srank; ++a) {{
+                    count *= sdims[a];
+                }}
+                {ct} acc = {zero};
+                for (ulong s = 0; s < count; ++s) {{
+                    ulong offset = base, q = s;
+                    for (ulong a = 0; a < srank; ++a) {{
+                        offset += (q % sdims[a])*sstrides[a];
+                        q /= sdims[a];
+                    }}
+                    acc = acc + {value};
+                }}
+                dst[o] = {store};
+            }}
+        "#,
+            dt=dt.name(), ct=ct.name(),
+            zero=cast_expr(DevType::Int(32), ct, "0"),
+            value=cast_expr(dt, ct, "src[offset]"), store=cast_expr(ct, dt, "acc"),
+        )
+    });
+    let layout = [dims.len(), sum_dims.len()].iter()
+        .chain(dims).chain(strides).chain(sum_dims).chain(sum_strides)
+        .map(|&x| x as u64).collect::<Vec<_>>();
+    let p = params(&context, &layout);
+    enqueue(
+        builder(&context, &program, "reduce_strided").arg(src.mem()).arg(dst.mem()).arg(p.mem()),
+        &[dst.len()],
+    );
+}
+
+/// Computes batch of matrix products `c[t] = a[t]*b[t]`,
+/// where matrices are `m*k`, `k*n` and `m*n` respectively and stored in column-major order.
+pub(crate) fn matmul_batched<T: Prm + Interop>(
+    a: &DeviceBuffer<T>, b: &DeviceBuffer<T>, c: &mut DeviceBuffer<T>,
+    (m, k, n): (usize, usize, usize), batch: usize,
+) {
+    assert_eq!(a.len(), m * k * batch);
+    assert_eq!(b.len(), k * n * batch);
+    assert_eq!(c.len(), m * n * batch);
+    assert!(a.context() == c.context() && b.context() == c.context());
+    let context = c.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    let ct = acc_type(dt);
+    let program = context.program(&format!("matmul_batched {:?}", dt), || {
+        header(&[dt, ct]) + &format!(r#"
+            __kernel void matmul_batched(__global const {dt} *a, __global const {dt} *b, __global {dt} *c, ulong k) {{
+                size_t i = get_global_id(0), j = get_global_id(1), t = get_global_id(2);
+                size_t m = get_global_size(0), n = get_global_size(1);
+                {ct} acc = {zero};
+                for (ulong l = 0; l < k; ++l) {{
+                    {ct} x = {x}, y = {y};
+                    acc = acc + {mul};
+                }}
+                c[i + m*(j + n*t)] = {store};
+            }}
+        "#,
+            dt=dt.name(), ct=ct.name(),
+            zero=cast_expr(DevType::Int(32), ct, "0"),
+            x=cast_expr(dt, ct, "a[i + m*(l + k*t)]"), y=cast_expr(dt, ct, "b[l + k*(j + n*t)]"),
+            mul=mul_expr(ct, "x", "y"), store=cast_expr(ct, dt, "acc"),
+        )
+    });
+    enqueue(
+        builder(&context, &program, "matmul_batched").arg(a.mem()).arg(b.mem()).arg(c.mem()).arg(k as u64),
+        &[m, n, batch],
+    );
+}
@@ -0,0 +1,115 @@
+use crate::{
+    Prm, Interop, DevType,
+    Buffer, DeviceBuffer,
+};
+use super::{header, builder, enqueue, scalar_type};
+
+
+/// Radices of FFT passes for axis of size `n`, their product is `n`.
+///
+/// Radix-4 and small prime radices are preferred, large prime factors are computed by direct DFT.
+fn radices(mut n: usize) -> Vec<usize> {
+    let mut factors = Vec::new();
+    for p in [4, 2, 3, 5, 7] {
+        while n > 1 && n % p == 0 {
+            factors.push(p);
+            n /= p;
+        }
+    }
+    let mut p = 11;
+    while n > 1 {
+        while n % p == 0 {
+            factors.push(p);
+            n /= p;
+        }
+        p += 2;
+    }
+    factors
+}
+
+/// Computes FFT of complex values along the axis, `blocks` are `(inner, size, outer)` as returned by `axis_blocks`.
+///
+/// Transform is performed by a sequence of mixed-radix Stockham passes, so no bit-reversal permutation is needed.
+/// Inverse transform is normalized by `1/size`.
+pub(crate) fn fft<T: Prm + Interop>(src: &DeviceBuffer<T>, (inner, n, outer): (usize, usize, usize), inverse: bool) -> DeviceBuffer<T> {
+    assert_eq!(src.len(), inner * n * outer);
+    let context = src.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    assert!(matches!(dt, DevType::Complex(_)), "FFT is only supported for complex types");
+    let st = scalar_type(dt);
+    let program = context.program(&format!("fft {:?} {}", dt, inverse), || {
+        header(&[dt]) + &format!(r#"
+            __kernel void fft_pass(
+                __global const {dt} *src, __global {dt} *dst,
+                ulong inner, ulong n, ulong radix, ulong ns, ulong div
+            ) {{
+                ulong j = get_global_id(0), x = get_global_id(1), o = get_global_id(2);
+                ulong m = n / radix, k = j % ns, d = (j / ns)*ns*radix + k;
+                __global const {dt} *s = src + x + inner*n*o;
+                __global {dt} *t = dst + x + inner*n*o;
+                for (ulong q = 0; q < radix; ++q) {{
+                    {dt} acc = ({dt})(0, 0);
+                    for (ulong r = 0; r < radix; ++r) {{
+                        {dt} v = s[inner*(j + r*m)];
+                        // Twiddle factor is combined with the radix DFT one and reduced modulo full turn for precision.
+                        {st} a = ({sign}({st})6.283185307179586)*({st})((r*(k + q*ns)) % (ns*radix))/({st})(ns*radix);
+                        {st} c = cos(a), si = sin(a);
+                        acc += ({dt})(v.x*c - v.y*si, v.x*si + v.y*c);
+                    }}
+                    t[inner*(d + q*ns)] = acc/({st})div;
+                }}
+            }}
+        "#, dt=dt.name(), st=st.name(), sign=if inverse { "" } else { "-" })
+    });
+    let factors = radices(n);
+    let mut dst = unsafe { DeviceBuffer::new_uninit_in(&context, src.len()) };
+    if factors.is_empty() {
+        dst.copy_from(src);
+        return dst;
+    }
+    let mut tmp = unsafe { DeviceBuffer::new_uninit_in(&context, src.len()) };
+    let mut ns = 1;
+    for (i, &radix) in factors.iter().enumerate() {
+        let last = i + 1 == factors.len();
+        let div = if inverse && last { n } else { 1 };
+        enqueue(
+            builder(&context, &program, "fft_pass")
+                .arg(if i == 0 { src.mem() } else { dst.mem() }).arg(tmp.mem())
+                .arg(inner as u64).arg(n as u64).arg(radix as u64).arg(ns as u64).arg(div as u64),
+            &[n / radix, inner, outer],
+        );
+        std::mem::swap(&mut dst, &mut tmp);
+        ns *= radix;
+    }
+    dst
+}
+
+/// Restores full spectrum of size `n` along the axis from its first `size` values using Hermitian symmetry.
+pub(crate) fn hermitian<T: Prm + Interop>(src: &DeviceBuffer<T>, dst: &mut DeviceBuffer<T>, (inner, m, outer): (usize, usize, usize), n: usize) {
+    assert_eq!(src.len(), inner * m * outer);
+    assert_eq!(dst.len(), inner * n * outer);
+    assert!(src.context() == dst.context());
+    let context = dst.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    assert!(matches!(dt, DevType::Complex(_)), "Hermitian symmetry is only defined for complex types");
+    let program = context.program(&format!("hermitian {:?}", dt), || {
+        header(&[dt]) + &format!(r#"
+            __kernel void hermitian(__global const {dt} *src, __global {dt} *dst, ulong m, ulong n) {{
+                ulong x = get_global_id(0), k = get_global_id(1), o = get_global_id(2), inner = get_global_size(0);
+                __global const {dt} *s = src + x + inner*m*o;
+                {dt} v;
+                if (k < m) {{
+                    v = s[inner*k];
+                }} else {{
+                    v = s[inner*(n - k)];
+                    v.y = -v.y;
+                }}
+                dst[x + inner*(k + n*o)] = v;
+            }}
+        "#, dt=dt.name())
+    });
+    enqueue(
+        builder(&context, &program, "hermitian").arg(src.mem()).arg(dst.mem()).arg(m as u64).arg(n as u64),
+        &[inner, n, outer],
+    );
+}
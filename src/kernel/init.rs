@@ -0,0 +1,79 @@
+use crate::{
+    Prm, Interop, DevType,
+    Buffer, DeviceBuffer,
+};
+use super::{header, builder, enqueue, params, cast_expr, acc_type, scalar_type};
+
+/// Fills buffer with `start + i*step` values.
+pub(crate) fn arange<T: Prm + Interop>(dst: &mut DeviceBuffer<T>, start: T, step: T) {
+    let context = dst.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    let ct = acc_type(dt);
+    let program = context.program(&format!("arange {:?}", dt), || {
+        header(&[dt, ct]) + &format!(r#"
+            __kernel void arange(__global const {dt} *p, __global {dt} *dst) {{
+                size_t i = get_global_id(0);
+                {ct} start = {start}, step = {step};
+                dst[i] = {value};
+            }}
+        "#,
+            dt=dt.name(), ct=ct.name(),
+            start=cast_expr(dt, ct, "p[0]"), step=cast_expr(dt, ct, "p[1]"),
+            value=cast_expr(ct, dt, &format!(
+                "(start + {}*step)", cast_expr(DevType::Uint(64), scalar_type(ct), "(ulong)i"),
+            )),
+        )
+    });
+    let p = params(&context, &[start, step]);
+    enqueue(
+        builder(&context, &program, "arange").arg(p.mem()).arg(dst.mem()),
+        &[dst.len()],
+    );
+}
+
+/// Fills buffer with values evenly spaced from `start` to `stop` (both inclusive).
+pub(crate) fn linspace<T: Prm + Interop>(dst: &mut DeviceBuffer<T>, start: T, stop: T) {
+    let context = dst.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    let ct = acc_type(dt);
+    let program = context.program(&format!("linspace {:?}", dt), || {
+        header(&[dt, ct]) + &format!(r#"
+            __kernel void linspace(__global const {dt} *p, __global {dt} *dst, ulong n) {{
+                size_t i = get_global_id(0);
+                {ct} start = {start}, stop = {stop};
+                dst[i] = {value};
+            }}
+        "#,
+            dt=dt.name(), ct=ct.name(),
+            start=cast_expr(dt, ct, "p[0]"), stop=cast_expr(dt, ct, "p[1]"),
+            value=cast_expr(ct, dt, &format!(
+                "(n > 1 ? start + (stop - start)*({ct})i/({ct})(n - 1) : start)", ct=ct.name(),
+            )),
+        )
+    });
+    let p = params(&context, &[start, stop]);
+    enqueue(
+        builder(&context, &program, "linspace").arg(p.mem()).arg(dst.mem()).arg(dst.len() as u64),
+        &[dst.len()],
+    );
+}
+
+/// Fills buffer of `n*n` size with identity matrix.
+pub(crate) fn eye<T: Prm + Interop>(dst: &mut DeviceBuffer<T>, n: usize) {
+    assert_eq!(dst.len(), n*n);
+    let context = dst.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    let program = context.program(&format!("eye {:?}", dt), || {
+        header(&[dt]) + &format!(r#"
+            __kernel void eye(__global const {dt} *p, __global {dt} *dst, ulong n) {{
+                size_t i = get_global_id(0);
+                dst[i] = p[i % (n + 1) == 0];
+            }}
+        "#, dt=dt.name())
+    });
+    let p = params(&context, &[T::zero(), T::one()]);
+    enqueue(
+        builder(&context, &program, "eye").arg(p.mem()).arg(dst.mem()).arg(n as u64),
+        &[dst.len()],
+    );
+}
@@ -0,0 +1,127 @@
+use std::{
+    collections::BTreeSet,
+};
+use ocl::{Program, Kernel, SpatialDims, builders::KernelBuilder};
+use crate::{
+    Prm, Interop, DevType,
+    Buffer, DeviceBuffer, DeviceContext,
+};
+
+mod cast;
+pub(crate) use cast::*;
+
+mod init;
+pub(crate) use init::*;
+
+mod random;
+pub(crate) use random::*;
+
+mod select;
+pub(crate) use select::*;
+
+mod einsum;
+pub(crate) use einsum::*;
+
+mod pad;
+pub(crate) use pad::*;
+
+mod conv;
+pub(crate) use conv::*;
+
+mod fft;
+pub(crate) use fft::*;
+
+mod complex;
+pub(crate) use complex::*;
+
+mod sort;
+pub(crate) use sort::*;
+
+
+/// Functions for converting `bfloat16` raw bits to `float` and back (rounding to nearest even).
+const BF16_SOURCE: &str = r#"
+float bf16_to_float(ushort x) {
+    return as_float(((uint)x) << 16);
+}
+ushort float_to_bf16(float f) {
+    uint x = as_uint(f);
+    if ((x & 0x7FFFFFFF) > 0x7F800000) {
+        return (ushort)((x >> 16) | 0x40);
+    }
+    return (ushort)((x + 0x7FFF + ((x >> 16) & 1)) >> 16);
+}
+"#;
+
+/// Program header that enables extensions and declares helper functions required by specified types.
+pub(crate) fn header(types: &[DevType]) -> String {
+    let extensions = types.iter().filter_map(|t| t.extension()).collect::<BTreeSet<_>>();
+    let mut src = String::new();
+    for ext in extensions {
+        src += &format!("#pragma OPENCL EXTENSION {} : enable\n", ext);
+    }
+    src += BF16_SOURCE;
+    src
+}
+
+/// Type in which arithmetic on values of specified type is performed by kernels.
+/// It is `float` for half-precision types and the type itself for other ones, similarly to [`Float::Acc`](crate::Float::Acc).
+pub(crate) fn acc_type(t: DevType) -> DevType {
+    match t {
+        DevType::Float(16) | DevType::BFloat16 => DevType::Float(32),
+        t => t,
+    }
+}
+
+/// Type of scalar components of specified type.
+pub(crate) fn scalar_type(t: DevType) -> DevType {
+    match t {
+        DevType::Complex(bits) => DevType::Float(bits),
+        t => t,
+    }
+}
+
+/// OpenCL expression of the product of `x` and `y` of specified type.
+pub(crate) fn mul_expr(t: DevType, x: &str, y: &str) -> String {
+    match t {
+        DevType::Complex(_) => format!(
+            "({t})(({x}).x*({y}).x - ({x}).y*({y}).y, ({x}).x*({y}).y + ({x}).y*({y}).x)",
+            t=t.name(), x=x, y=y,
+        ),
+        _ => format!("({})*({})", x, y),
+    }
+}
+
+/// Creates buffer that contains specified values.
+/// It is used to pass values of arbitrary type to kernels.
+pub(crate) fn params<T: Prm + Interop>(context: &DeviceContext, values: &[T]) -> DeviceBuffer<T> {
+    let mut buffer = unsafe { DeviceBuffer::new_uninit_in(context, values.len()) };
+    buffer.store(values);
+    buffer
+}
+
+/// Creates kernel builder for program in context.
+///
+/// Argument type checking is disabled because `usize`, `bool` and complex values
+/// are passed as buffers of other host types.
+pub(crate) fn builder<'b>(context: &DeviceContext, program: &'b Program, name: &str) -> KernelBuilder<'b> {
+    let mut builder = Kernel::builder();
+    builder.program(program).name(name).queue(context.queue().clone());
+    unsafe { builder.disable_arg_type_check(); }
+    builder
+}
+
+/// Builds kernel and enqueues it with specified global work size.
+/// Does nothing if work size is zero.
+pub(crate) fn enqueue(builder: &mut KernelBuilder, work_size: &[usize]) {
+    if work_size.contains(&0) {
+        return;
+    }
+    let dims = match *work_size {
+        [x] => SpatialDims::One(x),
+        [x, y] => SpatialDims::Two(x, y),
+        [x, y, z] => SpatialDims::Three(x, y, z),
+        _ => panic!("Kernel work size must have from 1 to 3 dimensions"),
+    };
+    let kernel = builder.global_work_size(dims).build().unwrap();
+    unsafe { kernel.enq().unwrap(); }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    Prm, Interop, PadMode,
+    Buffer, DeviceBuffer,
+};
+use super::{header, builder, enqueue, params};
+
+
+/// OpenCL body of `long pad_index(long j, long n)` function, it is the same as [`PadMode::source_index`].
+fn index_source<T>(mode: &PadMode<T>) -> &'static str {
+    match mode {
+        PadMode::Constant(_) => "return (j < 0 || j >= n) ? -1 : j;",
+        PadMode::Reflect => r#"
+            if (n == 1) {
+                return 0;
+            }
+            long m = 2*(n - 1);
+            j %= m;
+            if (j < 0) {
+                j += m;
+            }
+            return j < n ? j : m - j;
+        "#,
+        PadMode::Replicate => "return j < 0 ? 0 : (j >= n ? n - 1 : j);",
+        PadMode::Circular => r#"
+            j %= n;
+            return j < 0 ? j + n : j;
+        "#,
+    }
+}
+
+/// Writes padded `src` tensor of specified dimensions to `dst`, `before` is the padding size at the beginning of each axis.
+pub(crate) fn pad<T: Prm + Interop>(src: &DeviceBuffer<T>, dst: &mut DeviceBuffer<T>, dims: &[usize], before: &[usize], out_dims: &[usize], mode: &PadMode<T>) {
+    assert_eq!(dims.len(), before.len());
+    assert_eq!(dims.len(), out_dims.len());
+    assert_eq!(dst.len(), out_dims.iter().product::<usize>());
+    assert!(src.context() == dst.context());
+    let context = src.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    let source = index_source(mode);
+    let program = context.program(&format!("pad {:?} {}", dt, source), || {
+        header(&[dt]) + &format!(r#"
+            long pad_index(long j, long n) {{
+                {source}
+            }}
+            __kernel void pad(__global const {dt} *src, __global {dt} *dst, __global const {dt} *p, __global const ulong *layout) {{
+                size_t o = get_global_id(0);
+                ulong rank = layout[0];
+                __global const ulong *dims = layout + 1, *before = dims + rank, *out_dims = before + rank;
+                ulong r = o, offset = 0, stride = 1;
+                for (ulong a = 0; a < rank; ++a) {{
+                    long x = (long)(r % out_dims[a]);
+                    r /= out_dims[a];
+                    long j = pad_index(x - (long)before[a], (long)dims[a]);
+                    if (j < 0) {{
+                        dst[o] = p[0];
+                        return;
+                    }}
+                    offset += (ulong)j*stride;
+                    stride *= dims[a];
+                }}
+                dst[o] = src[offset];
+            }}
+        "#, dt=dt.name(), source=source)
+    });
+    let value = match mode {
+        PadMode::Constant(value) => *value,
+        _ => T::zero(),
+    };
+    let p = params(&context, &[value]);
+    let layout = [dims.len()].iter().chain(dims).chain(before).chain(out_dims).map(|&x| x as u64).collect::<Vec<_>>();
+    let l = params(&context, &layout);
+    enqueue(
+        builder(&context, &program, "pad").arg(src.mem()).arg(dst.mem()).arg(p.mem()).arg(l.mem()),
+        &[dst.len()],
+    );
+}
@@ -0,0 +1,120 @@
+use crate::{
+    Prm, Interop, DevType,
+    Buffer, DeviceBuffer,
+};
+use super::{header, builder, enqueue, params, cast_expr, acc_type};
+
+
+/// Philox-4x32-10 generator, the same as [`Philox`](crate::Philox) on the host.
+const PHILOX_SOURCE: &str = r#"
+uint4 philox(uint4 ctr, uint2 key) {
+    for (int round = 0; round < 10; ++round) {
+        if (round > 0) {
+            key += (uint2)(0x9E3779B9, 0xBB67AE85);
+        }
+        uint hi0 = mul_hi(0xD2511F53u, ctr.x), lo0 = 0xD2511F53u*ctr.x;
+        uint hi1 = mul_hi(0xCD9E8D57u, ctr.z), lo1 = 0xCD9E8D57u*ctr.z;
+        ctr = (uint4)(hi1 ^ ctr.y ^ key.x, lo1, hi0 ^ ctr.w ^ key.y, lo0);
+    }
+    return ctr;
+}
+uint4 philox_block(size_t i, uint2 key) {
+    return philox((uint4)((uint)i, (uint)((ulong)i >> 32), 0, 0), key);
+}
+float unit_float(uint hi, uint lo) {
+    return (float)(hi >> 8)*(1.0f/16777216.0f);
+}
+"#;
+
+const UNIT_DOUBLE_SOURCE: &str = r#"
+double unit_double(uint hi, uint lo) {
+    return (double)(((((ulong)hi) << 32) | (ulong)lo) >> 11)*(1.0/9007199254740992.0);
+}
+"#;
+
+/// Program source with Philox generator and uniform distribution function for accumulator type.
+///
+/// Contraction into FMA is disabled to compute the same values as on the host.
+fn source(types: &[DevType], ct: DevType) -> String {
+    let mut src = header(types) + "#pragma OPENCL FP_CONTRACT OFF\n" + PHILOX_SOURCE;
+    if ct == DevType::Float(64) {
+        src += UNIT_DOUBLE_SOURCE;
+    }
+    src
+}
+
+/// Name of the constant `pi` for specified type.
+fn pi(ct: DevType) -> &'static str {
+    match ct {
+        DevType::Float(64) => "M_PI",
+        _ => "M_PI_F",
+    }
+}
+
+/// Kind of distribution of random values.
+pub(crate) enum Distribution<T> {
+    /// Uniform distribution in `[low, high)`, values rounded up to `high` are replaced with the last one.
+    Uniform(T, T, T),
+    /// Normal distribution with mean and standard deviation.
+    Normal(T, T),
+}
+
+/// Fills buffer with random values from specified distribution.
+pub(crate) fn random<T: Prm + Interop>(dst: &mut DeviceBuffer<T>, distr: Distribution<T>, seed: u64) {
+    let context = dst.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    let ct = acc_type(dt);
+    let (name, values) = match distr {
+        Distribution::Uniform(low, high, top) => ("uniform", vec![low, high, top]),
+        Distribution::Normal(mean, std) => ("normal", vec![mean, std]),
+    };
+    let program = context.program(&format!("random {:?}", dt), || {
+        source(&[dt, ct], ct) + &format!(r#"
+            __kernel void uniform(__global const {dt} *p, __global {dt} *dst, uint k0, uint k1) {{
+                size_t i = get_global_id(0);
+                uint4 b = philox_block(i, (uint2)(k0, k1));
+                {ct} low = {a}, high = {b};
+                {dt} x = {uniform};
+                dst[i] = {x} < high ? x : p[2];
+            }}
+            __kernel void normal(__global const {dt} *p, __global {dt} *dst, uint k0, uint k1) {{
+                size_t i = get_global_id(0);
+                uint4 b = philox_block(i, (uint2)(k0, k1));
+                {ct} mean = {a}, std = {b};
+                {ct} u1 = ({ct})1 - unit_{ct}(b.x, b.y), u2 = unit_{ct}(b.z, b.w);
+                dst[i] = {normal};
+            }}
+        "#,
+            dt=dt.name(), ct=ct.name(),
+            a=cast_expr(dt, ct, "p[0]"), b=cast_expr(dt, ct, "p[1]"),
+            x=cast_expr(dt, ct, "x"),
+            uniform=cast_expr(ct, dt, &format!("(low + (high - low)*unit_{}(b.x, b.y))", ct.name())),
+            normal=cast_expr(ct, dt, &format!(
+                "(mean + std*(sqrt(-({ct})2*log(u1))*cos(({ct})2*{pi}*u2)))", ct=ct.name(), pi=pi(ct),
+            )),
+        )
+    });
+    let p = params(&context, &values);
+    enqueue(
+        builder(&context, &program, name).arg(p.mem()).arg(dst.mem()).arg(seed as u32).arg((seed >> 32) as u32),
+        &[dst.len()],
+    );
+}
+
+/// Fills buffer with values that are `true` if the first value of corresponding block is less than threshold.
+pub(crate) fn bernoulli(dst: &mut DeviceBuffer<bool>, threshold: u64, seed: u64) {
+    let context = dst.context().clone();
+    let program = context.program("bernoulli", || {
+        source(&[], DevType::Float(32)) + r#"
+            __kernel void bernoulli(__global uchar *dst, ulong threshold, uint k0, uint k1) {
+                size_t i = get_global_id(0);
+                uint4 b = philox_block(i, (uint2)(k0, k1));
+                dst[i] = ((ulong)b.x < threshold) ? 0xFF : 0x00;
+            }
+        "#
+    });
+    enqueue(
+        builder(&context, &program, "bernoulli").arg(dst.mem()).arg(threshold).arg(seed as u32).arg((seed >> 32) as u32),
+        &[dst.len()],
+    );
+}
@@ -0,0 +1,161 @@
+use crate::{
+    Prm, Interop,
+    Buffer, DeviceBuffer,
+};
+use super::{header, builder, enqueue, params, cast_expr, acc_type, scan};
+
+
+/// Gathers values along the axis.
+///
+/// Source is split into `outer` blocks of `n` slices of `inner` values, the same for destination with `m` slices.
+/// If `select` is set then index contains `m` values applied to every block and slice position,
+/// otherwise index has the shape of destination. Out-of-range indices give zero values.
+pub(crate) fn gather<T: Prm + Interop>(
+    src: &DeviceBuffer<T>, index: &DeviceBuffer<usize>, dst: &mut DeviceBuffer<T>,
+    (inner, n, outer): (usize, usize, usize), m: usize, select: bool,
+) {
+    assert_eq!(src.len(), inner * n * outer);
+    assert_eq!(dst.len(), inner * m * outer);
+    assert_eq!(index.len(), if select { m } else { dst.len() });
+    assert!(src.context() == dst.context() && src.context() == index.context());
+    let context = src.context().clone();
+    let (dt, it) = (T::dev_type(context.size_repr()), usize::dev_type(context.size_repr()));
+    let program = context.program(&format!("gather {:?} {:?} {}", dt, it, select), || {
+        header(&[dt]) + &format!(r#"
+            __kernel void gather(
+                __global const {dt} *src, __global const {it} *idx, __global {dt} *dst,
+                __global const {dt} *p, ulong n
+            ) {{
+                size_t i = get_global_id(0), k = get_global_id(1), o = get_global_id(2);
+                size_t inner = get_global_size(0), m = get_global_size(1);
+                ulong j = idx[{pos}];
+                dst[i + inner*(k + m*o)] = j < n ? src[i + inner*(j + n*o)] : p[0];
+            }}
+        "#, dt=dt.name(), it=it.name(), pos=if select { "k" } else { "i + inner*(k + m*o)" })
+    });
+    let p = params(&context, &[T::zero()]);
+    enqueue(
+        builder(&context, &program, "gather")
+        .arg(src.mem()).arg(index.mem()).arg(dst.mem()).arg(p.mem()).arg(n as u64),
+        &[inner, m, outer],
+    );
+}
+
+/// Finds indices that are out of range of axis of size `n`, returns some of them if there are any.
+///
+/// Only the result is read back to the host, indices themselves stay on the device.
+pub(crate) fn find_out_of_range(index: &DeviceBuffer<usize>, n: usize) -> Option<usize> {
+    let context = index.context().clone();
+    let it = usize::dev_type(context.size_repr());
+    let program = context.program(&format!("out_of_range {:?}", it), || {
+        header(&[it]) + &format!(r#"
+            __kernel void out_of_range(__global const {it} *idx, __global ulong *found, ulong n) {{
+                size_t i = get_global_id(0);
+                if (idx[i] >= n) {{
+                    found[0] = 1;
+                    found[1] = idx[i];
+                }}
+            }}
+        "#, it=it.name())
+    });
+    let found = params(&context, &[0u64, 0]);
+    enqueue(
+        builder(&context, &program, "out_of_range").arg(index.mem()).arg(found.mem()).arg(n as u64),
+        &[index.len()],
+    );
+    let mut result = [0u64; 2];
+    found.load(&mut result);
+    // Work items with out-of-range indices race to write, so any of such indices is returned.
+    if result[0] != 0 { Some(result[1] as usize) } else { None }
+}
+
+/// Writes or adds values of `src` along the axis, it is the inverse of [`gather`] without `select`.
+///
+/// Each destination value is computed by a single work item that looks through all `m` source slices,
+/// so values with duplicate indices are processed in order without atomics.
+/// This costs `O(n*m)` operations per lane along the axis instead of `O(m)`. Out-of-range indices are ignored.
+pub(crate) fn scatter<T: Prm + Interop>(
+    dst: &mut DeviceBuffer<T>, index: &DeviceBuffer<usize>, src: &DeviceBuffer<T>,
+    (inner, n, outer): (usize, usize, usize), m: usize, add: bool,
+) {
+    assert_eq!(dst.len(), inner * n * outer);
+    assert_eq!(src.len(), inner * m * outer);
+    assert_eq!(index.len(), src.len());
+    assert!(src.context() == dst.context() && src.context() == index.context());
+    let context = src.context().clone();
+    let (dt, it) = (T::dev_type(context.size_repr()), usize::dev_type(context.size_repr()));
+    let ct = if add { acc_type(dt) } else { dt };
+    let program = context.program(&format!("scatter {:?} {:?} {}", dt, it, add), || {
+        header(&[dt, ct]) + &format!(r#"
+            __kernel void scatter(
+                __global {dt} *dst, __global const {it} *idx, __global const {dt} *src, ulong m
+            ) {{
+                size_t i = get_global_id(0), j = get_global_id(1), o = get_global_id(2);
+                size_t inner = get_global_size(0), n = get_global_size(1);
+                size_t d = i + inner*(j + n*o);
+                {ct} acc = {load};
+                for (ulong k = 0; k < m; ++k) {{
+                    size_t s = i + inner*(k + m*o);
+                    if (idx[s] == j) {{
+                        acc = {value};
+                    }}
+                }}
+                dst[d] = {store};
+            }}
+        "#,
+            dt=dt.name(), it=it.name(), ct=ct.name(),
+            load=cast_expr(dt, ct, "dst[d]"), store=cast_expr(ct, dt, "acc"),
+            value=if add {
+                format!("acc + {}", cast_expr(dt, ct, "src[s]"))
+            } else {
+                "src[s]".to_string()
+            },
+        )
+    });
+    enqueue(
+        builder(&context, &program, "scatter")
+        .arg(dst.mem()).arg(index.mem()).arg(src.mem()).arg(m as u64),
+        &[inner, n, outer],
+    );
+}
+
+/// Copies values of `src` where `mask` is set into a new buffer keeping their order.
+///
+/// Destination positions are found by cumulative sum of the mask on the device,
+/// only the number of selected values is read back to the host to allocate the destination.
+pub(crate) fn masked_select<T: Prm + Interop>(src: &DeviceBuffer<T>, mask: &DeviceBuffer<bool>) -> DeviceBuffer<T> {
+    assert_eq!(src.len(), mask.len());
+    assert!(src.context() == mask.context());
+    let len = src.len();
+    let context = src.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    let program = context.program(&format!("masked_select {:?}", dt), || {
+        header(&[dt]) + &format!(r#"
+            __kernel void mask_count(__global const uchar *mask, __global ulong *count) {{
+                size_t i = get_global_id(0);
+                count[i] = mask[i] != 0 ? 1 : 0;
+            }}
+            __kernel void compact(
+                __global const {dt} *src, __global const uchar *mask, __global const ulong *pos, __global {dt} *dst
+            ) {{
+                size_t i = get_global_id(0);
+                if (mask[i] != 0) {{
+                    dst[pos[i] - 1] = src[i];
+                }}
+            }}
+        "#, dt=dt.name())
+    });
+    let count = unsafe { DeviceBuffer::<u64>::new_uninit_in(&context, len) };
+    enqueue(builder(&context, &program, "mask_count").arg(mask.mem()).arg(count.mem()), &[len]);
+    let pos = scan(&count, (1, len, 1), false);
+    let mut total = [0u64];
+    if len > 0 {
+        pos.mem().read(&mut total[..]).offset(len - 1).enq().unwrap();
+    }
+    let dst = unsafe { DeviceBuffer::new_uninit_in(&context, total[0] as usize) };
+    enqueue(
+        builder(&context, &program, "compact").arg(src.mem()).arg(mask.mem()).arg(pos.mem()).arg(dst.mem()),
+        &[len],
+    );
+    dst
+}
@@ -0,0 +1,128 @@
+use crate::{
+    Prm, Interop, DevType,
+    Buffer, DeviceBuffer,
+};
+use super::{header, builder, enqueue, cast_expr, acc_type, scalar_type, mul_expr};
+
+
+/// Sorts values along the axis by bitonic network, `blocks` are `(inner, size, outer)` as returned by `axis_blocks`.
+///
+/// `dst` receives sorted values and `index` receives their positions along the axis in `src`.
+/// Equal values keep their relative order and NaN is greater than any other value, so result is the same as of stable sort.
+///
+/// Size of axis is virtually padded to the power of two by values that go after all others.
+/// Each merge starts with comparison of mirrored positions, so that all comparators are directed the same way
+/// and the padding never moves.
+pub(crate) fn sort<T: Prm + Interop>(
+    src: &DeviceBuffer<T>, dst: &mut DeviceBuffer<T>, index: &mut DeviceBuffer<usize>,
+    (inner, n, outer): (usize, usize, usize), descending: bool,
+) {
+    assert!(src.len() == inner * n * outer && dst.len() == src.len() && index.len() == src.len());
+    assert!(src.context() == dst.context() && index.context() == dst.context());
+    let context = dst.context().clone();
+    let (dt, it) = (T::dev_type(context.size_repr()), usize::dev_type(context.size_repr()));
+    let ct = acc_type(dt);
+    let program = context.program(&format!("sort {:?} {:?} {}", dt, it, descending), || {
+        let less = match scalar_type(ct) {
+            DevType::Float(_) => "(x < y) || (isnan(y) && !isnan(x))",
+            _ => "x < y",
+        };
+        let (a, b) = if descending { ("b", "a") } else { ("a", "b") };
+        header(&[dt, ct]) + &format!(r#"
+            int less({ct} x, {ct} y) {{
+                return {less};
+            }}
+            int before({ct} a, {it} ia, {ct} b, {it} ib) {{
+                return less({a}, {b}) || (!less({b}, {a}) && ia < ib);
+            }}
+            __kernel void sort_init(__global const {dt} *src, __global {dt} *dst, __global {it} *index, ulong n) {{
+                ulong k = get_global_id(0), x = get_global_id(1), o = get_global_id(2), inner = get_global_size(1);
+                ulong i = x + inner*(k + n*o);
+                dst[i] = src[i];
+                index[i] = ({it})k;
+            }}
+            __kernel void sort_step(__global {dt} *keys, __global {it} *index, ulong n, ulong j, ulong flip) {{
+                ulong t = get_global_id(0), x = get_global_id(1), o = get_global_id(2), inner = get_global_size(1);
+                ulong r = t % j, i = 2*j*(t/j) + r;
+                ulong p = flip ? i - r + 2*j - 1 - r : i + j;
+                if (p >= n) {{
+                    return;
+                }}
+                ulong u = x + inner*(i + n*o), v = x + inner*(p + n*o);
+                {dt} ka = keys[u], kb = keys[v];
+                {it} ia = index[u], ib = index[v];
+                if (before({kb}, ib, {ka}, ia)) {{
+                    keys[u] = kb;
+                    keys[v] = ka;
+                    index[u] = ib;
+                    index[v] = ia;
+                }}
+            }}
+        "#,
+            dt=dt.name(), ct=ct.name(), it=it.name(), less=less, a=a, b=b,
+            ka=cast_expr(dt, ct, "ka"), kb=cast_expr(dt, ct, "kb"),
+        )
+    });
+    enqueue(
+        builder(&context, &program, "sort_init").arg(src.mem()).arg(dst.mem()).arg(index.mem()).arg(n as u64),
+        &[n, inner, outer],
+    );
+    let size = n.next_power_of_two();
+    let mut k = 2;
+    while k <= size {
+        let mut j = k / 2;
+        let mut flip = true;
+        while j > 0 {
+            enqueue(
+                builder(&context, &program, "sort_step").arg(dst.mem()).arg(index.mem())
+                    .arg(n as u64).arg(j as u64).arg(flip as u64),
+                &[size / 2, inner, outer],
+            );
+            j /= 2;
+            flip = false;
+        }
+        k *= 2;
+    }
+}
+
+/// Computes cumulative sum or product along the axis, `blocks` are `(inner, size, outer)` as returned by `axis_blocks`.
+///
+/// Scan is performed in `log2(size)` passes, each of them combines values at distance doubled from the previous pass.
+pub(crate) fn scan<T: Prm + Interop>(src: &DeviceBuffer<T>, (inner, n, outer): (usize, usize, usize), product: bool) -> DeviceBuffer<T> {
+    assert_eq!(src.len(), inner * n * outer);
+    let context = src.context().clone();
+    let dt = T::dev_type(context.size_repr());
+    let ct = acc_type(dt);
+    let program = context.program(&format!("scan {:?} {}", dt, product), || {
+        let value = if product { mul_expr(ct, "a", "b") } else { "a + b".to_string() };
+        header(&[dt, ct]) + &format!(r#"
+            __kernel void scan_step(__global const {dt} *src, __global {dt} *dst, ulong n, ulong d) {{
+                ulong k = get_global_id(0), x = get_global_id(1), o = get_global_id(2), inner = get_global_size(1);
+                ulong i = x + inner*(k + n*o);
+                if (k < d) {{
+                    dst[i] = src[i];
+                    return;
+                }}
+                {ct} a = {a}, b = {b};
+                dst[i] = {store};
+            }}
+        "#,
+            dt=dt.name(), ct=ct.name(),
+            a=cast_expr(dt, ct, "src[i - inner*d]"), b=cast_expr(dt, ct, "src[i]"),
+            store=cast_expr(ct, dt, &format!("({})", value)),
+        )
+    });
+    let mut dst = unsafe { DeviceBuffer::new_uninit_in(&context, src.len()) };
+    dst.copy_from(src);
+    let mut tmp = unsafe { DeviceBuffer::new_uninit_in(&context, src.len()) };
+    let mut d = 1;
+    while d < n {
+        enqueue(
+            builder(&context, &program, "scan_step").arg(dst.mem()).arg(tmp.mem()).arg(n as u64).arg(d as u64),
+            &[n, inner, outer],
+        );
+        std::mem::swap(&mut dst, &mut tmp);
+        d *= 2;
+    }
+    dst
+}
@@ -1,14 +1,32 @@
 mod num;
-pub use num::Prm;
+pub use num::{Prm, Num, Float, Field};
 #[cfg(feature = "device")]
-pub use num::Interop;
+pub use num::{Interop, SizeRepr, DevType, OverflowError};
+
+mod cast;
+pub use cast::Cast;
+
+mod random;
+pub use random::{Philox, RandomFloat};
 
 mod buffer;
 pub(crate) use buffer::*;
+#[cfg(feature = "mmap")]
+pub use buffer::{MappedBuffer, MapMode};
 
 mod tensor;
 pub use tensor::*;
 
+#[cfg(feature = "device")]
+mod kernel;
+
+mod io;
+pub use io::*;
+
+mod interop;
+#[cfg(feature = "nalgebra")]
+pub use interop::ShapeMismatch;
+
 pub mod prelude {
     pub use crate::{
         Tensor as _,
@@ -1,5 +1,7 @@
 use num_traits as num;
 use num_complex::Complex;
+#[cfg(feature = "half")]
+use half::{f16, bf16};
 
 /// Analog of `num_traits::Zero` but also implemented for `bool` type.
 pub trait Zero {
@@ -14,7 +16,16 @@ pub trait One {
 pub trait Num: num::Num {}
 
 /// Wrapper for `num_traits::Float`.
-pub trait Float: Num + num::Float {}
+pub trait Float: Num + num::Float {
+    /// Type in which intermediate results of host computations are accumulated.
+    /// It is `f32` for half-precision types and `Self` for other ones.
+    type Acc: Float<Acc=Self::Acc>;
+
+    /// Convert value to accumulator type.
+    fn to_acc(self) -> Self::Acc;
+    /// Convert value from accumulator type.
+    fn from_acc(x: Self::Acc) -> Self;
+}
 
 impl Num for u8 {}
 impl Num for u16 {}
@@ -32,11 +43,108 @@ impl Num for isize {}
 impl Num for f32 {}
 impl Num for f64 {}
 
-impl Float for f32 {}
-impl Float for f64 {}
+#[cfg(feature = "half")]
+impl Num for f16 {}
+#[cfg(feature = "half")]
+impl Num for bf16 {}
+
+impl Float for f32 {
+    type Acc = f32;
+    fn to_acc(self) -> f32 {
+        self
+    }
+    fn from_acc(x: f32) -> Self {
+        x
+    }
+}
+impl Float for f64 {
+    type Acc = f64;
+    fn to_acc(self) -> f64 {
+        self
+    }
+    fn from_acc(x: f64) -> Self {
+        x
+    }
+}
+
+#[cfg(feature = "half")]
+impl Float for f16 {
+    type Acc = f32;
+    fn to_acc(self) -> f32 {
+        self.to_f32()
+    }
+    fn from_acc(x: f32) -> Self {
+        Self::from_f32(x)
+    }
+}
+#[cfg(feature = "half")]
+impl Float for bf16 {
+    type Acc = f32;
+    fn to_acc(self) -> f32 {
+        self.to_f32()
+    }
+    fn from_acc(x: f32) -> Self {
+        Self::from_f32(x)
+    }
+}
 
 impl<T: Float> Num for Complex<T> {}
 
+/// Floating-point or complex number, element type of linear algebra operations.
+pub trait Field: Num + Copy {
+    /// Type of real numbers, `Self` for floating-point types and type of components for complex ones.
+    type Real: Field<Real=Self::Real> + Float;
+
+    /// Complex conjugate, it does nothing for real numbers.
+    fn conj(self) -> Self;
+    /// Absolute value of real number or modulus of complex one.
+    fn abs(self) -> Self::Real;
+    /// Real part of number.
+    fn re(self) -> Self::Real;
+    /// Number with specified real part and zero imaginary part.
+    fn from_real(x: Self::Real) -> Self;
+}
+
+macro_rules! impl_field_real {
+    ($($t:ty),*) => {
+        $(
+            impl Field for $t {
+                type Real = $t;
+                fn conj(self) -> Self {
+                    self
+                }
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+                fn re(self) -> Self {
+                    self
+                }
+                fn from_real(x: Self) -> Self {
+                    x
+                }
+            }
+        )*
+    };
+}
+
+impl_field_real!(f32, f64);
+
+impl<T: Float + Field<Real=T>> Field for Complex<T> {
+    type Real = T;
+    fn conj(self) -> Self {
+        Complex::conj(&self)
+    }
+    fn abs(self) -> T {
+        self.norm()
+    }
+    fn re(self) -> T {
+        self.re
+    }
+    fn from_real(x: T) -> Self {
+        Complex::new(x, T::zero())
+    }
+}
+
 
 impl<T: Num> Zero for T {
     fn zero() -> Self {
@@ -55,7 +163,7 @@ impl<T: Num> One for T {
 }
 impl One for bool {
     fn one() -> Self {
-        false
+        true
     }
 }
 
@@ -71,127 +179,402 @@ impl Prm for bool {}
 #[cfg(feature = "device")]
 mod interop {
     use super::*;
-    use std::mem::transmute;
+    use std::{
+        convert::TryFrom,
+        fmt,
+        error::Error,
+        mem::transmute,
+    };
     use ocl::{OclPrm, Buffer};
     use num_complex_v01::{Complex as ComplexV01};
+    #[cfg(feature = "half")]
+    use half::slice::HalfFloatSliceExt;
+
+
+    /// Device representation of `usize` and `isize` values.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum SizeRepr {
+        /// Sizes are stored as `uint`/`int` (32 bits).
+        U32,
+        /// Sizes are stored as `ulong`/`long` (64 bits).
+        U64,
+    }
+
+    impl SizeRepr {
+        /// Selects representation according to `CL_DEVICE_ADDRESS_BITS`.
+        pub fn from_address_bits(bits: u32) -> Self {
+            if bits > 32 {
+                SizeRepr::U64
+            } else {
+                SizeRepr::U32
+            }
+        }
+        /// Number of bits in representation.
+        pub fn bits(self) -> u32 {
+            match self {
+                SizeRepr::U32 => 32,
+                SizeRepr::U64 => 64,
+            }
+        }
+        /// Number of 32-bit words that a single value occupies.
+        pub fn words(self) -> usize {
+            (self.bits() / 32) as usize
+        }
+    }
+
+    /// Value cannot be represented on the host or on the device without truncation.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct OverflowError {
+        /// Position of the first value that caused an overflow.
+        pub index: usize,
+        /// Device representation of sizes that was used.
+        pub repr: SizeRepr,
+    }
+
+    impl fmt::Display for OverflowError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(
+                f, "value at position {} does not fit into {}-bit size representation",
+                self.index, self.repr.bits(),
+            )
+        }
+    }
+
+    impl Error for OverflowError {}
+
+
+    /// Description of value type as it is seen by device kernels.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum DevType {
+        /// `bool` stored as `uchar`, where `0xFF` is `true` and `0x00` is `false`.
+        Bool,
+        /// Unsigned integer with specified number of bits.
+        Uint(u32),
+        /// Signed integer with specified number of bits.
+        Int(u32),
+        /// IEEE 754 floating-point number with specified number of bits.
+        Float(u32),
+        /// `bfloat16` stored as raw bits in `ushort`.
+        BFloat16,
+        /// Complex number with floating-point components of specified number of bits.
+        Complex(u32),
+    }
+
+    impl DevType {
+        /// Name of the OpenCL type.
+        pub fn name(self) -> &'static str {
+            match self {
+                DevType::Bool => "uchar",
+                DevType::Uint(8) => "uchar",
+                DevType::Uint(16) => "ushort",
+                DevType::Uint(32) => "uint",
+                DevType::Uint(64) => "ulong",
+                DevType::Int(8) => "char",
+                DevType::Int(16) => "short",
+                DevType::Int(32) => "int",
+                DevType::Int(64) => "long",
+                DevType::Float(16) => "half",
+                DevType::Float(32) => "float",
+                DevType::Float(64) => "double",
+                DevType::BFloat16 => "ushort",
+                DevType::Complex(32) => "float2",
+                DevType::Complex(64) => "double2",
+                _ => panic!("There is no OpenCL type for {:?}", self),
+            }
+        }
+        /// OpenCL extension that device must support to run kernels operating on this type.
+        pub fn extension(self) -> Option<&'static str> {
+            match self {
+                DevType::Float(16) => Some("cl_khr_fp16"),
+                DevType::Float(64) | DevType::Complex(64) => Some("cl_khr_fp64"),
+                _ => None,
+            }
+        }
+    }
 
 
     /// Types that can be transformed from host representation to device one and back.
+    ///
+    /// Conversion works on slices because single host value may occupy
+    /// several `Dev` units on the device (see [`SizeRepr`]).
     pub trait Interop: Copy {
+        /// Unit of device storage.
         type Dev: OclPrm + Copy;
 
-        /// Transform from host to device type.
-        fn to_dev(self) -> Self::Dev;
-        /// Transform from device to host type.
-        fn from_dev(x: Self::Dev) -> Self;
+        /// Description of the device type that is used to generate kernels.
+        fn dev_type(repr: SizeRepr) -> DevType;
+
+        /// Number of `Dev` units that a single value occupies on the device.
+        fn dev_len(_repr: SizeRepr) -> usize {
+            1
+        }
+
+        /// Transform from host to device representation.
+        fn to_dev(src: &[Self], dst: &mut [Self::Dev], repr: SizeRepr) -> Result<(), OverflowError>;
+        /// Transform from device to host representation.
+        fn from_dev(src: &[Self::Dev], dst: &mut [Self], repr: SizeRepr) -> Result<(), OverflowError>;
 
         /// Copy data from OpenCL buffer to host slice.
-        fn load_from_buffer(dst: &mut [Self], src: &Buffer<Self::Dev>) {
-            assert_eq!(dst.len(), src.len());
-            let mut tmp = Vec::<Self::Dev>::new();
+        fn load_from_buffer(dst: &mut [Self], src: &Buffer<Self::Dev>, repr: SizeRepr) -> Result<(), OverflowError> {
+            assert_eq!(dst.len() * Self::dev_len(repr), src.len());
+            let mut tmp = vec![Self::Dev::default(); src.len()];
             src.read(&mut tmp).enq().unwrap();
-            for (d, &s) in dst.iter_mut().zip(tmp.iter()) {
-                *d = Self::from_dev(s);
-            }
+            Self::from_dev(&tmp, dst, repr)
         }
 
         /// Copy data from host slice to OpenCL buffer.
-        fn store_to_buffer(dst: &mut Buffer<Self::Dev>, src: &[Self]) {
-            assert_eq!(dst.len(), src.len());
-            let tmp = src.iter().map(|x| x.to_dev()).collect::<Vec<_>>();
+        fn store_to_buffer(dst: &mut Buffer<Self::Dev>, src: &[Self], repr: SizeRepr) -> Result<(), OverflowError> {
+            assert_eq!(src.len() * Self::dev_len(repr), dst.len());
+            let mut tmp = vec![Self::Dev::default(); dst.len()];
+            Self::to_dev(src, &mut tmp, repr)?;
             dst.write(&tmp).enq().unwrap();
+            Ok(())
         }
     }
 
     /// Type which representation remains the same for both host and device.
-    pub trait IdentInterop: Interop<Dev=Self> + OclPrm {}
+    pub trait IdentInterop: Interop<Dev=Self> + OclPrm {
+        const DEV_TYPE: DevType;
+    }
 
     impl <T: IdentInterop> Interop for T {
         type Dev = Self;
 
-        fn to_dev(self) -> Self::Dev {
-            self
+        fn dev_type(_: SizeRepr) -> DevType {
+            T::DEV_TYPE
         }
-        fn from_dev(x: Self::Dev) -> Self {
-            x
+
+        fn to_dev(src: &[Self], dst: &mut [Self::Dev], _: SizeRepr) -> Result<(), OverflowError> {
+            dst.copy_from_slice(src);
+            Ok(())
         }
-        fn load_from_buffer(dst: &mut [Self], src: &Buffer<Self::Dev>) {
+        fn from_dev(src: &[Self::Dev], dst: &mut [Self], _: SizeRepr) -> Result<(), OverflowError> {
+            dst.copy_from_slice(src);
+            Ok(())
+        }
+        fn load_from_buffer(dst: &mut [Self], src: &Buffer<Self::Dev>, _: SizeRepr) -> Result<(), OverflowError> {
             assert_eq!(dst.len(), src.len());
             src.read(dst).enq().unwrap();
+            Ok(())
         }
-        fn store_to_buffer(dst: &mut Buffer<Self::Dev>, src: &[Self]) {
+        fn store_to_buffer(dst: &mut Buffer<Self::Dev>, src: &[Self], _: SizeRepr) -> Result<(), OverflowError> {
             assert_eq!(dst.len(), src.len());
             dst.write(src).enq().unwrap();
+            Ok(())
         }
     }
 
     impl Interop for bool {
         type Dev = u8;
-        fn to_dev(self) -> Self::Dev {
-            if self {
-                0xFF
-            } else {
-                0x00
+        fn dev_type(_: SizeRepr) -> DevType {
+            DevType::Bool
+        }
+        fn to_dev(src: &[Self], dst: &mut [Self::Dev], _: SizeRepr) -> Result<(), OverflowError> {
+            assert_eq!(dst.len(), src.len());
+            for (d, &s) in dst.iter_mut().zip(src.iter()) {
+                *d = if s { 0xFF } else { 0x00 };
             }
+            Ok(())
         }
-        fn from_dev(x: Self::Dev) -> Self {
-            x != 0
+        fn from_dev(src: &[Self::Dev], dst: &mut [Self], _: SizeRepr) -> Result<(), OverflowError> {
+            assert_eq!(dst.len(), src.len());
+            for (d, &s) in dst.iter_mut().zip(src.iter()) {
+                *d = s != 0;
+            }
+            Ok(())
         }
     }
 
-    impl IdentInterop for u8 {}
-    impl IdentInterop for u16 {}
-    impl IdentInterop for u32 {}
-    impl IdentInterop for u64 {}
+    impl IdentInterop for u8 { const DEV_TYPE: DevType = DevType::Uint(8); }
+    impl IdentInterop for u16 { const DEV_TYPE: DevType = DevType::Uint(16); }
+    impl IdentInterop for u32 { const DEV_TYPE: DevType = DevType::Uint(32); }
+    impl IdentInterop for u64 { const DEV_TYPE: DevType = DevType::Uint(64); }
 
-    impl IdentInterop for i8 {}
-    impl IdentInterop for i16 {}
-    impl IdentInterop for i32 {}
-    impl IdentInterop for i64 {}
+    impl IdentInterop for i8 { const DEV_TYPE: DevType = DevType::Int(8); }
+    impl IdentInterop for i16 { const DEV_TYPE: DevType = DevType::Int(16); }
+    impl IdentInterop for i32 { const DEV_TYPE: DevType = DevType::Int(32); }
+    impl IdentInterop for i64 { const DEV_TYPE: DevType = DevType::Int(64); }
 
-    impl IdentInterop for f32 {}
-    impl IdentInterop for f64 {}
+    impl IdentInterop for f32 { const DEV_TYPE: DevType = DevType::Float(32); }
+    impl IdentInterop for f64 { const DEV_TYPE: DevType = DevType::Float(64); }
 
-    impl Interop for usize {
-        type Dev = u32;
-        fn to_dev(self) -> Self::Dev {
-            self as Self::Dev
-        }
-        fn from_dev(x: Self::Dev) -> Self {
-            x as Self
-        }
+    /// Sizes are stored on the device as sequences of 32-bit words in native byte order.
+    macro_rules! impl_size_interop {
+        ($host:ty, $dev:ident, $dev32:ty, $dev64:ty) => {
+            impl Interop for $host {
+                type Dev = u32;
+
+                fn dev_type(repr: SizeRepr) -> DevType {
+                    DevType::$dev(repr.bits())
+                }
+
+                fn dev_len(repr: SizeRepr) -> usize {
+                    repr.words()
+                }
+
+                fn to_dev(src: &[Self], dst: &mut [Self::Dev], repr: SizeRepr) -> Result<(), OverflowError> {
+                    assert_eq!(src.len() * repr.words(), dst.len());
+                    let overflow = |index| OverflowError { index, repr };
+                    match repr {
+                        SizeRepr::U32 => for (i, (d, &s)) in dst.iter_mut().zip(src.iter()).enumerate() {
+                            let x = <$dev32>::try_from(s).map_err(|_| overflow(i))?;
+                            *d = u32::from_ne_bytes(x.to_ne_bytes());
+                        },
+                        SizeRepr::U64 => for (i, (d, &s)) in dst.chunks_exact_mut(2).zip(src.iter()).enumerate() {
+                            let b = <$dev64>::try_from(s).map_err(|_| overflow(i))?.to_ne_bytes();
+                            d[0] = u32::from_ne_bytes([b[0], b[1], b[2], b[3]]);
+                            d[1] = u32::from_ne_bytes([b[4], b[5], b[6], b[7]]);
+                        },
+                    }
+                    Ok(())
+                }
+                fn from_dev(src: &[Self::Dev], dst: &mut [Self], repr: SizeRepr) -> Result<(), OverflowError> {
+                    assert_eq!(dst.len() * repr.words(), src.len());
+                    let overflow = |index| OverflowError { index, repr };
+                    match repr {
+                        SizeRepr::U32 => for (i, (d, &s)) in dst.iter_mut().zip(src.iter()).enumerate() {
+                            let x = <$dev32>::from_ne_bytes(s.to_ne_bytes());
+                            *d = Self::try_from(x).map_err(|_| overflow(i))?;
+                        },
+                        SizeRepr::U64 => for (i, (d, s)) in dst.iter_mut().zip(src.chunks_exact(2)).enumerate() {
+                            let (l, h) = (s[0].to_ne_bytes(), s[1].to_ne_bytes());
+                            let x = <$dev64>::from_ne_bytes([l[0], l[1], l[2], l[3], h[0], h[1], h[2], h[3]]);
+                            *d = Self::try_from(x).map_err(|_| overflow(i))?;
+                        },
+                    }
+                    Ok(())
+                }
+            }
+        };
     }
-    impl Interop for isize {
-        type Dev = i32;
-        fn to_dev(self) -> Self::Dev {
-            self as Self::Dev
-        }
-        fn from_dev(x: Self::Dev) -> Self {
-            x as Self
-        }
+
+    impl_size_interop!(usize, Uint, u32, u64);
+    impl_size_interop!(isize, Int, i32, i64);
+
+    /// Half-precision types are stored on the device as raw bits (`cl_half`).
+    #[cfg(feature = "half")]
+    macro_rules! impl_half_interop {
+        ($host:ty, $dev:expr) => {
+            impl Interop for $host {
+                type Dev = u16;
+
+                fn dev_type(_: SizeRepr) -> DevType {
+                    $dev
+                }
+
+                fn to_dev(src: &[Self], dst: &mut [Self::Dev], _: SizeRepr) -> Result<(), OverflowError> {
+                    dst.copy_from_slice(src.reinterpret_cast());
+                    Ok(())
+                }
+                fn from_dev(src: &[Self::Dev], dst: &mut [Self], _: SizeRepr) -> Result<(), OverflowError> {
+                    dst.reinterpret_cast_mut().copy_from_slice(src);
+                    Ok(())
+                }
+                fn load_from_buffer(dst: &mut [Self], src: &Buffer<Self::Dev>, _: SizeRepr) -> Result<(), OverflowError> {
+                    assert_eq!(dst.len(), src.len());
+                    src.read(dst.reinterpret_cast_mut()).enq().unwrap();
+                    Ok(())
+                }
+                fn store_to_buffer(dst: &mut Buffer<Self::Dev>, src: &[Self], _: SizeRepr) -> Result<(), OverflowError> {
+                    assert_eq!(dst.len(), src.len());
+                    dst.write(src.reinterpret_cast()).enq().unwrap();
+                    Ok(())
+                }
+            }
+        };
     }
 
-    impl<T: Float> Interop for Complex<T> where ComplexV01<T>: OclPrm {
+    #[cfg(feature = "half")]
+    impl_half_interop!(f16, DevType::Float(16));
+    // There is no `bfloat16` type in OpenCL, so kernels have to unpack raw bits by themselves.
+    #[cfg(feature = "half")]
+    impl_half_interop!(bf16, DevType::BFloat16);
+
+    impl<T: Float + Interop> Interop for Complex<T> where ComplexV01<T>: OclPrm {
         type Dev = ComplexV01<T>;
-        fn to_dev(self) -> Self::Dev {
-            Self::Dev::new(self.re, self.im)
+        fn dev_type(repr: SizeRepr) -> DevType {
+            match T::dev_type(repr) {
+                DevType::Float(bits) => DevType::Complex(bits),
+                other => panic!("Cannot make complex device type from {:?}", other),
+            }
         }
-        fn from_dev(x: Self::Dev) -> Self {
-            Self::new(x.re, x.im)
+        fn to_dev(src: &[Self], dst: &mut [Self::Dev], _: SizeRepr) -> Result<(), OverflowError> {
+            assert_eq!(dst.len(), src.len());
+            for (d, s) in dst.iter_mut().zip(src.iter()) {
+                *d = Self::Dev::new(s.re, s.im);
+            }
+            Ok(())
         }
-        fn load_from_buffer(dst: &mut [Self], src: &Buffer<Self::Dev>) {
+        fn from_dev(src: &[Self::Dev], dst: &mut [Self], _: SizeRepr) -> Result<(), OverflowError> {
+            assert_eq!(dst.len(), src.len());
+            for (d, s) in dst.iter_mut().zip(src.iter()) {
+                *d = Self::new(s.re, s.im);
+            }
+            Ok(())
+        }
+        fn load_from_buffer(dst: &mut [Self], src: &Buffer<Self::Dev>, _: SizeRepr) -> Result<(), OverflowError> {
             assert_eq!(dst.len(), src.len());
             src.read(
                 unsafe { transmute::<_, &mut [Self::Dev]>(dst) }
             ).enq().unwrap();
+            Ok(())
         }
-        fn store_to_buffer(dst: &mut Buffer<Self::Dev>, src: &[Self]) {
+        fn store_to_buffer(dst: &mut Buffer<Self::Dev>, src: &[Self], _: SizeRepr) -> Result<(), OverflowError> {
             assert_eq!(dst.len(), src.len());
             dst.write(
                 unsafe { transmute::<_, &[Self::Dev]>(src) }
             ).enq().unwrap();
+            Ok(())
         }
     }
 }
 #[cfg(feature = "device")]
 pub use interop::*;
+
+#[cfg(all(test, feature = "device"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_u32_overflow() {
+        let mut dst = [0u32; 3];
+        assert_eq!(usize::to_dev(&[1, 2, 3], &mut dst, SizeRepr::U32), Ok(()));
+        assert_eq!(dst, [1, 2, 3]);
+        let err = usize::to_dev(&[5, 1 << 32, 7], &mut dst, SizeRepr::U32);
+        assert_eq!(err, Err(OverflowError { index: 1, repr: SizeRepr::U32 }));
+
+        let mut back = [0usize; 2];
+        assert_eq!(usize::from_dev(&[3, u32::MAX], &mut back, SizeRepr::U32), Ok(()));
+        assert_eq!(back, [3, u32::MAX as usize]);
+    }
+
+    #[test]
+    fn size_negative() {
+        let mut dst = [0u32; 3];
+        assert_eq!(isize::to_dev(&[-5, i32::MIN as isize, 7], &mut dst, SizeRepr::U32), Ok(()));
+        let mut back = [0isize; 3];
+        assert_eq!(isize::from_dev(&dst, &mut back, SizeRepr::U32), Ok(()));
+        assert_eq!(back, [-5, i32::MIN as isize, 7]);
+        let err = isize::to_dev(&[0, 0, i32::MIN as isize - 1], &mut dst, SizeRepr::U32);
+        assert_eq!(err, Err(OverflowError { index: 2, repr: SizeRepr::U32 }));
+    }
+
+    #[test]
+    fn size_u64_round_trip() {
+        let src = [0, 1, 0xDEAD_BEEF_0123_4567, usize::MAX];
+        let mut dst = [0u32; 8];
+        assert_eq!(usize::to_dev(&src, &mut dst, SizeRepr::U64), Ok(()));
+        let words = if cfg!(target_endian = "little") { [0x0123_4567, 0xDEAD_BEEF] } else { [0xDEAD_BEEF, 0x0123_4567] };
+        assert_eq!(dst[4..6], words);
+        let mut back = [0usize; 4];
+        assert_eq!(usize::from_dev(&dst, &mut back, SizeRepr::U64), Ok(()));
+        assert_eq!(back, src);
+
+        let src = [isize::MIN, -1, 0, isize::MAX];
+        let mut dst = [0u32; 8];
+        assert_eq!(isize::to_dev(&src, &mut dst, SizeRepr::U64), Ok(()));
+        let mut back = [0isize; 4];
+        assert_eq!(isize::from_dev(&dst, &mut back, SizeRepr::U64), Ok(()));
+        assert_eq!(back, src);
+    }
+}
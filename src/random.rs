@@ -0,0 +1,234 @@
+#[cfg(feature = "rand")]
+use rand::{RngCore, SeedableRng, Error as RandError};
+use num_traits::FloatConst;
+use crate::Float;
+
+
+const PHILOX_M: [u32; 2] = [0xD2511F53, 0xCD9E8D57];
+const PHILOX_W: [u32; 2] = [0x9E3779B9, 0xBB67AE85];
+const PHILOX_ROUNDS: usize = 10;
+
+/// Philox-4x32-10 bijection of 128-bit counter with 64-bit key.
+fn philox(mut ctr: [u32; 4], mut key: [u32; 2]) -> [u32; 4] {
+    for round in 0..PHILOX_ROUNDS {
+        if round > 0 {
+            key[0] = key[0].wrapping_add(PHILOX_W[0]);
+            key[1] = key[1].wrapping_add(PHILOX_W[1]);
+        }
+        let p0 = (PHILOX_M[0] as u64) * (ctr[0] as u64);
+        let p1 = (PHILOX_M[1] as u64) * (ctr[2] as u64);
+        ctr = [
+            ((p1 >> 32) as u32) ^ ctr[1] ^ key[0],
+            p1 as u32,
+            ((p0 >> 32) as u32) ^ ctr[3] ^ key[1],
+            p0 as u32,
+        ];
+    }
+    ctr
+}
+
+/// Philox-4x32-10 counter-based random number generator.
+///
+/// Every block of four 32-bit values is computed from its index and the seed independently,
+/// so any part of the stream can be generated in parallel.
+/// Random tensors use a block per element: `i`-th element (in flattened order) is computed from `i`-th block.
+/// Device kernels implement the same algorithm, so host and device produce identical streams for the same seed.
+#[derive(Clone, Debug)]
+pub struct Philox {
+    key: [u32; 2],
+    #[cfg(feature = "rand")]
+    counter: u64,
+    #[cfg(feature = "rand")]
+    block: [u32; 4],
+    #[cfg(feature = "rand")]
+    index: usize,
+}
+
+impl Philox {
+    /// Create generator with specified seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            key: [seed as u32, (seed >> 32) as u32],
+            #[cfg(feature = "rand")]
+            counter: 0,
+            #[cfg(feature = "rand")]
+            block: [0; 4],
+            #[cfg(feature = "rand")]
+            index: 4,
+        }
+    }
+
+    /// Compute block of random values with specified index.
+    pub fn block(&self, index: u64) -> [u32; 4] {
+        philox([index as u32, (index >> 32) as u32, 0, 0], self.key)
+    }
+}
+
+#[cfg(feature = "rand")]
+impl RngCore for Philox {
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= 4 {
+            self.block = self.block(self.counter);
+            self.counter = self.counter.wrapping_add(1);
+            self.index = 0;
+        }
+        let value = self.block[self.index];
+        self.index += 1;
+        value
+    }
+    fn next_u64(&mut self) -> u64 {
+        let lo = self.next_u32() as u64;
+        let hi = self.next_u32() as u64;
+        (hi << 32) | lo
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), RandError> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rand")]
+impl SeedableRng for Philox {
+    type Seed = [u8; 8];
+    fn from_seed(seed: [u8; 8]) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+    fn seed_from_u64(seed: u64) -> Self {
+        Self::new(seed)
+    }
+}
+
+/// Floating-point type in which random values are produced from Philox blocks.
+pub trait RandomFloat: Float<Acc=Self> + FloatConst {
+    /// Uniformly distributed value in `[0, 1)` made of two 32-bit random values.
+    fn unit(hi: u32, lo: u32) -> Self;
+}
+
+impl RandomFloat for f32 {
+    fn unit(hi: u32, _: u32) -> Self {
+        (hi >> 8) as f32 * (1.0 / (1u32 << 24) as f32)
+    }
+}
+impl RandomFloat for f64 {
+    fn unit(hi: u32, lo: u32) -> Self {
+        ((((hi as u64) << 32) | (lo as u64)) >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+}
+
+/// Uniformly distributed value in `[0, 1)`.
+pub(crate) fn uniform<T: RandomFloat>(block: [u32; 4]) -> T {
+    T::unit(block[0], block[1])
+}
+
+/// Checks that `[low, high)` is a non-empty range with finite bounds.
+pub(crate) fn check_bounds<T: Float>(low: T, high: T) {
+    assert!(low.is_finite() && high.is_finite(), "Bounds of uniform distribution must be finite");
+    assert!(low < high, "Lower bound of uniform distribution must be less than upper one");
+}
+
+/// Largest value of `T` that is less than `x`.
+///
+/// Uniform values that are rounded up to the upper bound are replaced with it to keep them in `[low, high)`.
+pub(crate) fn below<T: Float>(x: T) -> T {
+    let two = T::one() + T::one();
+    let mut y = x - (x.abs()*T::epsilon()).max(T::min_positive_value());
+    loop {
+        let mid = y + (x - y)/two;
+        if y < mid && mid < x {
+            y = mid;
+        } else {
+            return y;
+        }
+    }
+}
+
+/// Normally distributed value with zero mean and unit variance (Box-Muller transform).
+///
+/// Note that host and device implementations of `log` and `cos` may differ in the last bits,
+/// so normal values are equal only up to rounding errors.
+pub(crate) fn normal<T: RandomFloat>(block: [u32; 4]) -> T {
+    let two = T::one() + T::one();
+    let u1 = T::one() - T::unit(block[0], block[1]);
+    let u2 = T::unit(block[2], block[3]);
+    (-two*u1.ln()).sqrt() * (two*T::PI()*u2).cos()
+}
+
+/// Threshold for the first value of a block to get `true` with probability `p`.
+pub(crate) fn bernoulli_threshold(p: f64) -> u64 {
+    assert!((0.0..=1.0).contains(&p), "Probability must be in [0, 1] range");
+    (p * (1u64 << 32) as f64) as u64
+}
+
+/// Bernoulli-distributed value for the threshold computed by [`bernoulli_threshold`].
+pub(crate) fn bernoulli(block: [u32; 4], threshold: u64) -> bool {
+    (block[0] as u64) < threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn philox_() {
+        // Known answers from Random123 library.
+        assert_eq!(
+            philox([0; 4], [0; 2]),
+            [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8],
+        );
+        assert_eq!(
+            philox([0xffffffff; 4], [0xffffffff; 2]),
+            [0x408f276d, 0x41c83b0e, 0xa20bc7c6, 0x6d5451fd],
+        );
+        assert_eq!(
+            philox([0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344], [0xa4093822, 0x299f31d0]),
+            [0xd16cfe09, 0x94fdcceb, 0x5001e420, 0x24126ea1],
+        );
+    }
+
+    #[test]
+    fn block() {
+        let gen = Philox::new(0x299f31d0_a4093822);
+        assert_eq!(gen.block(0x85a308d3_243f6a88), philox([0x243f6a88, 0x85a308d3, 0, 0], [0xa4093822, 0x299f31d0]));
+    }
+
+    #[test]
+    fn below_() {
+        assert_eq!(below(1.0f32), 1.0 - f32::EPSILON/2.0);
+        assert_eq!(below(3.0f64), 3.0 - 2.0*f64::EPSILON);
+        assert_eq!(below(-1.0f32), -1.0 - f32::EPSILON);
+        assert_eq!(below(0.0f32), -f32::from_bits(1));
+        assert_eq!(below(f32::from_bits(1)), 0.0);
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn below_half() {
+        use half::{f16, bf16};
+        assert_eq!(below(f16::ONE), f16::from_bits(f16::ONE.to_bits() - 1));
+        assert_eq!(below(bf16::from_f32(-2.0)), bf16::from_bits(bf16::from_f32(-2.0).to_bits() + 1));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn rng() {
+        let mut gen = Philox::seed_from_u64(1);
+        let blocks = [gen.block(0), gen.block(1)];
+        for i in 0..8 {
+            assert_eq!(gen.next_u32(), blocks[i / 4][i % 4]);
+        }
+    }
+
+    #[test]
+    fn unit() {
+        assert_eq!(f32::unit(0, 0), 0.0);
+        assert!(f32::unit(u32::MAX, u32::MAX) < 1.0);
+        assert!(f64::unit(u32::MAX, u32::MAX) < 1.0);
+        assert!(normal::<f64>([u32::MAX, u32::MAX, 0, 0]).is_finite());
+    }
+}
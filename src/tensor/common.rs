@@ -24,7 +24,7 @@ impl<T: Prm, Buf: Buffer<T>> CommonTensor<T, Buf> {
     }
     /// Create tensor from specified buffer and shape
     pub fn from_buffer(buffer: Buf, shape: &Shape) -> Self {
-        assert_eq!(buffer.len(), shape.iter().product());
+        assert_eq!(buffer.len(), shape.content());
         Self::from_shared_buffer(Rc::new(buffer), shape)
     }
 
@@ -36,6 +36,10 @@ impl<T: Prm, Buf: Buffer<T>> CommonTensor<T, Buf> {
     pub fn buffer_mut(&mut self) -> &mut Buf {
         Rc::make_mut(&mut self.buffer)
     }
+    /// Extracts inner buffer, clones it if it is shared.
+    pub fn into_buffer(self) -> Buf {
+        Rc::try_unwrap(self.buffer).unwrap_or_else(|rc| rc.as_ref().clone())
+    }
 }
 
 impl<T: Prm, Buf: Buffer<T>> Tensor<T> for CommonTensor<T, Buf> {
@@ -56,6 +60,9 @@ impl<T: Prm, Buf: Buffer<T>> Tensor<T> for CommonTensor<T, Buf> {
     fn new_zeroed_in(context: &Buf::Context, shape: &Shape) -> Self {
         Self::new_filled_in(context, shape, T::zero())
     }
+    fn new_ones_in(context: &Buf::Context, shape: &Shape) -> Self {
+        Self::new_filled_in(context, shape, T::one())
+    }
 
     fn shape(&self) -> &Shape {
         &self.shape
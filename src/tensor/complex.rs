@@ -0,0 +1,158 @@
+use num_complex::Complex;
+use crate::{Prm, Float, Shape, Tensor, HostTensor};
+#[cfg(feature = "device")]
+use crate::{Interop, Buffer, DeviceBuffer, DeviceTensor, kernel::{self, ComplexOp}};
+
+
+/// Checks that tensors of real values have the same shape.
+fn check_parts(a: &Shape, b: &Shape) {
+    assert_eq!(a, b, "Shapes of parts {} and {} differ", a, b);
+}
+
+impl<T: Float> HostTensor<Complex<T>> {
+    /// Applies function to each element.
+    fn map<U: Prm, F: Fn(Complex<T>) -> U>(&self, f: F) -> HostTensor<U> {
+        HostTensor::from_vec(self.shape(), self.buffer().as_slice().iter().map(|&x| f(x)).collect())
+    }
+
+    /// Complex conjugate of each element.
+    pub fn conj(&self) -> Self {
+        self.map(|x| x.conj())
+    }
+    /// Modulus of each element.
+    pub fn norm(&self) -> HostTensor<T> {
+        self.map(|x| x.norm())
+    }
+    /// Modulus of each element, the same as [`HostTensor::norm`].
+    pub fn abs(&self) -> HostTensor<T> {
+        self.norm()
+    }
+    /// Argument of each element in range `[-pi, pi]`.
+    pub fn arg(&self) -> HostTensor<T> {
+        self.map(|x| x.arg())
+    }
+
+    /// Real part of each element.
+    ///
+    /// Values are copied, because tensor cannot refer to every other value of buffer yet.
+    pub fn real(&self) -> HostTensor<T> {
+        self.map(|x| x.re)
+    }
+    /// Imaginary part of each element, values are copied as in [`HostTensor::real`].
+    pub fn imag(&self) -> HostTensor<T> {
+        self.map(|x| x.im)
+    }
+
+    /// Create tensor from real and imaginary parts of the same shape.
+    pub fn from_parts(re: &HostTensor<T>, im: &HostTensor<T>) -> Self {
+        check_parts(re.shape(), im.shape());
+        let values = re.buffer().as_slice().iter().zip(im.buffer().as_slice()).map(|(&a, &b)| Complex::new(a, b));
+        Self::from_vec(re.shape(), values.collect())
+    }
+    /// Create tensor from modulus and argument of the same shape.
+    pub fn from_polar(r: &HostTensor<T>, theta: &HostTensor<T>) -> Self {
+        check_parts(r.shape(), theta.shape());
+        let values = r.buffer().as_slice().iter().zip(theta.buffer().as_slice()).map(|(&a, &b)| Complex::from_polar(a, b));
+        Self::from_vec(r.shape(), values.collect())
+    }
+}
+
+#[cfg(feature = "device")]
+impl<T: Float + Interop> DeviceTensor<Complex<T>> where Complex<T>: Interop {
+    /// Applies operation to each element on the device or on the host if the device does not support the type.
+    fn map<U: Prm + Interop, F: Fn(&HostTensor<Complex<T>>) -> HostTensor<U>>(&self, op: ComplexOp, f: F) -> DeviceTensor<U> {
+        let context = self.context();
+        if !context.supports::<Complex<T>>() {
+            return DeviceTensor::from_host(context, &f(&self.to_host()));
+        }
+        let mut dst = unsafe { DeviceBuffer::new_uninit_in(context, self.buffer().len()) };
+        kernel::complex_map(self.buffer(), &mut dst, op);
+        DeviceTensor::from_buffer(dst, self.shape())
+    }
+
+    /// Complex conjugate of each element.
+    pub fn conj(&self) -> Self {
+        self.map(ComplexOp::Conj, |x| x.conj())
+    }
+    /// Modulus of each element.
+    pub fn norm(&self) -> DeviceTensor<T> {
+        self.map(ComplexOp::Abs, |x| x.norm())
+    }
+    /// Modulus of each element, the same as [`DeviceTensor::norm`].
+    pub fn abs(&self) -> DeviceTensor<T> {
+        self.norm()
+    }
+    /// Argument of each element in range `[-pi, pi]`.
+    pub fn arg(&self) -> DeviceTensor<T> {
+        self.map(ComplexOp::Arg, |x| x.arg())
+    }
+
+    /// Real part of each element, values are copied as in [`HostTensor::real`].
+    pub fn real(&self) -> DeviceTensor<T> {
+        self.map(ComplexOp::Real, |x| x.real())
+    }
+    /// Imaginary part of each element, values are copied as in [`HostTensor::real`].
+    pub fn imag(&self) -> DeviceTensor<T> {
+        self.map(ComplexOp::Imag, |x| x.imag())
+    }
+
+    /// Builds tensor from pair of real tensors on the device or on the host if the device does not support the type.
+    fn join(a: &DeviceTensor<T>, b: &DeviceTensor<T>, polar: bool) -> Self {
+        check_parts(a.shape(), b.shape());
+        let context = a.context();
+        if !context.supports::<Complex<T>>() {
+            let (a, b) = (a.to_host(), b.to_host());
+            let host = if polar { HostTensor::from_polar(&a, &b) } else { HostTensor::from_parts(&a, &b) };
+            return Self::from_host(context, &host);
+        }
+        let mut dst = unsafe { DeviceBuffer::new_uninit_in(context, a.buffer().len()) };
+        kernel::complex_join(a.buffer(), b.buffer(), &mut dst, polar);
+        Self::from_buffer(dst, a.shape())
+    }
+
+    /// Create tensor from real and imaginary parts of the same shape.
+    pub fn from_parts(re: &DeviceTensor<T>, im: &DeviceTensor<T>) -> Self {
+        Self::join(re, im, false)
+    }
+    /// Create tensor from modulus and argument of the same shape.
+    pub fn from_polar(r: &DeviceTensor<T>, theta: &DeviceTensor<T>) -> Self {
+        Self::join(r, theta, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shape, values};
+
+    #[test]
+    fn parts() {
+        let re = HostTensor::from_vec(&shape![2, 2], vec![1.0, 0.0, -2.0, 3.0]);
+        let im = HostTensor::from_vec(&shape![2, 2], vec![0.0, 1.0, 0.0, -4.0]);
+        let a = HostTensor::from_parts(&re, &im);
+        assert_eq!(a.shape(), &shape![2, 2]);
+        assert_eq!(values(&a.real()), values(&re));
+        assert_eq!(values(&a.imag()), values(&im));
+        assert_eq!(values(&a.conj().imag()), [0.0, -1.0, 0.0, 4.0]);
+        assert_eq!(values(&a.abs()), [1.0, 1.0, 2.0, 5.0]);
+        assert_eq!(values(&a.norm()), values(&a.abs()));
+    }
+
+    #[test]
+    fn polar() {
+        use std::f64::consts::PI;
+        let a = HostTensor::from_vec(&shape![4], vec![
+            Complex::new(2.0, 0.0), Complex::new(0.0, 3.0), Complex::new(-1.0, 0.0), Complex::new(1.0, -1.0),
+        ]);
+        let arg = a.arg();
+        assert_eq!(values(&arg), [0.0, PI / 2.0, PI, -PI / 4.0]);
+        let b = HostTensor::from_polar(&a.norm(), &arg);
+        assert!(a.iter().zip(b.iter()).all(|(x, y)| (x - y).norm() < 1e-12));
+    }
+
+    #[test]
+    #[should_panic]
+    fn shape_mismatch() {
+        HostTensor::from_parts(&HostTensor::<f32>::new_zeroed(&shape![2]), &HostTensor::new_zeroed(&shape![3]));
+    }
+}
@@ -0,0 +1,396 @@
+use crate::{
+    Prm, Float, Cast, Shape, Tensor, HostTensor,
+    num::Zero,
+};
+#[cfg(feature = "device")]
+use crate::{Interop, Buffer, DeviceBuffer, DeviceTensor, kernel};
+
+
+/// Parameters of convolution, see [`HostTensor::conv`] for the layout of tensors.
+///
+/// Each vector contains values for spatial axes, empty vector means default value for all axes,
+/// and single value is used for all axes.
+#[derive(Clone, Debug)]
+pub struct ConvOptions {
+    /// Step between kernel positions. Default is `1`.
+    pub stride: Vec<usize>,
+    /// Number of zeros added at both ends of input. Default is `0`.
+    pub padding: Vec<usize>,
+    /// Step between kernel elements. Default is `1`.
+    pub dilation: Vec<usize>,
+    /// Number of groups that input and output channels are split into,
+    /// each group of output channels depends only on the corresponding group of input ones. Default is `1`.
+    pub groups: usize,
+}
+
+impl Default for ConvOptions {
+    fn default() -> Self {
+        Self { stride: Vec::new(), padding: Vec::new(), dilation: Vec::new(), groups: 1 }
+    }
+}
+
+/// Parameters of pooling, see [`ConvOptions`] for the meaning of vectors.
+#[derive(Clone, Debug)]
+pub struct PoolOptions {
+    /// Step between window positions. Default is the window size.
+    pub stride: Vec<usize>,
+    /// Size of padding at both ends of input, it must not exceed half of the window size. Default is `0`.
+    pub padding: Vec<usize>,
+    /// Padded elements are counted in average. Default is `true`.
+    pub count_padding: bool,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        Self { stride: Vec::new(), padding: Vec::new(), count_padding: true }
+    }
+}
+
+/// Value of the parameter for specified axis.
+fn axis_value(values: &[usize], axis: usize, dims: usize, default: usize) -> usize {
+    assert!(values.len() <= 1 || values.len() == dims, "Expected 1 or {} values of parameter, got {}", dims, values.len());
+    match values.len() {
+        0 => default,
+        1 => values[0],
+        _ => values[axis],
+    }
+}
+
+/// Geometry of sliding window along 3 spatial axes, unused axes have size `1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Window {
+    pub input: [usize; 3],
+    pub kernel: [usize; 3],
+    pub stride: [usize; 3],
+    pub padding: [usize; 3],
+    pub dilation: [usize; 3],
+    pub output: [usize; 3],
+}
+
+impl Window {
+    fn new(shape: &Shape, kernel: &[usize], stride: &[usize], padding: &[usize], dilation: &[usize]) -> Self {
+        let dims = kernel.len();
+        assert!((1..=3).contains(&dims), "Only 1, 2 and 3 spatial axes are supported, got {}", dims);
+        let mut w = Window { input: [1; 3], kernel: [1; 3], stride: [1; 3], padding: [0; 3], dilation: [1; 3], output: [1; 3] };
+        for a in 0..dims {
+            w.input[a] = shape[a];
+            w.kernel[a] = kernel[a];
+            w.stride[a] = axis_value(stride, a, dims, kernel[a]);
+            w.padding[a] = axis_value(padding, a, dims, 0);
+            w.dilation[a] = axis_value(dilation, a, dims, 1);
+            assert!(w.stride[a] > 0 && w.dilation[a] > 0, "Stride and dilation must be positive");
+            let extent = w.dilation[a] * (w.kernel[a].max(1) - 1) + 1;
+            let padded = w.input[a] + 2 * w.padding[a];
+            assert!(w.kernel[a] > 0 && padded >= extent, "Kernel of size {} does not fit into axis {} of size {}", kernel[a], a, padded);
+            w.output[a] = (padded - extent) / w.stride[a] + 1;
+        }
+        w
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.input.iter().product()
+    }
+    pub fn kernel_size(&self) -> usize {
+        self.kernel.iter().product()
+    }
+    pub fn output_size(&self) -> usize {
+        self.output.iter().product()
+    }
+
+    /// Input offset for output position `q` and kernel position `r`, `None` if it refers to padding.
+    fn source(&self, q: usize, r: usize) -> Option<usize> {
+        let (mut q, mut r, mut offset, mut stride) = (q, r, 0, 1);
+        for a in 0..3 {
+            let (o, k) = (q % self.output[a], r % self.kernel[a]);
+            q /= self.output[a];
+            r /= self.kernel[a];
+            let x = (o * self.stride[a] + k * self.dilation[a]).checked_sub(self.padding[a]).filter(|&x| x < self.input[a])?;
+            offset += x * stride;
+            stride *= self.input[a];
+        }
+        Some(offset)
+    }
+}
+
+/// Shape of result with specified spatial output sizes followed by other dimensions.
+fn output_shape(window: &Window, dims: usize, rest: &[usize]) -> Shape {
+    Shape::from(window.output[..dims].iter().chain(rest).cloned().collect::<Vec<_>>())
+}
+
+/// Geometry of convolution of input with weights.
+pub(crate) struct ConvGeometry {
+    pub window: Window,
+    pub in_channels: usize,
+    pub out_channels: usize,
+    pub groups: usize,
+    pub batch: usize,
+}
+
+impl ConvGeometry {
+    fn new(input: &Shape, weight: &Shape, bias: Option<&Shape>, dims: usize, options: &ConvOptions) -> Self {
+        assert!(input.len() <= dims + 2, "Input of shape {} has more than {} axes", input, dims + 2);
+        assert!(weight.len() <= dims + 2, "Weight of shape {} has more than {} axes", weight, dims + 2);
+        let kernel = (0..dims).map(|a| weight[a]).collect::<Vec<_>>();
+        // Unlike pooling, convolution stride defaults to `1` rather than to the kernel size.
+        let stride = if options.stride.is_empty() { &[1][..] } else { &options.stride };
+        let window = Window::new(input, &kernel, stride, &options.padding, &options.dilation);
+        let (in_channels, out_channels, groups) = (input[dims], weight[dims + 1], options.groups);
+        assert!(groups > 0 && out_channels % groups == 0, "Number of output channels {} is not divisible by {} groups", out_channels, groups);
+        assert_eq!(in_channels, weight[dims] * groups, "Number of input channels does not match weight of shape {}", weight);
+        if let Some(bias) = bias {
+            assert_eq!(bias, &Shape::from(vec![out_channels]), "Bias must be of shape ({},)", out_channels);
+        }
+        Self { window, in_channels, out_channels, groups, batch: input[dims + 1] }
+    }
+
+    fn output_shape(&self, dims: usize) -> Shape {
+        output_shape(&self.window, dims, &[self.out_channels, self.batch])
+    }
+}
+
+impl<T: Prm + Float> HostTensor<T> {
+    /// Convolution with `dims` spatial axes.
+    ///
+    /// Tensors of images are laid out with spatial axes first, then channels and then batch,
+    /// e.g. batch of 2D images has shape `(width, height, channels, batch)` and element at position `(x, y, c, n)`.
+    /// This is the same memory layout as PyTorch `NCHW` one with the reversed order of axes,
+    /// so PyTorch tensors loaded from safetensors are used as is.
+    ///
+    /// Input has shape `(x, .., in_channels, batch)`, weight has shape `(kernel_x, .., in_channels/groups, out_channels)`
+    /// and optional bias has shape `(out_channels,)`. Result has shape `(output_x, .., out_channels, batch)`.
+    /// Like in PyTorch, this is actually a cross-correlation, i.e. kernel is not flipped.
+    ///
+    /// Input is unfolded into matrix of patches (im2col) that is multiplied by weight matrix.
+    pub fn conv(&self, weight: &Self, bias: Option<&Self>, dims: usize, options: &ConvOptions) -> Self {
+        let geometry = ConvGeometry::new(self.shape(), weight.shape(), bias.map(|b| b.shape()), dims, options);
+        let ConvGeometry { window, in_channels, out_channels, groups, batch } = geometry;
+        let (cg, og) = (in_channels / groups, out_channels / groups);
+        let (k, p) = (window.kernel_size() * cg, window.output_size());
+        let (src, w) = (self.buffer().as_slice(), weight.buffer().as_slice());
+        let bias = bias.map(|b| b.buffer().as_slice());
+
+        // Input offsets of patch elements, `None` for padding.
+        let offsets = (0..p).flat_map(|q| (0..window.kernel_size()).map(move |r| window.source(q, r))).collect::<Vec<_>>();
+        let mut cols = vec![<T as Zero>::zero(); k * p];
+        let mut dst = vec![<T as Zero>::zero(); p * out_channels * batch];
+        for b in 0..batch {
+            for g in 0..groups {
+                // Column `q` of patch matrix contains input values for output position `q`.
+                for q in 0..p {
+                    for c in 0..cg {
+                        let base = window.input_size() * (g*cg + c + in_channels*b);
+                        for (r, offset) in offsets[(q * window.kernel_size())..((q + 1) * window.kernel_size())].iter().enumerate() {
+                            cols[(c * window.kernel_size() + r) + k*q] = offset.map_or(<T as Zero>::zero(), |x| src[base + x]);
+                        }
+                    }
+                }
+                for j in (g*og)..((g + 1)*og) {
+                    let column = &w[(j * k)..((j + 1) * k)];
+                    let init = bias.map_or(<T as Zero>::zero(), |b| b[j]).to_acc();
+                    for q in 0..p {
+                        let patch = &cols[(q * k)..((q + 1) * k)];
+                        let acc = patch.iter().zip(column).fold(init, |acc, (&x, &y)| acc + x.to_acc() * y.to_acc());
+                        dst[q + p*(j + out_channels*b)] = T::from_acc(acc);
+                    }
+                }
+            }
+        }
+        Self::from_vec(&geometry.output_shape(dims), dst)
+    }
+    /// Convolution with 1 spatial axis, see [`HostTensor::conv`].
+    pub fn conv1d(&self, weight: &Self, bias: Option<&Self>, options: &ConvOptions) -> Self {
+        self.conv(weight, bias, 1, options)
+    }
+    /// Convolution with 2 spatial axes, see [`HostTensor::conv`].
+    pub fn conv2d(&self, weight: &Self, bias: Option<&Self>, options: &ConvOptions) -> Self {
+        self.conv(weight, bias, 2, options)
+    }
+    /// Convolution with 3 spatial axes, see [`HostTensor::conv`].
+    pub fn conv3d(&self, weight: &Self, bias: Option<&Self>, options: &ConvOptions) -> Self {
+        self.conv(weight, bias, 3, options)
+    }
+
+    /// Applies function to each pooling window, it receives iterator over values and the number of padded elements.
+    fn pool<F: Fn(&mut dyn Iterator<Item=T>, usize) -> T>(&self, kernel: &[usize], options: &PoolOptions, f: F) -> Self {
+        let window = Window::new(self.shape(), kernel, &options.stride, &options.padding, &[]);
+        for a in 0..kernel.len() {
+            assert!(2 * window.padding[a] <= window.kernel[a], "Padding must not exceed half of the window size");
+        }
+        let rest = (kernel.len()..self.shape().len()).map(|a| self.shape()[a]).collect::<Vec<_>>();
+        let src = self.buffer().as_slice();
+        let (n, p) = (window.input_size(), window.output_size());
+        let mut dst = Vec::with_capacity(p * rest.iter().product::<usize>());
+        for c in 0..rest.iter().product() {
+            for q in 0..p {
+                let offsets = (0..window.kernel_size()).filter_map(|r| window.source(q, r)).collect::<Vec<_>>();
+                let padded = window.kernel_size() - offsets.len();
+                dst.push(f(&mut offsets.iter().map(|&x| src[x + n*c]), padded));
+            }
+        }
+        Self::from_vec(&output_shape(&window, kernel.len(), &rest), dst)
+    }
+    /// Maximum over sliding window with specified spatial size, padded elements are ignored.
+    ///
+    /// Input has shape `(x, .., ...)` where the number of spatial axes is `kernel.len()` and the rest of axes are arbitrary,
+    /// result has shape `(output_x, .., ...)`.
+    pub fn max_pool(&self, kernel: &[usize], options: &PoolOptions) -> Self {
+        self.pool(kernel, options, |values, _| values.fold(T::neg_infinity(), |a, x| if x > a { x } else { a }))
+    }
+}
+
+impl<T: Prm + Float> HostTensor<T> where usize: Cast<T::Acc> {
+    /// Average over sliding window with specified spatial size, see [`HostTensor::max_pool`].
+    pub fn avg_pool(&self, kernel: &[usize], options: &PoolOptions) -> Self {
+        self.pool(kernel, options, |values, padded| {
+            let (sum, count) = values.fold((<T as Zero>::zero().to_acc(), 0), |(s, n), x| (s + x.to_acc(), n + 1));
+            let count = if options.count_padding { count + padded } else { count };
+            T::from_acc(sum / count.cast())
+        })
+    }
+}
+
+#[cfg(feature = "device")]
+impl<T: Prm + Interop + Float> DeviceTensor<T> {
+    /// Convolution with `dims` spatial axes, see [`HostTensor::conv`].
+    ///
+    /// This is a direct convolution where each output value is computed by a separate work item
+    /// without local memory tiling, so it is best suited for small kernels and moderate numbers of channels.
+    pub fn conv(&self, weight: &Self, bias: Option<&Self>, dims: usize, options: &ConvOptions) -> Self {
+        let context = self.context();
+        if !context.supports::<T>() {
+            let bias = bias.map(|b| b.to_host());
+            return Self::from_host(context, &self.to_host().conv(&weight.to_host(), bias.as_ref(), dims, options));
+        }
+        let geometry = ConvGeometry::new(self.shape(), weight.shape(), bias.map(|b| b.shape()), dims, options);
+        let shape = geometry.output_shape(dims);
+        let mut dst = unsafe { DeviceBuffer::new_uninit_in(context, shape.content()) };
+        kernel::conv(self.buffer(), weight.buffer(), bias.map(|b| b.buffer()), &mut dst, &geometry);
+        Self::from_buffer(dst, &shape)
+    }
+    /// Convolution with 1 spatial axis, see [`HostTensor::conv`].
+    pub fn conv1d(&self, weight: &Self, bias: Option<&Self>, options: &ConvOptions) -> Self {
+        self.conv(weight, bias, 1, options)
+    }
+    /// Convolution with 2 spatial axes, see [`HostTensor::conv`].
+    pub fn conv2d(&self, weight: &Self, bias: Option<&Self>, options: &ConvOptions) -> Self {
+        self.conv(weight, bias, 2, options)
+    }
+    /// Convolution with 3 spatial axes, see [`HostTensor::conv`].
+    pub fn conv3d(&self, weight: &Self, bias: Option<&Self>, options: &ConvOptions) -> Self {
+        self.conv(weight, bias, 3, options)
+    }
+
+    fn pool(&self, kernel: &[usize], options: &PoolOptions, mode: kernel::Pool) -> Self {
+        let window = Window::new(self.shape(), kernel, &options.stride, &options.padding, &[]);
+        for a in 0..kernel.len() {
+            assert!(2 * window.padding[a] <= window.kernel[a], "Padding must not exceed half of the window size");
+        }
+        let rest = (kernel.len()..self.shape().len()).map(|a| self.shape()[a]).collect::<Vec<_>>();
+        let shape = output_shape(&window, kernel.len(), &rest);
+        let mut dst = unsafe { DeviceBuffer::new_uninit_in(self.context(), shape.content()) };
+        kernel::pool(self.buffer(), &mut dst, &window, mode);
+        Self::from_buffer(dst, &shape)
+    }
+    /// Maximum over sliding window, see [`HostTensor::max_pool`].
+    pub fn max_pool(&self, kernel: &[usize], options: &PoolOptions) -> Self {
+        if !self.context().supports::<T>() {
+            return Self::from_host(self.context(), &self.to_host().max_pool(kernel, options));
+        }
+        self.pool(kernel, options, kernel::Pool::Max)
+    }
+}
+
+#[cfg(feature = "device")]
+impl<T: Prm + Interop + Float> DeviceTensor<T> where usize: Cast<T::Acc> {
+    /// Average over sliding window, see [`HostTensor::avg_pool`].
+    pub fn avg_pool(&self, kernel: &[usize], options: &PoolOptions) -> Self {
+        if !self.context().supports::<T>() {
+            return Self::from_host(self.context(), &self.to_host().avg_pool(kernel, options));
+        }
+        self.pool(kernel, options, kernel::Pool::Avg { count_padding: options.count_padding })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shape, values};
+
+    /// Direct convolution of 1D signal with a single channel.
+    fn naive(x: &[f64], w: &[f64], stride: usize, padding: usize, dilation: usize) -> Vec<f64> {
+        let n = x.len() + 2*padding;
+        let extent = dilation*(w.len() - 1) + 1;
+        (0..((n - extent)/stride + 1)).map(|o| {
+            w.iter().enumerate().map(|(k, &w)| {
+                let i = (o*stride + k*dilation) as isize - padding as isize;
+                if i >= 0 && (i as usize) < x.len() { w*x[i as usize] } else { 0.0 }
+            }).sum()
+        }).collect()
+    }
+
+    #[test]
+    fn conv1d() {
+        let x = (0..10).map(|i| (i*i) as f64 - 3.0).collect::<Vec<_>>();
+        let w = vec![1.0, -2.0, 0.5];
+        let input = HostTensor::from_vec(&shape![10], x.clone());
+        let weight = HostTensor::from_vec(&shape![3], w.clone());
+        for &(s, p, d) in &[(1, 0, 1), (2, 1, 1), (1, 2, 2), (3, 0, 4)] {
+            let options = ConvOptions { stride: vec![s], padding: vec![p], dilation: vec![d], ..ConvOptions::default() };
+            assert_eq!(values(&input.conv1d(&weight, None, &options)), naive(&x, &w, s, p, d));
+        }
+    }
+
+    #[test]
+    fn conv2d() {
+        // Batch of 2 images 4x3 with 2 channels, 3 output channels, kernel 2x2.
+        let input = HostTensor::from_fn(&shape![4, 3, 2, 2], |p| (p[0] + 4*p[1]) as f64 * if p[2] == 0 { 1.0 } else { -1.0 } + p[3] as f64);
+        let weight = HostTensor::from_fn(&shape![2, 2, 2, 3], |p| (p[0] + 2*p[1] + p[2]) as f64 - p[3] as f64);
+        let bias = HostTensor::from_vec(&shape![3], vec![0.5, 1.0, -1.0]);
+        let out = input.conv2d(&weight, Some(&bias), &ConvOptions::default());
+        assert_eq!(out.shape(), &shape![3, 2, 3, 2]);
+        let v = values(&out);
+        let (x, y, o, n) = (2, 1, 1, 1);
+        let expected = bias.iter().nth(o).unwrap() + (0..2).flat_map(|i| (0..2).flat_map(move |j| (0..2).map(move |c| (i, j, c)))).map(|(i, j, c)| {
+            let a = (x + i + 4*(y + j)) as f64 * if c == 0 { 1.0 } else { -1.0 } + n as f64;
+            a * ((i + 2*j + c) as f64 - o as f64)
+        }).sum::<f64>();
+        assert_eq!(v[x + 3*(y + 2*(o + 3*n))], expected);
+    }
+
+    #[test]
+    fn groups() {
+        // Depthwise convolution, each channel is convolved with its own kernel.
+        let input = HostTensor::from_fn(&shape![5, 2], |p| (p[0] + 10*p[1]) as f64);
+        let weight = HostTensor::from_vec(&shape![2, 1, 2], vec![1.0, 1.0, 1.0, -1.0]);
+        let out = input.conv1d(&weight, None, &ConvOptions { groups: 2, ..ConvOptions::default() });
+        assert_eq!(out.shape(), &shape![4, 2]);
+        assert_eq!(values(&out), [1.0, 3.0, 5.0, 7.0, -1.0, -1.0, -1.0, -1.0]);
+    }
+
+    #[test]
+    fn conv3d() {
+        let input = HostTensor::from_fn(&shape![3, 3, 3], |p| (p[0] + 3*p[1] + 9*p[2]) as f64);
+        let weight = HostTensor::new_ones(&shape![3, 3, 3]);
+        let out = input.conv3d(&weight, None, &ConvOptions { padding: vec![1], ..ConvOptions::default() });
+        assert_eq!(out.shape(), &shape![3, 3, 3]);
+        assert_eq!(values(&out)[13], (0..27).sum::<usize>() as f64);
+        assert_eq!(values(&out)[0], [0, 1, 3, 4, 9, 10, 12, 13].iter().sum::<usize>() as f64);
+    }
+
+    #[test]
+    fn pooling() {
+        let input = HostTensor::from_fn(&shape![4, 4, 2], |p| (p[0] + 4*p[1]) as f64 * if p[2] == 0 { 1.0 } else { -1.0 });
+        let max = input.max_pool(&[2, 2], &PoolOptions::default());
+        assert_eq!(max.shape(), &shape![2, 2, 2]);
+        assert_eq!(values(&max), [5.0, 7.0, 13.0, 15.0, 0.0, -2.0, -8.0, -10.0]);
+        let avg = input.avg_pool(&[2, 2], &PoolOptions::default());
+        assert_eq!(values(&avg)[..4], [2.5, 4.5, 10.5, 12.5]);
+
+        let x = HostTensor::from_vec(&shape![4], vec![1.0, 2.0, 3.0, 4.0]);
+        let options = PoolOptions { stride: vec![1], padding: vec![1], count_padding: true };
+        assert_eq!(values(&x.avg_pool(&[3], &options)), [1.0, 2.0, 3.0, 7.0/3.0]);
+        let options = PoolOptions { count_padding: false, ..options };
+        assert_eq!(values(&x.avg_pool(&[3], &options)), [1.5, 2.0, 3.0, 3.5]);
+        assert_eq!(values(&x.max_pool(&[3], &options)), [2.0, 3.0, 4.0, 4.0]);
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
-    Prm, Interop,
-    DeviceBuffer, DeviceContext,
-    Shape, Tensor, CommonTensor,
+    Prm, Num, Float, Interop, OverflowError, Cast,
+    Buffer, DeviceBuffer, DeviceContext,
+    Shape, Tensor, CommonTensor, HostTensor,
+    RandomFloat,
+    kernel, arange_len, axis_blocks, with_axis, check_index, check_range,
+    random::{below, check_bounds, bernoulli_threshold},
 };
 
 type InnerTensor<T> = CommonTensor<T, DeviceBuffer<T>>;
@@ -13,7 +16,251 @@ pub struct DeviceTensor<T: Prm + Interop> {
     inner: InnerTensor<T>,
 }
 
-impl<T: Prm + Interop> DeviceTensor<T> {}
+impl<T: Prm + Interop> DeviceTensor<T> {
+    /// Create tensor from data generated on the host.
+    pub(crate) fn from_host(context: &DeviceContext, host: &HostTensor<T>) -> Self {
+        let mut tensor = unsafe { Self::new_uninit_in(context, host.shape()) };
+        tensor.store(host.buffer().as_slice());
+        tensor
+    }
+
+    /// Download tensor to the host.
+    pub(crate) fn to_host(&self) -> HostTensor<T> {
+        let mut data = vec![T::zero(); self.shape().content()];
+        self.load(&mut data);
+        HostTensor::from_vec(self.shape(), data)
+    }
+
+    /// Create identity matrix of size `n` in specified context.
+    pub fn eye_in(context: &DeviceContext, n: usize) -> Self {
+        if !context.supports::<T>() {
+            return Self::from_host(context, &HostTensor::eye(n));
+        }
+        let mut buffer = unsafe { DeviceBuffer::new_uninit_in(context, n*n) };
+        kernel::eye(&mut buffer, n);
+        Self { inner: InnerTensor::from_buffer(buffer, &[n, n].as_ref().into()) }
+    }
+
+    /// Create tensor of specified shape that takes ownership of the buffer.
+    pub(crate) fn from_buffer(buffer: DeviceBuffer<T>, shape: &Shape) -> Self {
+        Self { inner: InnerTensor::from_buffer(buffer, shape) }
+    }
+    /// Provides access to underlying buffer.
+    pub(crate) fn buffer(&self) -> &DeviceBuffer<T> {
+        self.inner.buffer()
+    }
+
+    /// Context of the tensor.
+    pub fn context(&self) -> &DeviceContext {
+        self.inner.buffer().context()
+    }
+
+    /// Load flattened data from tensor to slice.
+    /// Fails if some value cannot be represented on the host.
+    pub fn try_load(&self, dst: &mut [T]) -> Result<(), OverflowError> {
+        self.inner.buffer().try_load(dst)
+    }
+    /// Store data from slice to a tensor in a flattened manner.
+    /// Fails if some value cannot be represented on the device.
+    pub fn try_store(&mut self, src: &[T]) -> Result<(), OverflowError> {
+        self.inner.buffer_mut().try_store(src)
+    }
+
+    /// Joins tensors along the existing axis, all other dimensions must be equal.
+    ///
+    /// Tensors must be in the same context, data is copied on the device.
+    pub fn concat(tensors: &[&Self], axis: usize) -> Self {
+        Self { inner: InnerTensor::concat(&tensors.iter().map(|t| &t.inner).collect::<Vec<_>>(), axis) }
+    }
+    /// Joins tensors of the same shape along the new axis inserted at specified position.
+    pub fn stack(tensors: &[&Self], axis: usize) -> Self {
+        Self { inner: InnerTensor::stack(&tensors.iter().map(|t| &t.inner).collect::<Vec<_>>(), axis) }
+    }
+    /// Splits tensor along the axis into parts of specified sizes, sizes must sum up to the size of axis.
+    pub fn split(&self, sizes: &[usize], axis: usize) -> Vec<Self> {
+        self.inner.split(sizes, axis).into_iter().map(|inner| Self { inner }).collect()
+    }
+    /// Splits tensor along the axis into at most `n` parts of the same size, the last part may be smaller.
+    pub fn chunk(&self, n: usize, axis: usize) -> Vec<Self> {
+        self.inner.chunk(n, axis).into_iter().map(|inner| Self { inner }).collect()
+    }
+
+    /// Converts tensor elements to another type.
+    /// See [`Cast`] for the conversion semantics.
+    ///
+    /// Conversion is performed by device kernel if the device supports both types,
+    /// otherwise data is converted on the host.
+    pub fn cast<U: Prm + Interop>(&self) -> DeviceTensor<U> where T: Cast<U> {
+        let context = self.context();
+        let mut dst = unsafe { DeviceBuffer::<U>::new_uninit_in(context, self.inner.buffer().len()) };
+        if context.supports::<T>() && context.supports::<U>() {
+            kernel::cast(self.inner.buffer(), &mut dst);
+        } else {
+            let mut tmp = vec![T::zero(); self.inner.buffer().len()];
+            self.load(&mut tmp);
+            dst.store(&tmp.into_iter().map(|x| x.cast()).collect::<Vec<_>>());
+        }
+        DeviceTensor { inner: CommonTensor::from_buffer(dst, self.shape()) }
+    }
+}
+
+/// Panics if some of indices is out of range of axis of size `n` with the same message as the host does.
+fn check_indices(index: &DeviceTensor<usize>, n: usize, axis: usize) {
+    if let Some(j) = kernel::find_out_of_range(index.inner.buffer(), n) {
+        check_range(j, n, axis);
+    }
+}
+
+impl<T: Prm + Interop> DeviceTensor<T> {
+    /// Selects slices along the axis at specified indices, see [`HostTensor::index_select`].
+    ///
+    /// Indices are checked on the device and it panics if some index is out of range, like the host does.
+    pub fn index_select(&self, axis: usize, indices: &DeviceTensor<usize>) -> Self {
+        let context = self.context();
+        if !context.supports::<T>() {
+            return Self::from_host(context, &self.to_host().index_select(axis, &indices.to_host()));
+        }
+        let blocks = axis_blocks(self.shape(), axis);
+        check_indices(indices, blocks.1, axis);
+        let m = indices.shape().content();
+        let shape = with_axis(self.shape(), axis, m);
+        let mut buffer = unsafe { DeviceBuffer::new_uninit_in(context, shape.content()) };
+        kernel::gather(self.inner.buffer(), indices.inner.buffer(), &mut buffer, blocks, m, true);
+        Self { inner: InnerTensor::from_buffer(buffer, &shape) }
+    }
+
+    /// Gathers values along the axis, see [`HostTensor::gather`].
+    ///
+    /// Panics if some index is out of range, see [`DeviceTensor::index_select`].
+    pub fn gather(&self, axis: usize, index: &DeviceTensor<usize>) -> Self {
+        let context = self.context();
+        if !context.supports::<T>() {
+            return Self::from_host(context, &self.to_host().gather(axis, &index.to_host()));
+        }
+        let m = check_index(self.shape(), index.shape(), axis);
+        let blocks = axis_blocks(self.shape(), axis);
+        check_indices(index, blocks.1, axis);
+        let mut buffer = unsafe { DeviceBuffer::new_uninit_in(context, index.shape().content()) };
+        kernel::gather(self.inner.buffer(), index.inner.buffer(), &mut buffer, blocks, m, false);
+        Self { inner: InnerTensor::from_buffer(buffer, index.shape()) }
+    }
+
+    /// Writes values of `src` along the axis, see [`HostTensor::scatter`].
+    ///
+    /// Panics if some index is out of range, see [`DeviceTensor::index_select`].
+    /// Each destination value is computed by looking through all source values along the axis
+    /// to keep the order of duplicates without atomics, so the cost is proportional to the product of their sizes.
+    pub fn scatter(&mut self, axis: usize, index: &DeviceTensor<usize>, src: &Self) {
+        if !self.context().supports::<T>() {
+            let mut host = self.to_host();
+            host.scatter(axis, &index.to_host(), &src.to_host());
+            self.store(host.buffer().as_slice());
+            return;
+        }
+        self.scatter_with(axis, index, src, false);
+    }
+
+    fn scatter_with(&mut self, axis: usize, index: &DeviceTensor<usize>, src: &Self, add: bool) {
+        let m = check_index(self.shape(), index.shape(), axis);
+        assert_eq!(src.shape(), index.shape(), "Source and index shapes must be equal");
+        let blocks = axis_blocks(self.shape(), axis);
+        check_indices(index, blocks.1, axis);
+        kernel::scatter(self.inner.buffer_mut(), index.inner.buffer(), src.inner.buffer(), blocks, m, add);
+    }
+
+    /// Selects elements where mask is `true` into tensor of rank 1, see [`HostTensor::masked_select`].
+    ///
+    /// Elements are compacted on the device, only the number of selected elements is read back to the host.
+    pub fn masked_select(&self, mask: &DeviceTensor<bool>) -> Self {
+        assert_eq!(self.shape(), mask.shape(), "Mask shape must be equal to tensor shape");
+        let context = self.context();
+        if !context.supports::<T>() {
+            return Self::from_host(context, &self.to_host().masked_select(&mask.to_host()));
+        }
+        let buffer = kernel::masked_select(self.inner.buffer(), mask.inner.buffer());
+        let len = buffer.len();
+        Self { inner: InnerTensor::from_buffer(buffer, &[len].as_ref().into()) }
+    }
+}
+
+impl<T: Prm + Interop + Num> DeviceTensor<T> {
+    /// Adds values of `src` along the axis, see [`HostTensor::scatter_add`].
+    ///
+    /// Panics if some index is out of range and has the same cost as [`DeviceTensor::scatter`].
+    pub fn scatter_add(&mut self, axis: usize, index: &DeviceTensor<usize>, src: &Self) {
+        if !self.context().supports::<T>() {
+            let mut host = self.to_host();
+            host.scatter_add(axis, &index.to_host(), &src.to_host());
+            self.store(host.buffer().as_slice());
+            return;
+        }
+        self.scatter_with(axis, index, src, true);
+    }
+}
+
+impl<T: Prm + Interop + Num + PartialOrd + Cast<f64> + Cast<u64>> DeviceTensor<T> where usize: Cast<T> {
+    /// Create 1-dimensional tensor of values from `start` (inclusive) to `stop` (exclusive) with `step` between them
+    /// in specified context. See [`HostTensor::arange`] for details.
+    pub fn arange_in(context: &DeviceContext, start: T, stop: T, step: T) -> Self {
+        if !context.supports::<T>() {
+            return Self::from_host(context, &HostTensor::arange(start, stop, step));
+        }
+        let len = arange_len(start, stop, step);
+        let mut buffer = unsafe { DeviceBuffer::new_uninit_in(context, len) };
+        kernel::arange(&mut buffer, start, step);
+        Self { inner: InnerTensor::from_buffer(buffer, &[len].as_ref().into()) }
+    }
+}
+
+impl<T: Prm + Interop + Float> DeviceTensor<T> where usize: Cast<T::Acc> {
+    /// Create 1-dimensional tensor of `len` values evenly spaced from `start` to `stop` (both inclusive)
+    /// in specified context.
+    pub fn linspace_in(context: &DeviceContext, start: T, stop: T, len: usize) -> Self {
+        if !context.supports::<T>() {
+            return Self::from_host(context, &HostTensor::linspace(start, stop, len));
+        }
+        let mut buffer = unsafe { DeviceBuffer::new_uninit_in(context, len) };
+        kernel::linspace(&mut buffer, start, stop);
+        Self { inner: InnerTensor::from_buffer(buffer, &[len].as_ref().into()) }
+    }
+}
+
+impl<T: Prm + Interop + Float> DeviceTensor<T> where T::Acc: RandomFloat {
+    /// Create tensor of values uniformly distributed in `[low, high)` in specified context.
+    ///
+    /// Values are the same as produced by [`HostTensor::uniform`] with the same seed, bounds are checked in the same way.
+    pub fn uniform_in(context: &DeviceContext, shape: &Shape, low: T, high: T, seed: u64) -> Self {
+        check_bounds(low, high);
+        if !context.supports::<T>() {
+            return Self::from_host(context, &HostTensor::uniform(shape, low, high, seed));
+        }
+        let mut buffer = unsafe { DeviceBuffer::new_uninit_in(context, shape.content()) };
+        kernel::random(&mut buffer, kernel::Distribution::Uniform(low, high, below(high)), seed);
+        Self { inner: InnerTensor::from_buffer(buffer, shape) }
+    }
+    /// Create tensor of normally distributed values with specified mean and standard deviation in specified context.
+    ///
+    /// Values are the same as produced by [`HostTensor::normal`] with the same seed up to rounding errors.
+    pub fn normal_in(context: &DeviceContext, shape: &Shape, mean: T, std: T, seed: u64) -> Self {
+        if !context.supports::<T>() {
+            return Self::from_host(context, &HostTensor::normal(shape, mean, std, seed));
+        }
+        let mut buffer = unsafe { DeviceBuffer::new_uninit_in(context, shape.content()) };
+        kernel::random(&mut buffer, kernel::Distribution::Normal(mean, std), seed);
+        Self { inner: InnerTensor::from_buffer(buffer, shape) }
+    }
+}
+
+impl DeviceTensor<bool> {
+    /// Create tensor of values that are `true` with probability `p` in specified context.
+    ///
+    /// Values are the same as produced by [`HostTensor::bernoulli`] with the same seed.
+    pub fn bernoulli_in(context: &DeviceContext, shape: &Shape, p: f64, seed: u64) -> Self {
+        let mut buffer = unsafe { DeviceBuffer::new_uninit_in(context, shape.content()) };
+        kernel::bernoulli(&mut buffer, bernoulli_threshold(p), seed);
+        Self { inner: InnerTensor::from_buffer(buffer, shape) }
+    }
+}
 
 impl<T: Prm + Interop> Tensor<T> for DeviceTensor<T> {
     type Buffer = DeviceBuffer<T>;
@@ -27,6 +274,9 @@ impl<T: Prm + Interop> Tensor<T> for DeviceTensor<T> {
     fn new_zeroed_in(context: &DeviceContext, shape: &Shape) -> Self {
         Self { inner: InnerTensor::<T>::new_zeroed_in(context, shape) }
     }
+    fn new_ones_in(context: &DeviceContext, shape: &Shape) -> Self {
+        Self { inner: InnerTensor::<T>::new_ones_in(context, shape) }
+    }
 
     fn shape(&self) -> &Shape {
         self.inner.shape()
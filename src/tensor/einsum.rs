@@ -0,0 +1,363 @@
+use std::{
+    fmt,
+    error::Error,
+    collections::BTreeMap,
+};
+use crate::{
+    Prm, Num, Shape, Tensor, HostTensor,
+    num::Zero,
+};
+#[cfg(feature = "device")]
+use crate::{Interop, Buffer, DeviceBuffer, DeviceTensor, kernel};
+
+
+/// Error of parsing or validating `einsum` subscripts.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EinsumError {
+    /// Subscripts are malformed.
+    Syntax(String),
+    /// Number of operands differs from the number of input subscripts.
+    Operands {
+        expected: usize,
+        found: usize,
+    },
+    /// Operand has more axes than labels in its subscripts.
+    Rank {
+        operand: usize,
+        labels: usize,
+        shape: Shape,
+    },
+    /// Axes with the same label have different sizes.
+    Size {
+        label: char,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for EinsumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EinsumError::Syntax(message) => write!(f, "Invalid subscripts: {}", message),
+            EinsumError::Operands { expected, found } => write!(f, "Subscripts require {} operands, found {}", expected, found),
+            EinsumError::Rank { operand, labels, shape } => write!(f, "Operand {} of shape {} has more than {} axes", operand, shape, labels),
+            EinsumError::Size { label, expected, found } => write!(f, "Label '{}' refers to axes of size {} and {}", label, expected, found),
+        }
+    }
+}
+
+impl Error for EinsumError {}
+
+/// Subscripts of Einstein summation validated against operand shapes.
+struct Subscripts {
+    inputs: Vec<Vec<char>>,
+    output: Vec<char>,
+    sizes: BTreeMap<char, usize>,
+}
+
+impl Subscripts {
+    fn parse(text: &str, shapes: &[&Shape]) -> Result<Self, EinsumError> {
+        let text = text.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+        let (inputs, output) = match text.find("->") {
+            Some(i) => (&text[..i], Some(&text[(i + 2)..])),
+            None => (text.as_str(), None),
+        };
+        let labels = |s: &str| match s.chars().find(|c| !c.is_ascii_alphabetic()) {
+            Some(c) => Err(EinsumError::Syntax(format!("unexpected character '{}'", c))),
+            None => Ok(s.chars().collect::<Vec<_>>()),
+        };
+        let inputs = inputs.split(',').map(labels).collect::<Result<Vec<_>, _>>()?;
+        if inputs.len() != shapes.len() {
+            return Err(EinsumError::Operands { expected: inputs.len(), found: shapes.len() });
+        }
+
+        let mut sizes = BTreeMap::new();
+        for (operand, (labels, shape)) in inputs.iter().zip(shapes.iter()).enumerate() {
+            if shape.len() > labels.len() {
+                return Err(EinsumError::Rank { operand, labels: labels.len(), shape: (*shape).clone() });
+            }
+            for (axis, &label) in labels.iter().enumerate() {
+                let size = *sizes.entry(label).or_insert(shape[axis]);
+                if size != shape[axis] {
+                    return Err(EinsumError::Size { label, expected: size, found: shape[axis] });
+                }
+            }
+        }
+
+        let output = match output {
+            Some(s) => labels(s)?,
+            // Labels that appear only once in alphabetical order, as in NumPy.
+            None => sizes.keys().cloned().filter(|l| inputs.iter().flatten().filter(|&x| x == l).count() == 1).collect(),
+        };
+        for (i, label) in output.iter().enumerate() {
+            if !sizes.contains_key(label) {
+                return Err(EinsumError::Syntax(format!("output label '{}' does not appear in inputs", label)));
+            }
+            if output[..i].contains(label) {
+                return Err(EinsumError::Syntax(format!("output label '{}' is repeated", label)));
+            }
+        }
+        Ok(Self { inputs, output, sizes })
+    }
+
+    fn shape(&self, labels: &[char]) -> Shape {
+        Shape::from(labels.iter().map(|l| self.sizes[l]).collect::<Vec<_>>())
+    }
+}
+
+/// Operations that Einstein summation is lowered to.
+trait Contract<T: Prm>: Tensor<T> {
+    /// Tensor of specified shape with `dst[p] = sum(src[offset(p) + offset(s)])` over all `s`.
+    /// See [`kernel::reduce_strided`] for the offset computation.
+    fn reduce_strided(&self, shape: &Shape, strides: &[usize], sum_dims: &[usize], sum_strides: &[usize]) -> Self;
+    /// Batch of matrix products, see [`kernel::matmul_batched`].
+    fn matmul_batched(&self, other: &Self, mkn: (usize, usize, usize), batch: usize) -> Self;
+}
+
+/// Operand with labels of its axes.
+struct Labeled<X> {
+    labels: Vec<char>,
+    tensor: X,
+}
+
+impl<X> Labeled<X> {
+    /// Rearranges axes into specified order, takes diagonals for repeated labels and sums over labels not in order.
+    fn reduce<T: Prm>(self, subs: &Subscripts, order: &[char]) -> Self where X: Contract<T> {
+        if self.labels == order {
+            return self;
+        }
+        let mut stride = 1;
+        let mut strides = BTreeMap::new();
+        for label in self.labels.iter() {
+            *strides.entry(*label).or_insert(0) += stride;
+            stride *= subs.sizes[label];
+        }
+        let summed = strides.keys().cloned().filter(|l| !order.contains(l)).collect::<Vec<_>>();
+        let tensor = self.tensor.reduce_strided(
+            &subs.shape(order),
+            &order.iter().map(|l| strides[l]).collect::<Vec<_>>(),
+            &summed.iter().map(|l| subs.sizes[l]).collect::<Vec<_>>(),
+            &summed.iter().map(|l| strides[l]).collect::<Vec<_>>(),
+        );
+        Self { labels: order.to_vec(), tensor }
+    }
+}
+
+/// Labels of `a` and `b` that are needed after their contraction.
+fn kept(a: &[char], b: &[char], needed: &[char]) -> Vec<char> {
+    let mut labels = a.iter().chain(b).filter(|l| needed.contains(l)).cloned().collect::<Vec<_>>();
+    labels.sort_unstable();
+    labels.dedup();
+    labels
+}
+
+/// Contracts pair of operands with distinct labels, keeps only labels in `needed`.
+fn contract<T: Prm, X: Contract<T>>(subs: &Subscripts, a: Labeled<X>, b: Labeled<X>, needed: &[char]) -> Labeled<X> {
+    let common = a.labels.iter().filter(|l| b.labels.contains(l)).cloned().collect::<Vec<_>>();
+    let batch = common.iter().filter(|l| needed.contains(l)).cloned().collect::<Vec<_>>();
+    let sum = common.iter().filter(|l| !needed.contains(l)).cloned().collect::<Vec<_>>();
+    let m = a.labels.iter().filter(|l| !common.contains(l)).cloned().collect::<Vec<_>>();
+    let n = b.labels.iter().filter(|l| !common.contains(l)).cloned().collect::<Vec<_>>();
+
+    let a = a.reduce(subs, &[m.as_slice(), &sum, &batch].concat());
+    let b = b.reduce(subs, &[sum.as_slice(), &n, &batch].concat());
+    let size = |labels: &[char]| subs.shape(labels).content();
+    let labels = [m.as_slice(), &n, &batch].concat();
+    let tensor = a.tensor.matmul_batched(&b.tensor, (size(&m), size(&sum), size(&n)), size(&batch));
+    Labeled { tensor: tensor.reshape(&subs.shape(&labels)), labels }
+}
+
+/// Evaluates Einstein summation.
+///
+/// Each operand is first reduced over labels that appear nowhere else,
+/// then pairs of operands are contracted greedily choosing the pair with the smallest result.
+fn einsum<T: Prm, X: Contract<T>>(text: &str, operands: &[&X]) -> Result<X, EinsumError> {
+    let subs = Subscripts::parse(text, &operands.iter().map(|x| x.shape()).collect::<Vec<_>>())?;
+    let needed = |ops: &[Labeled<X>], skip: &[usize]| -> Vec<char> {
+        ops.iter().enumerate().filter(|(i, _)| !skip.contains(i))
+            .flat_map(|(_, x)| x.labels.iter().cloned())
+            .chain(subs.output.iter().cloned())
+            .collect()
+    };
+
+    let mut ops = subs.inputs.iter().zip(operands.iter())
+        .map(|(labels, x)| Labeled { labels: labels.clone(), tensor: x.reshape(x.shape()) })
+        .collect::<Vec<_>>();
+    for i in 0..ops.len() {
+        let needed = needed(&ops, &[i]);
+        let op = ops.remove(i);
+        let order = kept(&op.labels, &[], &needed);
+        ops.insert(i, op.reduce(&subs, &order));
+    }
+
+    while ops.len() > 1 {
+        let mut best = (0, 1, usize::MAX);
+        for i in 0..ops.len() {
+            for j in (i + 1)..ops.len() {
+                let size = subs.shape(&kept(&ops[i].labels, &ops[j].labels, &needed(&ops, &[i, j]))).content();
+                if size < best.2 {
+                    best = (i, j, size);
+                }
+            }
+        }
+        let needed = needed(&ops, &[best.0, best.1]);
+        let b = ops.remove(best.1);
+        let a = ops.remove(best.0);
+        ops.push(contract(&subs, a, b, &needed));
+    }
+
+    let result = ops.pop().unwrap().reduce(&subs, &subs.output);
+    Ok(result.tensor.reshape(&subs.shape(&subs.output)))
+}
+
+/// Offset of position `index` of tensor of specified dimensions in buffer with specified strides.
+fn offset(mut index: usize, dims: &[usize], strides: &[usize]) -> usize {
+    dims.iter().zip(strides).map(|(&n, &s)| {
+        let i = index % n;
+        index /= n;
+        i * s
+    }).sum()
+}
+
+impl<T: Prm + Num> Contract<T> for HostTensor<T> {
+    fn reduce_strided(&self, shape: &Shape, strides: &[usize], sum_dims: &[usize], sum_strides: &[usize]) -> Self {
+        let src = self.buffer().as_slice();
+        let (dims, count) = (shape.as_slice(), sum_dims.iter().product::<usize>());
+        let strides = &strides[..dims.len()];
+        Self::from_vec(shape, (0..shape.content()).map(|p| {
+            let base = offset(p, dims, strides);
+            (0..count).fold(<T as Zero>::zero(), |acc, s| acc + src[base + offset(s, sum_dims, sum_strides)])
+        }).collect())
+    }
+    fn matmul_batched(&self, other: &Self, (m, k, n): (usize, usize, usize), batch: usize) -> Self {
+        let (a, b) = (self.buffer().as_slice(), other.buffer().as_slice());
+        let mut c = vec![<T as Zero>::zero(); m * n * batch];
+        for t in 0..batch {
+            for j in 0..n {
+                for l in 0..k {
+                    let y = b[l + k*(j + n*t)];
+                    for i in 0..m {
+                        c[i + m*(j + n*t)] = c[i + m*(j + n*t)] + a[i + m*(l + k*t)] * y;
+                    }
+                }
+            }
+        }
+        Self::from_vec(&Shape::from(vec![m * n * batch]), c)
+    }
+}
+
+impl<T: Prm + Num> HostTensor<T> {
+    /// Einstein summation over operands, e.g. `HostTensor::einsum("ij,jk->ik", &[&a, &b])` is a matrix product.
+    ///
+    /// Subscripts consist of a single letter for each operand axis, axis `0` is labeled by the first letter.
+    /// Axes with labels that are missing in the output are summed over, repeated labels of the same operand take diagonal.
+    /// If the output is omitted then it consists of labels that appear only once in alphabetical order.
+    ///
+    /// Operands are contracted pairwise in a greedy order, each contraction is performed as a batched matrix product.
+    pub fn einsum(subscripts: &str, operands: &[&Self]) -> Result<Self, EinsumError> {
+        einsum(subscripts, operands)
+    }
+}
+
+#[cfg(feature = "device")]
+impl<T: Prm + Interop + Num> Contract<T> for DeviceTensor<T> {
+    fn reduce_strided(&self, shape: &Shape, strides: &[usize], sum_dims: &[usize], sum_strides: &[usize]) -> Self {
+        let src = self.buffer();
+        let mut dst = unsafe { DeviceBuffer::new_uninit_in(src.context(), shape.content()) };
+        let dims = shape.as_slice();
+        kernel::reduce_strided(src, &mut dst, (dims, &strides[..dims.len()]), (sum_dims, sum_strides));
+        Self::from_buffer(dst, shape)
+    }
+    fn matmul_batched(&self, other: &Self, mkn: (usize, usize, usize), batch: usize) -> Self {
+        let (a, b) = (self.buffer(), other.buffer());
+        let len = mkn.0 * mkn.2 * batch;
+        let mut c = unsafe { DeviceBuffer::new_uninit_in(a.context(), len) };
+        kernel::matmul_batched(a, b, &mut c, mkn, batch);
+        Self::from_buffer(c, &Shape::from(vec![len]))
+    }
+}
+
+#[cfg(feature = "device")]
+impl<T: Prm + Interop + Num> DeviceTensor<T> {
+    /// Einstein summation over operands in the same context, see [`HostTensor::einsum`].
+    ///
+    /// Computation is performed on the host if the device does not support the type.
+    pub fn einsum(subscripts: &str, operands: &[&Self]) -> Result<Self, EinsumError> {
+        let context = operands.first().map(|x| x.context()).ok_or(EinsumError::Operands { expected: 1, found: 0 })?;
+        if !context.supports::<T>() {
+            let hosts = operands.iter().map(|x| x.to_host()).collect::<Vec<_>>();
+            let result = HostTensor::einsum(subscripts, &hosts.iter().collect::<Vec<_>>())?;
+            return Ok(Self::from_host(context, &result));
+        }
+        einsum(subscripts, operands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shape, values};
+
+    #[test]
+    fn matmul() {
+        let a = HostTensor::from_fn(&shape![2, 3], |p| (p[0] + 2*p[1]) as i32);
+        let b = HostTensor::from_fn(&shape![3, 4], |p| (p[0] as i32) - (p[1] as i32));
+        let c = HostTensor::einsum("ij,jk->ik", &[&a, &b]).unwrap();
+        let expected = HostTensor::from_fn(&shape![2, 4], |p| (0..3).map(|j| ((p[0] + 2*j) as i32)*((j as i32) - (p[1] as i32))).sum::<i32>());
+        assert_eq!(c.shape(), &shape![2, 4]);
+        assert_eq!(values(&c), values(&expected));
+
+        assert_eq!(values(&HostTensor::einsum("ij,jk", &[&a, &b]).unwrap()), values(&expected));
+        let ct = HostTensor::einsum("ij,jk->ki", &[&a, &b]).unwrap();
+        assert_eq!(ct.shape(), &shape![4, 2]);
+        assert_eq!(values(&HostTensor::einsum("ij->ji", &[&ct]).unwrap()), values(&expected));
+    }
+
+    #[test]
+    fn batched() {
+        let a = HostTensor::from_fn(&shape![2, 3, 5], |p| (p[0] + p[1]*p[2]) as f64);
+        let b = HostTensor::from_fn(&shape![3, 4, 5], |p| (p[0] + 2*p[1] + p[2]) as f64);
+        let c = HostTensor::einsum("ijb,jkb->ikb", &[&a, &b]).unwrap();
+        let d = HostTensor::einsum("bij,bjk->bik", &[
+            &HostTensor::einsum("ijb->bij", &[&a]).unwrap(),
+            &HostTensor::einsum("jkb->bjk", &[&b]).unwrap(),
+        ]).unwrap();
+        assert_eq!(values(&HostTensor::einsum("bik->ikb", &[&d]).unwrap()), values(&c));
+        assert_eq!(values(&c)[2*4*4 + 2*3 + 1], (0..3).map(|j| ((1 + j*4)*(j + 6 + 4)) as f64).sum::<f64>());
+    }
+
+    #[test]
+    fn reductions() {
+        let a = HostTensor::from_fn(&shape![3, 3], |p| (10*p[0] + p[1]) as i32);
+        assert_eq!(values(&HostTensor::einsum("ii->", &[&a]).unwrap()), [33]);
+        assert_eq!(values(&HostTensor::einsum("ii->i", &[&a]).unwrap()), [0, 11, 22]);
+        assert_eq!(values(&HostTensor::einsum("ij->j", &[&a]).unwrap()), [30, 33, 36]);
+        let v = HostTensor::from_vec(&shape![3], vec![1, 2, 3]);
+        assert_eq!(values(&HostTensor::einsum("i,i", &[&v, &v]).unwrap()), [14]);
+        assert_eq!(HostTensor::einsum("i,j->ij", &[&v, &v]).unwrap().shape(), &shape![3, 3]);
+    }
+
+    #[test]
+    fn chain() {
+        let a = HostTensor::from_fn(&shape![2, 3], |p| (p[0] + p[1]) as i64);
+        let b = HostTensor::from_fn(&shape![3, 4], |p| (p[0] * p[1]) as i64);
+        let c = HostTensor::from_fn(&shape![4, 1], |p| (p[0] + 1) as i64);
+        let ab = HostTensor::einsum("ij,jk->ik", &[&a, &b]).unwrap();
+        let abc = HostTensor::einsum("ik,kl->il", &[&ab, &c]).unwrap();
+        assert_eq!(values(&HostTensor::einsum("ij,jk,kl->il", &[&a, &b, &c]).unwrap()), values(&abc));
+        assert_eq!(values(&HostTensor::einsum("kl,ij,jk->i", &[&c, &a, &b]).unwrap()), values(&abc));
+    }
+
+    #[test]
+    fn errors() {
+        let a = HostTensor::<f32>::new_zeroed(&shape![2, 3]);
+        let b = HostTensor::<f32>::new_zeroed(&shape![2, 3]);
+        assert_eq!(HostTensor::einsum("ij,jk->ik", &[&a]).unwrap_err(), EinsumError::Operands { expected: 2, found: 1 });
+        assert_eq!(HostTensor::einsum("ij,jk->ik", &[&a, &b]).unwrap_err(), EinsumError::Size { label: 'j', expected: 3, found: 2 });
+        assert!(matches!(HostTensor::einsum("i->i", &[&a]), Err(EinsumError::Rank { operand: 0, labels: 1, .. })));
+        assert!(matches!(HostTensor::einsum("i.j->", &[&a]), Err(EinsumError::Syntax(_))));
+        assert!(matches!(HostTensor::einsum("ij->k", &[&a]), Err(EinsumError::Syntax(_))));
+        assert!(matches!(HostTensor::einsum("ij->ii", &[&a]), Err(EinsumError::Syntax(_))));
+        assert_eq!(HostTensor::einsum("ijk->kji", &[&a]).unwrap().shape(), &shape![1, 3, 2]);
+    }
+}
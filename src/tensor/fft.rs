@@ -0,0 +1,277 @@
+use num_complex::Complex;
+use crate::{Float, Cast, Shape, Tensor};
+#[cfg(feature = "rustfft")]
+use rustfft::{FftNum, FftPlanner, FftDirection};
+#[cfg(feature = "rustfft")]
+use crate::HostTensor;
+#[cfg(feature = "device")]
+use crate::{Interop, Buffer, DeviceBuffer, DeviceTensor, kernel};
+use super::axis_blocks;
+
+
+/// Floating-point type of complex values that can be transformed.
+///
+/// With `rustfft` feature it is also required to be supported by `rustfft`,
+/// so that device tensors can be transformed on the host if the device does not support the type.
+#[cfg(feature = "rustfft")]
+pub trait FftFloat: Float + FftNum {}
+#[cfg(feature = "rustfft")]
+impl<T: Float + FftNum> FftFloat for T {}
+/// Floating-point type of complex values that can be transformed.
+#[cfg(not(feature = "rustfft"))]
+pub trait FftFloat: Float {}
+#[cfg(not(feature = "rustfft"))]
+impl<T: Float> FftFloat for T {}
+
+/// Checks that axes are distinct.
+fn check_axes(axes: &[usize]) {
+    for (i, a) in axes.iter().enumerate() {
+        assert!(!axes[..i].contains(a), "Axis {} is specified more than once", a);
+    }
+}
+
+/// Shape of the spectrum of real tensor, the size of the first axis is `n/2 + 1`.
+fn half_shape(shape: &Shape, axes: &[usize]) -> Shape {
+    check_axes(axes);
+    assert!(!axes.is_empty(), "At least one axis is required for real transform");
+    assert!(shape[axes[0]] > 0, "Axis {} of size 0 cannot be transformed", axes[0]);
+    let mut shape = shape.clone();
+    shape[axes[0]] = shape[axes[0]] / 2 + 1;
+    shape
+}
+
+/// Shape of real tensor restored from spectrum with size `n` of the first axis.
+fn full_shape(shape: &Shape, axes: &[usize], n: usize) -> Shape {
+    check_axes(axes);
+    assert!(!axes.is_empty(), "At least one axis is required for real transform");
+    let m = shape[axes[0]];
+    assert_eq!(m, n / 2 + 1, "Spectrum of {} real values must have {} values along axis {}, got {}", n, n / 2 + 1, axes[0], m);
+    let mut shape = shape.clone();
+    shape[axes[0]] = n;
+    shape
+}
+
+/// Computes FFT along the axis in place.
+#[cfg(feature = "rustfft")]
+fn fft_axis<T: Float + FftNum>(planner: &mut FftPlanner<T>, values: &mut [Complex<T>], shape: &Shape, axis: usize, direction: FftDirection) {
+    let (inner, n, outer) = axis_blocks(shape, axis);
+    if n <= 1 || values.is_empty() {
+        return;
+    }
+    // Lanes along the axis are gathered into contiguous buffer to transform them all at once.
+    let mut lanes = Vec::with_capacity(values.len());
+    for o in 0..outer {
+        for x in 0..inner {
+            lanes.extend((0..n).map(|k| values[x + inner*(k + n*o)]));
+        }
+    }
+    planner.plan_fft(n, direction).process(&mut lanes);
+    let norm = match direction {
+        FftDirection::Forward => T::one(),
+        FftDirection::Inverse => T::one() / T::from_usize(n).unwrap(),
+    };
+    for (l, lane) in lanes.chunks(n).enumerate() {
+        let (x, o) = (l % inner, l / inner);
+        for (k, &v) in lane.iter().enumerate() {
+            values[x + inner*(k + n*o)] = v * norm;
+        }
+    }
+}
+
+#[cfg(feature = "rustfft")]
+impl<T: Float + FftNum> HostTensor<Complex<T>> {
+    /// Computes FFT along each of specified axes.
+    fn fft_axes(&self, axes: &[usize], direction: FftDirection) -> Self {
+        check_axes(axes);
+        let mut values = self.buffer().as_slice().to_vec();
+        let mut planner = FftPlanner::new();
+        for &axis in axes {
+            fft_axis(&mut planner, &mut values, self.shape(), axis, direction);
+        }
+        Self::from_vec(self.shape(), values)
+    }
+
+    /// Computes forward FFT along specified axes, the result is not normalized.
+    ///
+    /// Transforms follow NumPy conventions: value `k` of spectrum along axis of size `n` is
+    /// `sum(x[j] * exp(-2 pi i j k / n))` over `j`, axes are transformed in the specified order.
+    pub fn fft(&self, axes: &[usize]) -> Self {
+        self.fft_axes(axes, FftDirection::Forward)
+    }
+    /// Computes inverse FFT along specified axes normalized by the number of transformed values.
+    ///
+    /// It is the exact inverse of [`HostTensor::fft`], so that `a.fft(axes).ifft(axes)` gives `a`.
+    pub fn ifft(&self, axes: &[usize]) -> Self {
+        self.fft_axes(axes, FftDirection::Inverse)
+    }
+
+    /// Computes inverse of [`HostTensor::rfft`], `n` is the size of the first of `axes` in result.
+    ///
+    /// Spectrum is assumed to be Hermitian-symmetric along the first axis, so imaginary part of the result is dropped.
+    pub fn irfft(&self, axes: &[usize], n: usize) -> HostTensor<T> where Complex<T>: Cast<T> {
+        let shape = full_shape(self.shape(), axes, n);
+        let spectrum = self.fft_axes(&axes[1..], FftDirection::Inverse);
+        let (inner, m, outer) = axis_blocks(self.shape(), axes[0]);
+        let src = spectrum.buffer().as_slice();
+        let mut full = Vec::with_capacity(shape.content());
+        for o in 0..outer {
+            for k in 0..n {
+                full.extend((0..inner).map(|x| if k < m {
+                    src[x + inner*(k + m*o)]
+                } else {
+                    src[x + inner*(n - k + m*o)].conj()
+                }));
+            }
+        }
+        let mut planner = FftPlanner::new();
+        fft_axis(&mut planner, &mut full, &shape, axes[0], FftDirection::Inverse);
+        HostTensor::from_vec(&shape, full).cast()
+    }
+}
+
+#[cfg(feature = "rustfft")]
+impl<T: Float + FftNum + Cast<Complex<T>>> HostTensor<T> {
+    /// Computes forward FFT of real values along specified axes.
+    ///
+    /// Only `n/2 + 1` non-negative frequencies are kept along the first of `axes`,
+    /// other values are determined by Hermitian symmetry. Normalization is the same as in [`HostTensor::fft`].
+    pub fn rfft(&self, axes: &[usize]) -> HostTensor<Complex<T>> {
+        let shape = half_shape(self.shape(), axes);
+        let spectrum = self.cast::<Complex<T>>().fft(axes);
+        spectrum.split(&[shape[axes[0]], self.shape()[axes[0]] - shape[axes[0]]], axes[0]).swap_remove(0)
+    }
+}
+
+#[cfg(feature = "device")]
+impl<T: FftFloat + Interop> DeviceTensor<Complex<T>> where Complex<T>: Interop {
+    /// Computes FFT on the host if the device does not support the type.
+    #[cfg(feature = "rustfft")]
+    fn host_fft(&self, axes: &[usize], inverse: bool) -> Self {
+        let direction = if inverse { FftDirection::Inverse } else { FftDirection::Forward };
+        Self::from_host(self.context(), &self.to_host().fft_axes(axes, direction))
+    }
+    #[cfg(feature = "rustfft")]
+    fn host_irfft(&self, axes: &[usize], n: usize) -> DeviceTensor<T> where Complex<T>: Cast<T> {
+        DeviceTensor::from_host(self.context(), &self.to_host().irfft(axes, n))
+    }
+    #[cfg(not(feature = "rustfft"))]
+    fn host_fft(&self, _axes: &[usize], _inverse: bool) -> Self {
+        self.unsupported()
+    }
+    #[cfg(not(feature = "rustfft"))]
+    fn host_irfft(&self, _axes: &[usize], _n: usize) -> DeviceTensor<T> {
+        self.unsupported()
+    }
+    #[cfg(not(feature = "rustfft"))]
+    fn unsupported(&self) -> ! {
+        let dt = Complex::<T>::dev_type(self.context().size_repr());
+        panic!("Device does not support {:?}, enable `rustfft` feature to transform it on the host", dt);
+    }
+
+    /// Computes FFT along each of specified axes.
+    fn fft_axes(&self, axes: &[usize], inverse: bool) -> Self {
+        check_axes(axes);
+        let context = self.context();
+        if !context.supports::<Complex<T>>() {
+            return self.host_fft(axes, inverse);
+        }
+        let mut buffer = unsafe { DeviceBuffer::new_uninit_in(context, self.buffer().len()) };
+        buffer.copy_from(self.buffer());
+        for &axis in axes {
+            buffer = kernel::fft(&buffer, axis_blocks(self.shape(), axis), inverse);
+        }
+        Self::from_buffer(buffer, self.shape())
+    }
+
+    /// Computes forward FFT along specified axes, see [`HostTensor::fft`].
+    ///
+    /// If the device does not support the type then the transform is computed on the host with `rustfft` feature
+    /// and panics without it.
+    pub fn fft(&self, axes: &[usize]) -> Self {
+        self.fft_axes(axes, false)
+    }
+    /// Computes inverse FFT along specified axes, see [`HostTensor::ifft`].
+    pub fn ifft(&self, axes: &[usize]) -> Self {
+        self.fft_axes(axes, true)
+    }
+
+    /// Computes inverse of [`DeviceTensor::rfft`], see [`HostTensor::irfft`].
+    pub fn irfft(&self, axes: &[usize], n: usize) -> DeviceTensor<T> where Complex<T>: Cast<T> {
+        let shape = full_shape(self.shape(), axes, n);
+        if !self.context().supports::<Complex<T>>() {
+            return self.host_irfft(axes, n);
+        }
+        let spectrum = self.fft_axes(&axes[1..], true);
+        let mut full = unsafe { DeviceBuffer::new_uninit_in(self.context(), shape.content()) };
+        kernel::hermitian(spectrum.buffer(), &mut full, axis_blocks(self.shape(), axes[0]), n);
+        let full = kernel::fft(&full, axis_blocks(&shape, axes[0]), true);
+        Self::from_buffer(full, &shape).cast()
+    }
+}
+
+#[cfg(feature = "device")]
+impl<T: FftFloat + Interop + Cast<Complex<T>>> DeviceTensor<T> where Complex<T>: Interop {
+    /// Computes forward FFT of real values along specified axes, see [`HostTensor::rfft`].
+    pub fn rfft(&self, axes: &[usize]) -> DeviceTensor<Complex<T>> {
+        let shape = half_shape(self.shape(), axes);
+        let spectrum = self.cast::<Complex<T>>().fft(axes);
+        spectrum.split(&[shape[axes[0]], self.shape()[axes[0]] - shape[axes[0]]], axes[0]).swap_remove(0)
+    }
+}
+
+#[cfg(all(test, feature = "rustfft"))]
+mod tests {
+    use super::*;
+    use crate::{shape, sample, assert_close};
+
+    /// Direct DFT along the axis.
+    fn dft(a: &HostTensor<Complex<f64>>, axis: usize, sign: f64) -> HostTensor<Complex<f64>> {
+        let n = a.shape()[axis];
+        let values = a.buffer().as_slice();
+        let offset = |q: &[usize]| q.iter().enumerate().rev().fold(0, |o, (i, &x)| o * a.shape()[i] + x);
+        HostTensor::from_fn(a.shape(), |p| {
+            let mut q = p.to_vec();
+            (0..n).map(|j| {
+                q[axis] = j;
+                let phase = sign * 2.0 * std::f64::consts::PI * (j * p[axis]) as f64 / n as f64;
+                values[offset(&q)] * Complex::from_polar(1.0, phase)
+            }).sum()
+        })
+    }
+
+    fn complex_sample(shape: &Shape) -> HostTensor<Complex<f64>> {
+        HostTensor::from_parts(&sample(shape, 0), &sample(shape, 1))
+    }
+
+    #[test]
+    fn complex() {
+        for n in [1, 2, 5, 12, 13] {
+            let a = complex_sample(&shape![3, n, 2]);
+            assert_close(&a.fft(&[1]), &dft(&a, 1, -1.0));
+            assert_close(&a.ifft(&[1]).fft(&[1]), &a);
+        }
+        let a = complex_sample(&shape![4, 3, 2]);
+        assert_close(&a.fft(&[2, 0]), &dft(&dft(&a, 0, -1.0), 2, -1.0));
+        assert_close(&a.fft(&[0, 1, 2]).ifft(&[1, 2, 0]), &a);
+        assert_close(&a.fft(&[]), &a);
+    }
+
+    #[test]
+    fn real() {
+        for n in [1, 6, 7] {
+            let a = HostTensor::from_fn(&shape![n, 3], |p| (p[0] as f64 - 2.0 * p[1] as f64).sin());
+            let s = a.rfft(&[0, 1]);
+            assert_eq!(s.shape(), &shape![n / 2 + 1, 3]);
+            assert_close(&s, &a.cast::<Complex<f64>>().fft(&[0, 1]).split(&[n / 2 + 1, n - n / 2 - 1], 0)[0]);
+            let b = s.irfft(&[0, 1], n);
+            assert_eq!(b.shape(), a.shape());
+            assert!(a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < 1e-9));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn repeated_axes() {
+        complex_sample(&shape![2, 2]).fft(&[0, 0]);
+    }
+}
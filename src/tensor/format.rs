@@ -0,0 +1,207 @@
+use std::fmt::{self, Display, Debug, Formatter, Write};
+use crate::{Prm, Shape, Tensor, HostTensor};
+#[cfg(feature = "device")]
+use crate::{Interop, DeviceTensor};
+
+
+/// Tensors with more elements than this are summarized.
+const THRESHOLD: usize = 1000;
+/// Number of elements printed at the beginning and at the end of each axis of summarized tensor.
+const EDGE_ITEMS: usize = 3;
+
+/// Prints shape like a Python tuple, e.g. `(2, 3)`, `(4,)` or `()`.
+impl Display for Shape {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_char('(')?;
+        for (i, n) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", n)?;
+        }
+        if self.len() == 1 {
+            f.write_char(',')?;
+        }
+        f.write_char(')')
+    }
+}
+
+fn display_item<T: Display>(x: &T, f: &Formatter) -> String {
+    match (f.precision(), f.sign_plus()) {
+        (Some(p), false) => format!("{:.*}", p, x),
+        (Some(p), true) => format!("{:+.*}", p, x),
+        (None, false) => format!("{}", x),
+        (None, true) => format!("{:+}", x),
+    }
+}
+fn debug_item<T: Debug>(x: &T, f: &Formatter) -> String {
+    match (f.precision(), f.sign_plus()) {
+        (Some(p), false) => format!("{:.*?}", p, x),
+        (Some(p), true) => format!("{:+.*?}", p, x),
+        (None, false) => format!("{:?}", x),
+        (None, true) => format!("{:+?}", x),
+    }
+}
+
+/// Printer of tensor elements in nested brackets.
+///
+/// Axis 0 is the outermost one, so element at position `(i, j)` is printed in `i`-th row and `j`-th column.
+struct Printer<'a, T> {
+    data: &'a [T],
+    dims: &'a [usize],
+    strides: Vec<usize>,
+    /// Indices of printed elements along each axis, `None` stands for `...`.
+    shown: Vec<Vec<Option<usize>>>,
+    /// Elements are separated by commas.
+    commas: bool,
+}
+
+impl<'a, T> Printer<'a, T> {
+    fn new(shape: &'a Shape, data: &'a [T], summarize: bool, commas: bool) -> Self {
+        let dims = shape.as_slice();
+        let strides = dims.iter().scan(1, |s, &n| { let t = *s; *s *= n; Some(t) }).collect();
+        let summarize = summarize && shape.content() > THRESHOLD;
+        let shown = dims.iter().map(|&n| {
+            if summarize && n > 2*EDGE_ITEMS {
+                (0..EDGE_ITEMS).map(Some).chain(Some(None)).chain(((n - EDGE_ITEMS)..n).map(Some)).collect()
+            } else {
+                (0..n).map(Some).collect()
+            }
+        }).collect();
+        Self { data, dims, strides, shown, commas }
+    }
+
+    /// Calls function for each printed element.
+    fn visit<F: FnMut(&T)>(&self, axis: usize, offset: usize, f: &mut F) {
+        if axis == self.dims.len() {
+            f(&self.data[offset]);
+        } else {
+            for &i in self.shown[axis].iter().flatten() {
+                self.visit(axis + 1, offset + i*self.strides[axis], f);
+            }
+        }
+    }
+
+    fn write<F: Fn(&T, &Formatter) -> String>(&self, f: &mut Formatter, item: F) -> fmt::Result {
+        let mut width = f.width().unwrap_or(0);
+        self.visit(0, 0, &mut |x| width = width.max(item(x, f).chars().count()));
+        self.write_axis(f, &item, width, 0, 0)
+    }
+
+    fn write_axis<F: Fn(&T, &Formatter) -> String>(&self, f: &mut Formatter, item: &F, width: usize, axis: usize, offset: usize) -> fmt::Result {
+        let len = self.dims.len();
+        if axis == len {
+            let text = item(&self.data[offset], f);
+            let pad = width - text.chars().count();
+            let (left, right) = match f.align() {
+                Some(fmt::Alignment::Left) => (0, pad),
+                Some(fmt::Alignment::Center) => (pad / 2, pad - pad / 2),
+                _ => (pad, 0),
+            };
+            let fill = f.fill();
+            (0..left).try_for_each(|_| f.write_char(fill))?;
+            f.write_str(&text)?;
+            return (0..right).try_for_each(|_| f.write_char(fill));
+        }
+        f.write_char('[')?;
+        for (k, index) in self.shown[axis].iter().enumerate() {
+            if k > 0 {
+                if self.commas {
+                    f.write_char(',')?;
+                }
+                if axis + 1 == len {
+                    f.write_char(' ')?;
+                } else {
+                    (0..(len - axis - 1)).try_for_each(|_| f.write_char('\n'))?;
+                    (0..(axis + 1)).try_for_each(|_| f.write_char(' '))?;
+                }
+            }
+            match index {
+                Some(i) => self.write_axis(f, item, width, axis + 1, offset + i*self.strides[axis])?,
+                None => f.write_str("...")?,
+            }
+        }
+        f.write_char(']')
+    }
+}
+
+/// Prints tensor in NumPy style with elements separated by spaces, e.g. `[[1 2 3]\n [4 5 6]]`.
+///
+/// Precision, width, alignment and sign flags are applied to each element,
+/// and all elements are padded to the same width.
+/// Tensors with more than 1000 elements are summarized with `...`, alternate flag `{:#}` disables summarization.
+impl<T: Prm + Display> Display for HostTensor<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Printer::new(self.shape(), self.buffer().as_slice(), !f.alternate(), false).write(f, display_item)
+    }
+}
+/// Prints tensor the same way as `Display` but elements are separated by commas and formatted with `Debug`.
+impl<T: Prm + Debug> Debug for HostTensor<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Printer::new(self.shape(), self.buffer().as_slice(), !f.alternate(), true).write(f, debug_item)
+    }
+}
+
+/// Tensor is downloaded from device and printed the same way as [`HostTensor`].
+#[cfg(feature = "device")]
+impl<T: Prm + Interop + Display> Display for DeviceTensor<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut data = vec![T::zero(); self.shape().content()];
+        self.try_load(&mut data).map_err(|_| fmt::Error)?;
+        Printer::new(self.shape(), &data, !f.alternate(), false).write(f, display_item)
+    }
+}
+/// Tensor is downloaded from device and printed the same way as [`HostTensor`].
+#[cfg(feature = "device")]
+impl<T: Prm + Interop + Debug> Debug for DeviceTensor<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut data = vec![T::zero(); self.shape().content()];
+        self.try_load(&mut data).map_err(|_| fmt::Error)?;
+        Printer::new(self.shape(), &data, !f.alternate(), true).write(f, debug_item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape;
+
+    #[test]
+    fn shape_() {
+        assert_eq!(format!("{}", shape![2, 3, 1]), "(2, 3)");
+        assert_eq!(format!("{}", shape![4]), "(4,)");
+        assert_eq!(format!("{}", shape![]), "()");
+    }
+
+    #[test]
+    fn display() {
+        let a = HostTensor::from_fn(&shape![2, 3], |p| (3*p[0] + p[1]) as i32);
+        assert_eq!(format!("{}", a), "[[0 1 2]\n [3 4 5]]");
+        assert_eq!(format!("{:?}", a), "[[0, 1, 2],\n [3, 4, 5]]");
+        assert_eq!(format!("{}", HostTensor::from_vec(&shape![], vec![7])), "7");
+        assert_eq!(format!("{}", HostTensor::<i32>::new_zeroed(&shape![0])), "[]");
+
+        let b = HostTensor::from_fn(&shape![2, 2, 2], |p| p[0] + p[1] + p[2]);
+        assert_eq!(format!("{}", b), "[[[0 1]\n  [1 2]]\n\n [[1 2]\n  [2 3]]]");
+    }
+
+    #[test]
+    fn flags() {
+        let a = HostTensor::from_vec(&shape![3], vec![1.0, -0.5, 10.25f64]);
+        assert_eq!(format!("{:.2}", a), "[ 1.00 -0.50 10.25]");
+        assert_eq!(format!("{:6.1}", a), "[   1.0   -0.5   10.2]");
+        assert_eq!(format!("{:<5}", a), "[1     -0.5  10.25]");
+        assert_eq!(format!("{:+}", a), "[    +1   -0.5 +10.25]");
+    }
+
+    #[test]
+    fn summarize() {
+        let a = HostTensor::from_fn(&shape![10, 200], |p| p[0]*1000 + p[1]);
+        let text = format!("{}", a);
+        assert!(text.starts_with("[[   0    1    2 ...  197  198  199]\n [1000 1001"));
+        assert!(text.contains("\n ...\n [7000"));
+        assert!(text.ends_with("9199]]"));
+        assert_eq!(text.lines().count(), 7);
+        assert_eq!(format!("{:#}", a).lines().count(), 10);
+    }
+}
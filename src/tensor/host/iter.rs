@@ -1,84 +1,352 @@
 use std::{
+    iter::FusedIterator,
+    marker::PhantomData,
     slice,
 };
 use crate::{
     Prm,
-    Tensor, HostTensor,
+    Shape, Tensor, HostTensor,
 };
 
-/// Iterator over host tensor content.
-pub struct HostTensorIter<'a, T: Prm> {
-    tensor: &'a HostTensor<T>,
+/// Strides of contiguous tensor of specified shape.
+pub(crate) fn contiguous_strides(shape: &Shape) -> Vec<usize> {
+    shape.iter().scan(1, |stride, &len| {
+        let s = *stride;
+        *stride *= len;
+        Some(s)
+    }).collect()
+}
+
+/// Whether elements with specified strides are placed contiguously in memory.
+fn is_contiguous(shape: &Shape, strides: &[usize]) -> bool {
+    // Strides of axes of size 1 do not matter.
+    shape.iter().zip(strides.iter().zip(contiguous_strides(shape))).all(|(&len, (&s, c))| len == 1 || s == c)
+}
+
+/// Position of an element along with its offset in the buffer.
+struct Cursor {
     position: Vec<usize>,
-    first: bool,
-    exhausted: bool,
+    offset: usize,
 }
 
-/// Mutable iterator over host tensor content.
-pub type HostTensorIterMut<'a, T> = slice::IterMut<'a, T>;
+impl Cursor {
+    /// Moves to the next position, axis 0 varies fastest.
+    fn advance(&mut self, dims: &[usize], strides: &[usize]) {
+        for ((pos, &len), &stride) in self.position.iter_mut().zip(dims).zip(strides) {
+            *pos += 1;
+            self.offset += stride;
+            if *pos < len {
+                return;
+            }
+            *pos = 0;
+            self.offset -= len*stride;
+        }
+    }
+    /// Moves to the previous position.
+    fn retreat(&mut self, dims: &[usize], strides: &[usize]) {
+        for ((pos, &len), &stride) in self.position.iter_mut().zip(dims).zip(strides) {
+            if *pos > 0 {
+                *pos -= 1;
+                self.offset -= stride;
+                return;
+            }
+            *pos = len - 1;
+            self.offset += (len - 1)*stride;
+        }
+    }
+}
+
+/// Walks over positions of all elements of shape from both ends, axis 0 varies fastest.
+///
+/// Offsets are updated incrementally, so each step costs amortized constant time.
+pub(crate) struct Positions {
+    dims: Vec<usize>,
+    strides: Vec<usize>,
+    front: Cursor,
+    back: Cursor,
+    /// Cursor points to the already returned element and must be moved before the next one.
+    front_moved: bool,
+    back_moved: bool,
+    remaining: usize,
+}
+
+impl Positions {
+    /// Positions of elements of shape which offsets are `base` plus positions multiplied by `strides`.
+    pub(crate) fn new(shape: &Shape, base: usize, strides: &[usize]) -> Self {
+        assert!(strides.len() >= shape.len());
+        let dims = shape.as_slice().to_vec();
+        let strides = strides[..shape.len()].to_vec();
+        let last = dims.iter().map(|&len| len.saturating_sub(1)).collect::<Vec<_>>();
+        let back_offset = last.iter().zip(strides.iter()).fold(base, |offset, (x, stride)| offset + x*stride);
+        Self {
+            front: Cursor { position: vec![0; dims.len()], offset: base },
+            back: Cursor { position: last, offset: back_offset },
+            front_moved: false,
+            back_moved: false,
+            remaining: shape.content(),
+            dims,
+            strides,
+        }
+    }
+
+    /// Number of positions left.
+    pub(crate) fn len(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns the next position and its offset.
+    pub(crate) fn next(&mut self) -> Option<(&[usize], usize)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        if self.front_moved {
+            self.front.advance(&self.dims, &self.strides);
+        }
+        self.front_moved = true;
+        self.remaining -= 1;
+        Some((self.front.position.as_slice(), self.front.offset))
+    }
+
+    /// Returns the last position and its offset.
+    pub(crate) fn next_back(&mut self) -> Option<(&[usize], usize)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        if self.back_moved {
+            self.back.retreat(&self.dims, &self.strides);
+        }
+        self.back_moved = true;
+        self.remaining -= 1;
+        Some((self.back.position.as_slice(), self.back.offset))
+    }
+}
+
+enum Inner<'a, T: Prm> {
+    Contiguous(slice::Iter<'a, T>),
+    Strided(&'a [T], Positions),
+}
+
+/// Iterator over host tensor content.
+///
+/// Elements that are placed contiguously in memory are iterated as a plain slice.
+pub struct HostTensorIter<'a, T: Prm> {
+    inner: Inner<'a, T>,
+}
 
 impl <'a, T: Prm> HostTensorIter<'a, T> {
     /// Create iterator over specified tensor.
     pub(crate) fn new(tensor: &'a HostTensor<T>) -> Self {
-        let mut position = Vec::<usize>::new();
-        position.resize(tensor.shape().len(), 0);
-        Self {
-            tensor,
-            position,
-            first: true,
-            exhausted: false,
-        }
-    }
-
-    fn next_position(&mut self) -> Option<&[usize]> {
-        let shape = self.tensor.shape();
-        if self.exhausted {
-            None
-        } else if self.first {
-            if shape.content() > 0 {
-                self.first = false;
-                Some(self.position.as_slice())
-            } else {
-                self.exhausted = true;
-                None
-            }
+        Self::contiguous(&tensor.buffer().as_slice()[..tensor.shape().content()])
+    }
+    fn contiguous(values: &'a [T]) -> Self {
+        Self { inner: Inner::Contiguous(values.iter()) }
+    }
+    /// Create iterator over elements of `values` at `offset` plus positions multiplied by `strides`.
+    pub(crate) fn strided(values: &'a [T], shape: &Shape, offset: usize, strides: &[usize]) -> Self {
+        let content = shape.content();
+        if is_contiguous(shape, strides) || content == 0 {
+            Self::contiguous(&values[offset..(offset + content)])
         } else {
-            let mut carry = true;
-            for i in 0..shape.len() {
-                if carry {
-                    carry = false;
-                    let mut pos = self.position[i] + 1;
-                    if pos >= shape[i] {
-                        pos = 0;
-                        carry = true;
-                    }
-                    self.position[i] = pos;
-                }
-            }
-            if carry {
-                self.exhausted = true;
-                None
-            } else {
-                Some(self.position.as_slice())
-            }
+            Self { inner: Inner::Strided(values, Positions::new(shape, offset, strides)) }
         }
     }
-
-    fn next_index(&mut self) -> Option<usize> {
-        let shape = self.tensor.shape().clone();
-        self.next_position().map(|slice| {
-            slice.iter().zip(shape.iter())
-            .fold((1, 0), |(stride, pos), (x, len)| {
-                (stride*len, pos + stride*x)
-            }).1
-        })
-    }
 }
 
 impl<'a, T: Prm> Iterator for HostTensorIter<'a, T> {
     type Item = &'a T;
     fn next(&mut self) -> Option<Self::Item> {
-        self.next_index()
-        .map(|pos| &self.tensor.buffer().as_slice()[pos])
+        match &mut self.inner {
+            Inner::Contiguous(iter) => iter.next(),
+            Inner::Strided(values, positions) => positions.next().map(|(_, offset)| &values[offset]),
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: Prm> DoubleEndedIterator for HostTensorIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            Inner::Contiguous(iter) => iter.next_back(),
+            Inner::Strided(values, positions) => positions.next_back().map(|(_, offset)| &values[offset]),
+        }
+    }
+}
+
+impl<'a, T: Prm> ExactSizeIterator for HostTensorIter<'a, T> {
+    fn len(&self) -> usize {
+        match &self.inner {
+            Inner::Contiguous(iter) => iter.len(),
+            Inner::Strided(_, positions) => positions.len(),
+        }
+    }
+}
+
+impl<'a, T: Prm> FusedIterator for HostTensorIter<'a, T> {}
+
+enum InnerMut<'a, T: Prm> {
+    Contiguous(slice::IterMut<'a, T>),
+    Strided(*mut T, usize, Positions, PhantomData<&'a mut T>),
+}
+
+/// Mutable iterator over host tensor content.
+///
+/// Elements that are placed contiguously in memory are iterated as a plain slice.
+pub struct HostTensorIterMut<'a, T: Prm> {
+    inner: InnerMut<'a, T>,
+}
+
+impl <'a, T: Prm> HostTensorIterMut<'a, T> {
+    /// Create mutable iterator over specified tensor, its buffer is cloned if it is shared.
+    pub(crate) fn new(tensor: &'a mut HostTensor<T>) -> Self {
+        let strides = contiguous_strides(tensor.shape());
+        let shape = tensor.shape().clone();
+        Self::strided(tensor.buffer_mut().as_mut_slice(), &shape, 0, &strides)
+    }
+    fn contiguous(values: &'a mut [T]) -> Self {
+        Self { inner: InnerMut::Contiguous(values.iter_mut()) }
+    }
+    /// Create mutable iterator over elements of `values` at `offset` plus positions multiplied by `strides`.
+    ///
+    /// Strides must map different positions to different offsets.
+    pub(crate) fn strided(values: &'a mut [T], shape: &Shape, offset: usize, strides: &[usize]) -> Self {
+        let content = shape.content();
+        if is_contiguous(shape, strides) || content == 0 {
+            Self::contiguous(&mut values[offset..(offset + content)])
+        } else {
+            let positions = Positions::new(shape, offset, strides);
+            Self { inner: InnerMut::Strided(values.as_mut_ptr(), values.len(), positions, PhantomData) }
+        }
+    }
+
+    fn item(values: *mut T, len: usize, offset: usize) -> &'a mut T {
+        assert!(offset < len);
+        // Different positions have different offsets, so each element is borrowed only once.
+        unsafe { &mut *values.add(offset) }
+    }
+}
+
+impl<'a, T: Prm> Iterator for HostTensorIterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            InnerMut::Contiguous(iter) => iter.next(),
+            InnerMut::Strided(values, len, positions, _) => positions.next().map(|(_, offset)| Self::item(*values, *len, offset)),
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
     }
 }
+
+impl<'a, T: Prm> DoubleEndedIterator for HostTensorIterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            InnerMut::Contiguous(iter) => iter.next_back(),
+            InnerMut::Strided(values, len, positions, _) => positions.next_back().map(|(_, offset)| Self::item(*values, *len, offset)),
+        }
+    }
+}
+
+impl<'a, T: Prm> ExactSizeIterator for HostTensorIterMut<'a, T> {
+    fn len(&self) -> usize {
+        match &self.inner {
+            InnerMut::Contiguous(iter) => iter.len(),
+            InnerMut::Strided(_, _, positions, _) => positions.len(),
+        }
+    }
+}
+
+impl<'a, T: Prm> FusedIterator for HostTensorIterMut<'a, T> {}
+
+/// Iterator over host tensor content along with positions of elements.
+///
+/// Position is returned as a new vector because iterator cannot lend its own storage.
+pub struct HostTensorIndexedIter<'a, T: Prm> {
+    values: &'a [T],
+    positions: Positions,
+}
+
+impl <'a, T: Prm> HostTensorIndexedIter<'a, T> {
+    pub(crate) fn new(tensor: &'a HostTensor<T>) -> Self {
+        Self {
+            values: tensor.buffer().as_slice(),
+            positions: Positions::new(tensor.shape(), 0, &contiguous_strides(tensor.shape())),
+        }
+    }
+}
+
+impl<'a, T: Prm> Iterator for HostTensorIndexedIter<'a, T> {
+    type Item = (Vec<usize>, &'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let values = self.values;
+        self.positions.next().map(|(p, offset)| (p.to_vec(), &values[offset]))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.positions.len(), Some(self.positions.len()))
+    }
+}
+
+impl<'a, T: Prm> DoubleEndedIterator for HostTensorIndexedIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let values = self.values;
+        self.positions.next_back().map(|(p, offset)| (p.to_vec(), &values[offset]))
+    }
+}
+
+impl<'a, T: Prm> ExactSizeIterator for HostTensorIndexedIter<'a, T> {}
+
+impl<'a, T: Prm> FusedIterator for HostTensorIndexedIter<'a, T> {}
+
+/// Mutable iterator over host tensor content along with positions of elements.
+pub struct HostTensorIndexedIterMut<'a, T: Prm> {
+    values: *mut T,
+    len: usize,
+    positions: Positions,
+    phantom: PhantomData<&'a mut T>,
+}
+
+impl <'a, T: Prm> HostTensorIndexedIterMut<'a, T> {
+    /// Create mutable iterator over specified tensor, its buffer is cloned if it is shared.
+    pub(crate) fn new(tensor: &'a mut HostTensor<T>) -> Self {
+        let positions = Positions::new(tensor.shape(), 0, &contiguous_strides(tensor.shape()));
+        let values = tensor.buffer_mut().as_mut_slice();
+        Self {
+            values: values.as_mut_ptr(),
+            len: values.len(),
+            positions,
+            phantom: PhantomData,
+        }
+    }
+
+    fn item(values: *mut T, len: usize, (position, offset): (&[usize], usize)) -> (Vec<usize>, &'a mut T) {
+        assert!(offset < len);
+        // Different positions have different offsets, so each element is borrowed only once.
+        (position.to_vec(), unsafe { &mut *values.add(offset) })
+    }
+}
+
+impl<'a, T: Prm> Iterator for HostTensorIndexedIterMut<'a, T> {
+    type Item = (Vec<usize>, &'a mut T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (values, len) = (self.values, self.len);
+        self.positions.next().map(|p| Self::item(values, len, p))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.positions.len(), Some(self.positions.len()))
+    }
+}
+
+impl<'a, T: Prm> DoubleEndedIterator for HostTensorIndexedIterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (values, len) = (self.values, self.len);
+        self.positions.next_back().map(|p| Self::item(values, len, p))
+    }
+}
+
+impl<'a, T: Prm> ExactSizeIterator for HostTensorIndexedIterMut<'a, T> {}
+
+impl<'a, T: Prm> FusedIterator for HostTensorIndexedIterMut<'a, T> {}
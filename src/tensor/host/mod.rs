@@ -4,5 +4,10 @@ pub use iter::*;
 mod tensor;
 pub use tensor::*;
 
+mod view;
+pub use view::*;
+
+mod random;
+
 #[cfg(test)]
 mod tests;
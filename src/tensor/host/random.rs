@@ -0,0 +1,60 @@
+#[cfg(feature = "rand")]
+use rand::{Rng, distributions::Distribution};
+use crate::{
+    Prm, Float, Shape,
+    Philox, RandomFloat,
+    HostTensor,
+    random::{uniform, below, check_bounds, normal, bernoulli, bernoulli_threshold},
+};
+
+
+impl<T: Prm> HostTensor<T> {
+    /// Create tensor of values sampled from distribution using specified random number generator.
+    #[cfg(feature = "rand")]
+    pub fn sample<D: Distribution<T>, R: Rng + ?Sized>(shape: &Shape, distr: &D, rng: &mut R) -> Self {
+        Self::from_vec(shape, (0..shape.content()).map(|_| distr.sample(rng)).collect())
+    }
+
+    /// Create tensor which `i`-th element is computed from `i`-th block of [`Philox`] generator.
+    fn from_blocks<F: FnMut([u32; 4]) -> T>(shape: &Shape, seed: u64, mut f: F) -> Self {
+        let gen = Philox::new(seed);
+        Self::from_vec(shape, (0..shape.content() as u64).map(|i| f(gen.block(i))).collect())
+    }
+}
+
+impl<T: Prm + Float> HostTensor<T> where T::Acc: RandomFloat {
+    /// Create tensor of values uniformly distributed in `[low, high)`.
+    ///
+    /// Values are produced by [`Philox`] generator with specified seed,
+    /// [`DeviceTensor::uniform_in`](crate::DeviceTensor::uniform_in) produces the same values.
+    /// Panics if bounds are not finite or `low` is not less than `high`.
+    pub fn uniform(shape: &Shape, low: T, high: T, seed: u64) -> Self {
+        check_bounds(low, high);
+        let top = below(high);
+        let (low, high) = (low.to_acc(), high.to_acc());
+        Self::from_blocks(shape, seed, |b| {
+            // Value may be rounded up to `high`, especially for half-precision types.
+            let x = T::from_acc(low + (high - low)*uniform(b));
+            if x.to_acc() < high { x } else { top }
+        })
+    }
+    /// Create tensor of normally distributed values with specified mean and standard deviation.
+    ///
+    /// Values are produced by [`Philox`] generator with specified seed,
+    /// [`DeviceTensor::normal_in`](crate::DeviceTensor::normal_in) produces the same values up to rounding errors.
+    pub fn normal(shape: &Shape, mean: T, std: T, seed: u64) -> Self {
+        let (mean, std) = (mean.to_acc(), std.to_acc());
+        Self::from_blocks(shape, seed, |b| T::from_acc(mean + std*normal(b)))
+    }
+}
+
+impl HostTensor<bool> {
+    /// Create tensor of values that are `true` with probability `p`.
+    ///
+    /// Values are produced by [`Philox`] generator with specified seed,
+    /// [`DeviceTensor::bernoulli_in`](crate::DeviceTensor::bernoulli_in) produces the same values.
+    pub fn bernoulli(shape: &Shape, p: f64, seed: u64) -> Self {
+        let threshold = bernoulli_threshold(p);
+        Self::from_blocks(shape, seed, |b| bernoulli(b, threshold))
+    }
+}
@@ -1,9 +1,17 @@
+use std::{
+    convert::TryFrom,
+    ops::{Index, IndexMut},
+};
 use crate::{
-    Prm,
+    Prm, Num, Float, Cast,
     HostBuffer,
     Shape, Tensor, CommonTensor,
     HostTensorIter, HostTensorIterMut,
+    HostTensorIndexedIter, HostTensorIndexedIterMut,
+    num::{Zero, One},
 };
+#[cfg(feature = "mmap")]
+use crate::MappedBuffer;
 
 
 type InnerTensor<T> = CommonTensor<T, HostBuffer<T>>;
@@ -28,6 +36,47 @@ impl<T: Prm> HostTensor<T> {
     pub fn new_zeroed(shape: &Shape) -> Self {
         Self::new_zeroed_in(&(), shape)
     }
+    /// Create tensor filled with ones
+    pub fn new_ones(shape: &Shape) -> Self {
+        Self::new_ones_in(&(), shape)
+    }
+
+    /// Create tensor of specified shape that takes ownership of the vector.
+    /// Vector length must be equal to the number of items in shape.
+    pub fn from_vec(shape: &Shape, vec: Vec<T>) -> Self {
+        Self::from_buffer(HostBuffer::from(vec), shape)
+    }
+    /// Create tensor of specified shape from memory-mapped buffer without reading it.
+    /// Buffer length must be equal to the number of items in shape.
+    #[cfg(feature = "mmap")]
+    pub fn from_mapped(buffer: MappedBuffer<T>, shape: &Shape) -> Self {
+        Self::from_buffer(HostBuffer::from(buffer), shape)
+    }
+    /// Create tensor of specified shape that takes ownership of the buffer.
+    pub(crate) fn from_buffer(buffer: HostBuffer<T>, shape: &Shape) -> Self {
+        Self { inner: InnerTensor::<T>::from_buffer(buffer, shape) }
+    }
+    /// Create tensor of specified shape and fill it with values returned from function of element position.
+    pub fn from_fn<F: FnMut(&[usize]) -> T>(shape: &Shape, mut f: F) -> Self {
+        let mut vec = Vec::with_capacity(shape.content());
+        let mut position = vec![0; shape.len()];
+        for _ in 0..shape.content() {
+            vec.push(f(&position));
+            for (p, &n) in position.iter_mut().zip(shape.iter()) {
+                *p += 1;
+                if *p < n {
+                    break;
+                }
+                *p = 0;
+            }
+        }
+        Self::from_vec(shape, vec)
+    }
+
+    /// Create identity matrix of size `n`.
+    pub fn eye(n: usize) -> Self {
+        Self::from_fn(&[n, n].as_ref().into(), |p| if p[0] == p[1] { T::one() } else { T::zero() })
+    }
 
     /// Provides access to underlying buffer.
     pub(crate) fn buffer(&self) -> &HostBuffer<T> {
@@ -38,14 +87,141 @@ impl<T: Prm> HostTensor<T> {
         self.inner.buffer_mut()
     }
 
+    /// Extracts flattened data, copies it only if it is shared with other tensors or memory-mapped.
+    pub fn into_vec(self) -> Vec<T> {
+        self.inner.into_buffer().into_vec()
+    }
+
+    /// Joins tensors along the existing axis, all other dimensions must be equal.
+    pub fn concat(tensors: &[&Self], axis: usize) -> Self {
+        Self { inner: InnerTensor::concat(&tensors.iter().map(|t| &t.inner).collect::<Vec<_>>(), axis) }
+    }
+    /// Joins tensors of the same shape along the new axis inserted at specified position.
+    pub fn stack(tensors: &[&Self], axis: usize) -> Self {
+        Self { inner: InnerTensor::stack(&tensors.iter().map(|t| &t.inner).collect::<Vec<_>>(), axis) }
+    }
+    /// Splits tensor along the axis into parts of specified sizes, sizes must sum up to the size of axis.
+    pub fn split(&self, sizes: &[usize], axis: usize) -> Vec<Self> {
+        self.inner.split(sizes, axis).into_iter().map(|inner| Self { inner }).collect()
+    }
+    /// Splits tensor along the axis into at most `n` parts of the same size, the last part may be smaller.
+    pub fn chunk(&self, n: usize, axis: usize) -> Vec<Self> {
+        self.inner.chunk(n, axis).into_iter().map(|inner| Self { inner }).collect()
+    }
+
     /// Iterator over flatten tensor.
     pub fn iter<'a>(&'a self) -> HostTensorIter<'a, T> {
         HostTensorIter::new(self)
     }
     /// Mutable iterator over flatten tensor.
     pub fn iter_mut<'a>(&'a mut self) -> HostTensorIterMut<'a, T> {
-        // FIXME: Make tensor plain when using strides.
-        self.buffer_mut().as_mut_slice().iter_mut()
+        HostTensorIterMut::new(self)
+    }
+    /// Iterator over flatten tensor that also yields positions of elements.
+    pub fn indexed_iter<'a>(&'a self) -> HostTensorIndexedIter<'a, T> {
+        HostTensorIndexedIter::new(self)
+    }
+    /// Mutable iterator over flatten tensor that also yields positions of elements.
+    pub fn indexed_iter_mut<'a>(&'a mut self) -> HostTensorIndexedIterMut<'a, T> {
+        HostTensorIndexedIterMut::new(self)
+    }
+
+    /// Offset of element at specified position in buffer.
+    ///
+    /// Position must contain an index for each axis, extra indices must be zero.
+    fn offset(&self, position: &[usize]) -> Option<usize> {
+        let shape = self.shape();
+        if position.len() < shape.len() {
+            return None;
+        }
+        position.iter().enumerate().try_fold((1, 0), |(stride, offset), (i, &x)| {
+            if x < shape[i] { Some((stride*shape[i], offset + stride*x)) } else { None }
+        }).map(|(_, offset)| offset)
+    }
+    /// Reference to element at specified position, `None` if position is out of tensor.
+    pub fn get(&self, position: &[usize]) -> Option<&T> {
+        self.offset(position).map(|i| &self.buffer().as_slice()[i])
+    }
+    /// Mutable reference to element at specified position, `None` if position is out of tensor.
+    pub fn get_mut(&mut self, position: &[usize]) -> Option<&mut T> {
+        self.offset(position).map(move |i| &mut self.buffer_mut().as_mut_slice()[i])
+    }
+
+    /// Converts tensor elements to another type.
+    /// See [`Cast`] for the conversion semantics.
+    pub fn cast<U: Prm>(&self) -> HostTensor<U> where T: Cast<U> {
+        let mut buffer = unsafe { HostBuffer::<U>::new_uninit(self.shape().content()) };
+        for (d, &s) in buffer.as_mut_slice().iter_mut().zip(self.iter()) {
+            *d = s.cast();
+        }
+        HostTensor { inner: CommonTensor::from_buffer(buffer, self.shape()) }
+    }
+}
+
+impl<T: Prm + Num + PartialOrd + Cast<f64> + Cast<u64>> HostTensor<T> where usize: Cast<T> {
+    /// Create 1-dimensional tensor of values from `start` (inclusive) to `stop` (exclusive) with `step` between them.
+    ///
+    /// Number of elements is `ceil((stop - start)/step)` (or zero if it is negative) and `i`-th element is `start + i*step`.
+    /// It is computed exactly for integer types, while bounds and step of floating-point types must be finite.
+    pub fn arange(start: T, stop: T, step: T) -> Self {
+        let len = arange_len(start, stop, step);
+        let values = if is_integer::<T>() {
+            // Accumulate to avoid overflow of `i*step` when all elements fit into type.
+            let mut value = start;
+            (0..len).map(|i| {
+                if i > 0 {
+                    value = value + step;
+                }
+                value
+            }).collect()
+        } else {
+            (0..len).map(|i| start + i.cast()*step).collect()
+        };
+        Self::from_vec(&[len].as_ref().into(), values)
+    }
+}
+
+/// Whether numeric type is integer, i.e. its division truncates.
+fn is_integer<T: Prm + Num>() -> bool {
+    let (zero, one) = (<T as Zero>::zero(), <T as One>::one());
+    one / (one + one) == zero
+}
+
+/// Number of elements in `arange` sequence.
+pub(crate) fn arange_len<T: Prm + Num + PartialOrd + Cast<f64> + Cast<u64>>(start: T, stop: T, step: T) -> usize {
+    let zero = <T as Zero>::zero();
+    assert!(step != zero, "Step must not be zero");
+    let len = if is_integer::<T>() {
+        // Integer type, differences are computed in two's complement which is exact for types of up to 64 bits.
+        let forward = step > zero;
+        let (from, to) = if forward { (start, stop) } else { (stop, start) };
+        if to <= from {
+            return 0;
+        }
+        let distance = Cast::<u64>::cast(to).wrapping_sub(from.cast());
+        let step = if forward { step.cast() } else { 0u64.wrapping_sub(step.cast()) };
+        (distance - 1) / step + 1
+    } else {
+        let (start, stop, step): (f64, f64, f64) = (start.cast(), stop.cast(), step.cast());
+        assert!(start.is_finite() && stop.is_finite() && step.is_finite(), "Bounds and step must be finite");
+        let len = ((stop - start) / step).ceil();
+        if len > 0.0 { len as u64 } else { 0 }
+    };
+    usize::try_from(len).ok().filter(|&len| len < usize::MAX).expect("Too many elements in sequence")
+}
+
+impl<T: Prm + Float> HostTensor<T> where usize: Cast<T::Acc> {
+    /// Create 1-dimensional tensor of `len` values evenly spaced from `start` to `stop` (both inclusive).
+    pub fn linspace(start: T, stop: T, len: usize) -> Self {
+        let (start, stop) = (start.to_acc(), stop.to_acc());
+        let delta = stop - start;
+        Self::from_vec(&[len].as_ref().into(), (0..len).map(|i| {
+            T::from_acc(if len > 1 {
+                start + delta*i.cast()/(len - 1).cast()
+            } else {
+                start
+            })
+        }).collect())
     }
 }
 
@@ -61,6 +237,9 @@ impl<T: Prm> Tensor<T> for HostTensor<T> {
     fn new_zeroed_in(_: &(), shape: &Shape) -> Self {
         Self { inner: InnerTensor::<T>::new_zeroed_in(&(), shape) }
     }
+    fn new_ones_in(_: &(), shape: &Shape) -> Self {
+        Self { inner: InnerTensor::<T>::new_ones_in(&(), shape) }
+    }
 
     fn shape(&self) -> &Shape {
         self.inner.shape()
@@ -77,3 +256,21 @@ impl<T: Prm> Tensor<T> for HostTensor<T> {
         self.inner.store(src);
     }
 }
+
+impl<T: Prm> Index<&[usize]> for HostTensor<T> {
+    type Output = T;
+    fn index(&self, position: &[usize]) -> &T {
+        match self.get(position) {
+            Some(x) => x,
+            None => panic!("Position {:?} is out of tensor of shape {}", position, self.shape()),
+        }
+    }
+}
+impl<T: Prm> IndexMut<&[usize]> for HostTensor<T> {
+    fn index_mut(&mut self, position: &[usize]) -> &mut T {
+        match self.offset(position) {
+            Some(i) => &mut self.buffer_mut().as_mut_slice()[i],
+            None => panic!("Position {:?} is out of tensor of shape {}", position, self.shape()),
+        }
+    }
+}
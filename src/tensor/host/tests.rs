@@ -1,4 +1,4 @@
-use crate::{prelude::*, Shape, HostTensor as Tensor};
+use crate::{prelude::*, values, Shape, HostTensor as Tensor};
 
 #[test]
 fn new_filled() {
@@ -46,3 +46,275 @@ fn iter_mut() {
         assert_eq!(a.buffer().as_slice()[i], i);
     }
 }
+
+#[test]
+fn iter_mut_shared() {
+    let a = Tensor::from_vec(&Shape::from([2, 2].as_ref()), vec![1, 2, 3, 4]);
+    let mut b = a.reshape(a.shape());
+    b.iter_mut().for_each(|x| *x *= 10);
+    assert_eq!(values(&a), [1, 2, 3, 4]);
+    assert_eq!(values(&b), [10, 20, 30, 40]);
+}
+
+#[test]
+fn iter_mut_strided() {
+    let mut values = (0..12).collect::<Vec<_>>();
+    // Elements at odd offsets from 1 to 7.
+    let mut it = crate::HostTensorIterMut::strided(&mut values, &Shape::from([2, 2].as_ref()), 1, &[2, 4]);
+    assert_eq!(it.len(), 4);
+    *it.next_back().unwrap() = -1;
+    it.for_each(|x| *x *= 10);
+    assert_eq!(values, [0, 10, 2, 30, 4, 50, 6, -1, 8, 9, 10, 11]);
+}
+
+#[test]
+fn indexed_iter() {
+    let mut a = Tensor::from_fn(&Shape::from([3, 2].as_ref()), |p| 10*p[0] + p[1]);
+    for (p, &v) in a.indexed_iter() {
+        assert_eq!(v, 10*p[0] + p[1]);
+    }
+    for (p, v) in a.indexed_iter_mut() {
+        *v += 100*(p[0] + p[1]);
+    }
+    assert_eq!(values(&a), [0, 110, 220, 101, 211, 321]);
+    assert_eq!(Tensor::<u8>::new_zeroed(&Shape::from([2, 0].as_ref())).indexed_iter().count(), 0);
+}
+
+#[test]
+fn iter_both_ends() {
+    let a = Tensor::from_fn(&Shape::from([3, 2].as_ref()), |p| 10*p[0] + p[1]);
+    let mut it = a.iter();
+    assert_eq!(it.len(), 6);
+    assert_eq!(it.next_back(), Some(&21));
+    assert_eq!(it.next(), Some(&0));
+    assert_eq!(it.len(), 4);
+    assert_eq!(it.rev().cloned().collect::<Vec<_>>(), [11, 1, 20, 10]);
+
+    let mut it = a.indexed_iter();
+    assert_eq!(it.next_back(), Some((vec![2, 1], &21)));
+    assert_eq!(it.len(), 5);
+    assert_eq!(it.map(|(p, _)| p).collect::<Vec<_>>(), [[0, 0], [1, 0], [2, 0], [0, 1], [1, 1]]);
+
+    let b = Tensor::from_fn(&Shape::from([3, 2, 2].as_ref()), |p| 100*p[0] + 10*p[1] + p[2]);
+    let lane = b.lanes(2).nth(4).unwrap();
+    let mut it = lane.iter();
+    assert_eq!(it.len(), 2);
+    assert_eq!(it.next_back(), Some(&111));
+    assert_eq!(it.next(), Some(&110));
+    assert_eq!((it.next(), it.next(), it.next_back()), (None, None, None));
+    let view = b.axis_iter(1).nth(1).unwrap();
+    assert_eq!(view.iter().rev().cloned().collect::<Vec<_>>(), [211, 111, 11, 210, 110, 10]);
+    assert_eq!(b.windows(&Shape::from([2, 2].as_ref())).rev().len(), 4);
+}
+
+#[test]
+fn get() {
+    let mut a = Tensor::from_fn(&Shape::from([3, 2].as_ref()), |p| 10*p[0] + p[1]);
+    assert_eq!(a.get(&[2, 1]), Some(&21));
+    assert_eq!(a.get(&[2, 1, 0]), Some(&21));
+    assert_eq!(a.get(&[2, 1, 1]), None);
+    assert_eq!(a.get(&[3, 0]), None);
+    assert_eq!(a.get(&[1]), None);
+    *a.get_mut(&[1, 1]).unwrap() = 7;
+    a[&[0, 1][..]] += 5;
+    assert_eq!(a[&[1, 1][..]], 7);
+    assert_eq!(values(&a), [0, 10, 20, 6, 7, 21]);
+}
+
+#[test]
+#[should_panic]
+fn index_out_of_range() {
+    let a = Tensor::<i32>::new_zeroed(&Shape::from([3, 2].as_ref()));
+    let _ = a[&[0, 2][..]];
+}
+
+fn view_values<T: crate::Prm>(view: crate::HostTensorView<T>) -> Vec<T> {
+    view.iter().cloned().collect()
+}
+
+#[test]
+fn axis_iter() {
+    let a = Tensor::from_fn(&Shape::from([3, 2, 2].as_ref()), |p| 100*p[0] + 10*p[1] + p[2]);
+    let views = a.axis_iter(1).collect::<Vec<_>>();
+    assert_eq!(views.len(), 2);
+    assert_eq!(views[1].shape(), &Shape::from([3, 2].as_ref()));
+    assert_eq!(views[0][&[2, 1][..]], 201);
+    assert_eq!(view_values(a.axis_iter(1).nth(1).unwrap()), [10, 110, 210, 11, 111, 211]);
+    assert_eq!(a.axis_iter(0).map(|v| v.to_tensor().iter().sum::<usize>()).collect::<Vec<_>>(), [22, 422, 822]);
+    assert_eq!(a.axis_iter(5).count(), 1);
+}
+
+#[test]
+fn lanes() {
+    let a = Tensor::from_fn(&Shape::from([3, 2, 2].as_ref()), |p| 100*p[0] + 10*p[1] + p[2]);
+    let lanes = a.lanes(0).map(view_values).collect::<Vec<_>>();
+    assert_eq!(lanes, [[0, 100, 200], [10, 110, 210], [1, 101, 201], [11, 111, 211]]);
+    assert_eq!(a.lanes(2).map(view_values).nth(5).unwrap(), [210, 211]);
+    assert_eq!(a.lanes(1).count(), 6);
+}
+
+#[test]
+fn windows() {
+    let a = Tensor::from_fn(&Shape::from([4, 3].as_ref()), |p| 10*p[0] + p[1]);
+    let windows = a.windows(&Shape::from([2, 2].as_ref())).collect::<Vec<_>>();
+    assert_eq!(windows.len(), 6);
+    assert_eq!(view_values(a.windows(&Shape::from([2, 2].as_ref())).next_back().unwrap()), [21, 31, 22, 32]);
+    assert_eq!(windows[4].get(&[1, 1]), Some(&22));
+    assert_eq!(windows[4].get(&[2, 1]), None);
+    assert_eq!(a.windows(&Shape::from([5].as_ref())).count(), 0);
+    assert_eq!(a.windows(&Shape::from([1, 1, 1].as_ref())).count(), 12);
+}
+
+#[test]
+fn exact_chunks() {
+    let a = Tensor::from_fn(&Shape::from([5, 4].as_ref()), |p| 10*p[0] + p[1]);
+    let chunks = a.exact_chunks(&Shape::from([2, 2].as_ref())).map(view_values).collect::<Vec<_>>();
+    assert_eq!(chunks, [[0, 10, 1, 11], [20, 30, 21, 31], [2, 12, 3, 13], [22, 32, 23, 33]]);
+    assert_eq!(a.exact_chunks(&Shape::from([6].as_ref())).count(), 0);
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn half() {
+    use half::{f16, bf16};
+    use crate::num::Float;
+
+    let a = Tensor::new_filled(&Shape::from([2, 3].as_ref()), f16::from_f32(1.5));
+    assert!(a.iter().all(|x| x.to_f32() == 1.5));
+
+    let b = Tensor::new_filled(&Shape::from([2, 3].as_ref()), bf16::from_f32(0.25));
+    let sum = b.iter().fold(0.0, |acc, x| acc + x.to_acc());
+    assert_eq!(bf16::from_acc(sum), bf16::from_f32(1.5));
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn uniform_half() {
+    use half::f16;
+    let (low, high) = (f16::from_f32(0.0), f16::from_f32(1.0));
+    let a = Tensor::uniform(&Shape::from([1 << 16].as_ref()), low, high, 0);
+    assert!(a.iter().all(|&x| low <= x && x < high));
+    assert!(a.iter().any(|&x| x == f16::from_bits(high.to_bits() - 1)));
+}
+
+#[test]
+fn cast() {
+    let mut a = Tensor::<i32>::new_zeroed(&Shape::from([2, 3].as_ref()));
+    for (i, v) in a.iter_mut().enumerate() {
+        *v = 100*(i as i32) - 200;
+    }
+
+    let b = a.cast::<u8>();
+    assert_eq!(b.shape(), a.shape());
+    assert_eq!(values(&b), [56, 156, 0, 100, 200, 44]);
+
+    let c = a.cast::<bool>().cast::<f32>();
+    assert_eq!(values(&c), [1.0, 1.0, 0.0, 1.0, 1.0, 1.0]);
+}
+
+#[test]
+fn new_ones() {
+    let a = Tensor::<bool>::new_ones(&Shape::from([4, 3].as_ref()));
+    assert!(a.iter().all(|&x| x));
+}
+
+#[test]
+fn from_vec() {
+    let a = Tensor::from_vec(&Shape::from([2, 3].as_ref()), (0..6).collect());
+    assert_eq!(values(&a), [0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn from_fn() {
+    let a = Tensor::from_fn(&Shape::from([2, 3, 4].as_ref()), |p| 100*p[2] + 10*p[1] + p[0]);
+    for (i, &v) in a.iter().enumerate() {
+        assert_eq!(v, 100*(i/6) + 10*((i/2) % 3) + i % 2);
+    }
+}
+
+#[test]
+fn arange() {
+    let a = Tensor::arange(2, 11, 3);
+    assert_eq!(a.shape(), &Shape::from([3].as_ref()));
+    assert_eq!(values(&a), [2, 5, 8]);
+
+    let b = Tensor::arange(1.0, 0.0, -0.25);
+    assert_eq!(values(&b), [1.0, 0.75, 0.5, 0.25]);
+
+    assert_eq!(Tensor::arange(3u8, 1, 1).shape().content(), 0);
+
+    let c = Tensor::arange((1u64 << 60) + 1, (1 << 60) + 4, 1);
+    assert_eq!(values(&c), [(1 << 60) + 1, (1 << 60) + 2, (1 << 60) + 3]);
+    let d = Tensor::arange(i64::MAX, i64::MIN, i64::MIN);
+    assert_eq!(values(&d), [i64::MAX, -1]);
+    let e = Tensor::arange(-128i8, 127, 100);
+    assert_eq!(values(&e), [-128, -28, 72]);
+    let f = Tensor::arange(i64::MIN, i64::MAX, i64::MAX);
+    assert_eq!(values(&f), [i64::MIN, -1, i64::MAX - 1]);
+}
+
+#[test]
+#[should_panic]
+fn arange_infinite() {
+    Tensor::arange(0.0, f64::INFINITY, 1.0);
+}
+
+#[test]
+fn linspace() {
+    let a = Tensor::linspace(-1.0f32, 1.0, 5);
+    assert_eq!(values(&a), [-1.0, -0.5, 0.0, 0.5, 1.0]);
+    assert_eq!(values(&Tensor::linspace(2.0f64, 3.0, 1)), [2.0]);
+}
+
+#[test]
+fn eye() {
+    let a = Tensor::<i32>::eye(3);
+    assert_eq!(values(&a), [1, 0, 0, 0, 1, 0, 0, 0, 1]);
+}
+
+#[test]
+fn uniform() {
+    let shape = Shape::from([10, 100].as_ref());
+    let a = Tensor::uniform(&shape, -1.0f32, 3.0, 42);
+    assert!(a.iter().all(|x| (-1.0..3.0).contains(x)));
+    let mean = a.iter().sum::<f32>() / 1000.0;
+    assert!((mean - 1.0).abs() < 0.2);
+
+    let b = Tensor::uniform(&shape, -1.0f32, 3.0, 42);
+    assert!(a.iter().zip(b.iter()).all(|(x, y)| x == y));
+    let c = Tensor::uniform(&shape, -1.0f32, 3.0, 43);
+    assert!(a.iter().zip(c.iter()).any(|(x, y)| x != y));
+}
+
+#[test]
+#[should_panic]
+fn uniform_inverted() {
+    Tensor::uniform(&Shape::from([4].as_ref()), 1.0f32, -1.0, 0);
+}
+
+#[test]
+fn normal() {
+    let a = Tensor::normal(&Shape::from([1000].as_ref()), 2.0f64, 0.5, 1);
+    let mean = a.iter().sum::<f64>() / 1000.0;
+    let var = a.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 1000.0;
+    assert!((mean - 2.0).abs() < 0.1);
+    assert!((var.sqrt() - 0.5).abs() < 0.1);
+}
+
+#[test]
+fn bernoulli() {
+    let a = Tensor::bernoulli(&Shape::from([1000].as_ref()), 0.25, 7);
+    let count = a.iter().filter(|&&x| x).count();
+    assert!(150 < count && count < 350);
+    assert!(Tensor::bernoulli(&Shape::from([100].as_ref()), 1.0, 0).iter().all(|&x| x));
+    assert!(Tensor::bernoulli(&Shape::from([100].as_ref()), 0.0, 0).iter().all(|&x| !x));
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn sample() {
+    use rand::{SeedableRng, distributions::Uniform};
+    let mut rng = crate::Philox::seed_from_u64(0);
+    let a = Tensor::sample(&Shape::from([10, 10].as_ref()), &Uniform::new(0, 10), &mut rng);
+    assert!(a.iter().all(|&x| (0..10).contains(&x)));
+}
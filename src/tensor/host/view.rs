@@ -0,0 +1,154 @@
+use std::{
+    iter::FusedIterator,
+    ops::Index,
+};
+use crate::{
+    Prm,
+    Shape, Tensor, HostTensor, HostTensorIter,
+};
+use super::iter::{Positions, contiguous_strides};
+
+/// Read-only view of a part of host tensor, it refers to the tensor data without copying.
+pub struct HostTensorView<'a, T: Prm> {
+    values: &'a [T],
+    offset: usize,
+    shape: Shape,
+    strides: Vec<usize>,
+}
+
+impl<'a, T: Prm> HostTensorView<'a, T> {
+    fn new(values: &'a [T], offset: usize, shape: Shape, strides: Vec<usize>) -> Self {
+        Self { values, offset, shape, strides }
+    }
+
+    /// Shape of the view.
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+    /// Iterator over flatten view.
+    pub fn iter(&self) -> HostTensorIter<'a, T> {
+        HostTensorIter::strided(self.values, &self.shape, self.offset, &self.strides)
+    }
+    /// Reference to element at specified position, `None` if position is out of view.
+    pub fn get(&self, position: &[usize]) -> Option<&'a T> {
+        if position.len() < self.shape.len() {
+            return None;
+        }
+        let mut offset = self.offset;
+        for (i, &x) in position.iter().enumerate() {
+            if x >= self.shape[i] {
+                return None;
+            }
+            offset += x * self.strides.get(i).cloned().unwrap_or(0);
+        }
+        Some(&self.values[offset])
+    }
+    /// Copies viewed elements to a new tensor.
+    pub fn to_tensor(&self) -> HostTensor<T> {
+        HostTensor::from_vec(&self.shape, self.iter().cloned().collect())
+    }
+}
+
+impl<'a, T: Prm> Index<&[usize]> for HostTensorView<'a, T> {
+    type Output = T;
+    fn index(&self, position: &[usize]) -> &T {
+        match self.get(position) {
+            Some(x) => x,
+            None => panic!("Position {:?} is out of view of shape {}", position, self.shape),
+        }
+    }
+}
+
+/// Iterator over views of the same shape placed at the nodes of a regular grid over tensor.
+pub struct HostTensorViews<'a, T: Prm> {
+    values: &'a [T],
+    grid: Positions,
+    shape: Shape,
+    strides: Vec<usize>,
+}
+
+impl<'a, T: Prm> HostTensorViews<'a, T> {
+    /// Views of `shape` with element `strides` which origins are at positions of `grid` with `grid_strides`.
+    fn new(values: &'a [T], grid: &Shape, grid_strides: &[usize], shape: Shape, strides: Vec<usize>) -> Self {
+        Self { values, grid: Positions::new(grid, 0, grid_strides), shape, strides }
+    }
+}
+
+impl<'a, T: Prm> Iterator for HostTensorViews<'a, T> {
+    type Item = HostTensorView<'a, T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, offset) = self.grid.next()?;
+        Some(HostTensorView::new(self.values, offset, self.shape.clone(), self.strides.clone()))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.grid.len(), Some(self.grid.len()))
+    }
+}
+
+impl<'a, T: Prm> DoubleEndedIterator for HostTensorViews<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (_, offset) = self.grid.next_back()?;
+        Some(HostTensorView::new(self.values, offset, self.shape.clone(), self.strides.clone()))
+    }
+}
+
+impl<'a, T: Prm> ExactSizeIterator for HostTensorViews<'a, T> {}
+
+impl<'a, T: Prm> FusedIterator for HostTensorViews<'a, T> {}
+
+/// Sizes and strides of axes of tensor, padded with unit axes up to `rank`.
+fn axes<T: Prm>(tensor: &HostTensor<T>, rank: usize) -> (Vec<usize>, Vec<usize>) {
+    let shape = tensor.shape();
+    let rank = rank.max(shape.len());
+    let dims = (0..rank).map(|i| shape[i]).collect::<Vec<_>>();
+    let mut strides = contiguous_strides(shape);
+    strides.resize(rank, shape.content());
+    (dims, strides)
+}
+
+/// Removes the axis from sizes or strides.
+fn without(values: &[usize], axis: usize) -> Vec<usize> {
+    values.iter().enumerate().filter(|&(i, _)| i != axis).map(|(_, &x)| x).collect()
+}
+
+impl<T: Prm> HostTensor<T> {
+    /// View of the whole tensor.
+    pub fn view(&self) -> HostTensorView<'_, T> {
+        HostTensorView::new(self.buffer().as_slice(), 0, self.shape().clone(), contiguous_strides(self.shape()))
+    }
+
+    /// Iterator over sub-tensors at each index along the axis, the axis is removed from their shape.
+    pub fn axis_iter(&self, axis: usize) -> HostTensorViews<'_, T> {
+        let (dims, strides) = axes(self, axis + 1);
+        let grid = Shape::from(vec![dims[axis]]);
+        HostTensorViews::new(self.buffer().as_slice(), &grid, &strides[axis..], Shape::from(without(&dims, axis)), without(&strides, axis))
+    }
+
+    /// Iterator over 1-dimensional lanes along the axis, for each position of other axes with axis 0 varying fastest.
+    pub fn lanes(&self, axis: usize) -> HostTensorViews<'_, T> {
+        let (dims, strides) = axes(self, axis + 1);
+        let grid = Shape::from(without(&dims, axis));
+        HostTensorViews::new(self.buffer().as_slice(), &grid, &without(&strides, axis), Shape::from(vec![dims[axis]]), vec![strides[axis]])
+    }
+
+    /// Iterator over all overlapping windows of specified shape with unit step.
+    ///
+    /// There are no windows if the window is larger than the tensor along some axis.
+    pub fn windows(&self, window: &Shape) -> HostTensorViews<'_, T> {
+        let (dims, strides) = axes(self, window.len());
+        let grid = Shape::from(dims.iter().enumerate().map(|(i, &n)| (n + 1).saturating_sub(window[i])).collect::<Vec<_>>());
+        HostTensorViews::new(self.buffer().as_slice(), &grid, &strides, window.clone(), strides.clone())
+    }
+
+    /// Iterator over non-overlapping chunks of specified shape that tile the tensor.
+    ///
+    /// Remainders along axes which sizes are not divisible by the chunk size are skipped.
+    pub fn exact_chunks(&self, chunk: &Shape) -> HostTensorViews<'_, T> {
+        let (dims, strides) = axes(self, chunk.len());
+        assert!((0..dims.len()).all(|i| chunk[i] > 0), "Chunk of shape {} is empty", chunk);
+        let grid = Shape::from(dims.iter().enumerate().map(|(i, &n)| n / chunk[i]).collect::<Vec<_>>());
+        let grid_strides = strides.iter().enumerate().map(|(i, &s)| s * chunk[i]).collect::<Vec<_>>();
+        HostTensorViews::new(self.buffer().as_slice(), &grid, &grid_strides, chunk.clone(), strides)
+    }
+}
+
@@ -0,0 +1,146 @@
+use crate::{
+    Prm, Buffer, Blocks, Shape, Tensor, CommonTensor,
+};
+
+
+/// Splits tensor of specified shape into blocks along the axis.
+///
+/// Returns the number of values in a single slice of axis, the size of axis and the number of blocks.
+pub(crate) fn axis_blocks(shape: &Shape, axis: usize) -> (usize, usize, usize) {
+    let inner = (0..axis).map(|i| shape[i]).product();
+    let outer = ((axis + 1)..shape.len()).map(|i| shape[i]).product();
+    (inner, shape[axis], outer)
+}
+
+/// Inserts a new axis of size `1` into shape.
+fn insert_axis(shape: &Shape, axis: usize) -> Shape {
+    let mut vec = (0..axis.max(shape.len())).map(|i| shape[i]).collect::<Vec<_>>();
+    vec.insert(axis, 1);
+    Shape::from(vec)
+}
+
+impl<T: Prm, Buf: Buffer<T>> CommonTensor<T, Buf> {
+    /// Joins tensors along the existing axis.
+    pub fn concat(tensors: &[&Self], axis: usize) -> Self {
+        assert!(!tensors.is_empty(), "At least one tensor is required");
+        let mut shape = tensors[0].shape().clone();
+        shape[axis] = 0;
+        for tensor in tensors {
+            let other = tensor.shape();
+            let len = shape.len().max(other.len());
+            assert!(
+                (0..len).all(|i| i == axis || shape[i] == other[i]),
+                "Shapes {} and {} differ not only along axis {}", tensors[0].shape(), other, axis,
+            );
+            shape[axis] += other[axis];
+        }
+        let (inner, size, outer) = axis_blocks(&shape, axis);
+        let mut buffer = unsafe { Buf::new_uninit_in(tensors[0].buffer().context(), shape.content()) };
+        let mut start = 0;
+        for tensor in tensors {
+            let len = inner * tensor.shape()[axis];
+            buffer.copy_blocks_from(tensor.buffer(), &Blocks {
+                len, count: outer,
+                src_offset: 0, src_stride: len,
+                dst_offset: start, dst_stride: inner * size,
+            });
+            start += len;
+        }
+        Self::from_buffer(buffer, &shape)
+    }
+    /// Joins tensors of the same shape along the new axis.
+    pub fn stack(tensors: &[&Self], axis: usize) -> Self {
+        assert!(!tensors.is_empty(), "At least one tensor is required");
+        let shape = tensors[0].shape();
+        assert!(tensors.iter().all(|t| t.shape() == shape), "All tensors must have the same shape");
+        let shape = insert_axis(shape, axis);
+        let tensors = tensors.iter().map(|t| t.reshape(&shape)).collect::<Vec<_>>();
+        Self::concat(&tensors.iter().collect::<Vec<_>>(), axis)
+    }
+
+    /// Splits tensor along the axis into parts of specified sizes.
+    pub fn split(&self, sizes: &[usize], axis: usize) -> Vec<Self> {
+        let (inner, size, outer) = axis_blocks(self.shape(), axis);
+        assert_eq!(sizes.iter().sum::<usize>(), size, "Sizes must sum up to the size of axis {}", axis);
+        let mut start = 0;
+        sizes.iter().map(|&n| {
+            let mut shape = self.shape().clone();
+            shape[axis] = n;
+            let mut buffer = unsafe { Buf::new_uninit_in(self.buffer().context(), shape.content()) };
+            buffer.copy_blocks_from(self.buffer(), &Blocks {
+                len: inner * n, count: outer,
+                src_offset: start, src_stride: inner * size,
+                dst_offset: 0, dst_stride: inner * n,
+            });
+            start += inner * n;
+            Self::from_buffer(buffer, &shape)
+        }).collect()
+    }
+    /// Splits tensor along the axis into `n` parts of the same size, the last part may be smaller.
+    ///
+    /// Size of parts is rounded up, so there may be less than `n` parts.
+    pub fn chunk(&self, n: usize, axis: usize) -> Vec<Self> {
+        assert!(n > 0, "Number of chunks must be positive");
+        let size = self.shape()[axis];
+        let step = size.div_ceil(n).max(1);
+        let sizes = (0..size).step_by(step).map(|i| step.min(size - i)).collect::<Vec<_>>();
+        self.split(&sizes, axis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shape, values, Tensor, HostTensor};
+
+    #[test]
+    fn concat() {
+        let a = HostTensor::from_fn(&shape![2, 3], |p| 10*p[0] + p[1]);
+        let b = HostTensor::from_fn(&shape![2, 1], |p| 100 + 10*p[0]);
+        let c = HostTensor::concat(&[&a, &b], 1);
+        assert_eq!(c.shape(), &shape![2, 4]);
+        assert_eq!(format!("{}", c), "[[  0   1   2 100]\n [ 10  11  12 110]]");
+
+        let d = HostTensor::concat(&[&a, &a, &a], 0);
+        assert_eq!(d.shape(), &shape![6, 3]);
+        assert_eq!(values(&d)[..6], [0, 10, 0, 10, 0, 10]);
+
+        let e = HostTensor::concat(&[&a, &a], 2);
+        assert_eq!(e.shape(), &shape![2, 3, 2]);
+        assert_eq!(values(&e), [values(&a), values(&a)].concat());
+    }
+
+    #[test]
+    #[should_panic]
+    fn concat_mismatch() {
+        let a = HostTensor::<usize>::new_zeroed(&shape![2, 3]);
+        let b = HostTensor::<usize>::new_zeroed(&shape![3, 3]);
+        HostTensor::concat(&[&a, &b], 1);
+    }
+
+    #[test]
+    fn stack() {
+        let a = HostTensor::from_fn(&shape![2, 3], |p| 10*p[0] + p[1]);
+        let b = HostTensor::from_fn(&shape![2, 3], |p| 100 + 10*p[0] + p[1]);
+        let c = HostTensor::stack(&[&a, &b], 0);
+        assert_eq!(c.shape(), &shape![2, 2, 3]);
+        assert_eq!(values(&c)[..4], [0, 100, 10, 110]);
+        assert_eq!(HostTensor::stack(&[&a, &b], 1).shape(), &shape![2, 2, 3]);
+        assert_eq!(HostTensor::stack(&[&a, &b], 2).shape(), &shape![2, 3, 2]);
+        assert_eq!(HostTensor::stack(&[&a, &b], 4).shape(), &shape![2, 3, 1, 1, 2]);
+    }
+
+    #[test]
+    fn split() {
+        let a = HostTensor::from_fn(&shape![3, 5, 2], |p| 100*p[0] + 10*p[1] + p[2]);
+        let parts = a.split(&[2, 0, 3], 1);
+        assert_eq!(parts.iter().map(|t| t.shape().clone()).collect::<Vec<_>>(), [shape![3, 2, 2], shape![3, 0, 2], shape![3, 3, 2]]);
+        assert_eq!(values(&parts[2]), values(&HostTensor::from_fn(&shape![3, 3, 2], |p| 100*p[0] + 10*(p[1] + 2) + p[2])));
+        let refs = parts.iter().collect::<Vec<_>>();
+        assert_eq!(values(&HostTensor::concat(&refs, 1)), values(&a));
+
+        let chunks = a.chunk(2, 1);
+        assert_eq!(chunks.iter().map(|t| t.shape()[1]).collect::<Vec<_>>(), [3, 2]);
+        assert_eq!(a.chunk(4, 0).len(), 3);
+        assert_eq!(a.chunk(2, 3).len(), 1);
+    }
+}
@@ -0,0 +1,506 @@
+use std::{
+    fmt,
+    error::Error,
+    ops::{Index, IndexMut},
+};
+use num_traits::Float;
+use crate::{
+    Prm, Field, Shape, Tensor, HostTensor,
+    num::{Zero, One},
+};
+
+
+/// Error of linear algebra operation, it contains the index of matrix in batch that caused the error.
+///
+/// Linear algebra operations of [`HostTensor`] treat the first two axes of tensor as matrix axes,
+/// so that element `(i, j)` of matrix of `n` rows and `m` columns is at position `(i, j, ..)`.
+/// The rest of axes are batch ones, operations are applied to each matrix of batch independently,
+/// and `batch` is the index of matrix in the flattened batch axes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinalgError {
+    /// Matrix is singular.
+    Singular { batch: usize },
+    /// Matrix is not positive-definite.
+    NotPositiveDefinite { batch: usize },
+    /// Eigenvalue iterations did not converge.
+    NoConvergence { batch: usize },
+}
+
+impl fmt::Display for LinalgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LinalgError::Singular { batch } => write!(f, "Matrix {} is singular", batch),
+            LinalgError::NotPositiveDefinite { batch } => write!(f, "Matrix {} is not positive-definite", batch),
+            LinalgError::NoConvergence { batch } => write!(f, "Eigenvalues of matrix {} did not converge", batch),
+        }
+    }
+}
+
+impl Error for LinalgError {}
+
+/// Maximum number of Jacobi sweeps in eigenvalue computation.
+const MAX_SWEEPS: usize = 64;
+
+/// Column-major matrix.
+#[derive(Clone, Debug)]
+struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Field> Matrix<T> {
+    fn zeros(rows: usize, cols: usize) -> Self {
+        Self { rows, cols, data: vec![<T as Zero>::zero(); rows * cols] }
+    }
+    fn identity(n: usize) -> Self {
+        let mut a = Self::zeros(n, n);
+        for i in 0..n {
+            a[(i, i)] = <T as One>::one();
+        }
+        a
+    }
+    /// Submatrix of the first `rows` rows and `cols` columns.
+    fn top_left(&self, rows: usize, cols: usize) -> Self {
+        let data = (0..cols).flat_map(|j| (0..rows).map(move |i| (i, j))).map(|p| self[p]).collect();
+        Self { rows, cols, data }
+    }
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for j in 0..self.cols {
+            self.data.swap(a + self.rows * j, b + self.rows * j);
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        &self.data[i + self.rows * j]
+    }
+}
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        &mut self.data[i + self.rows * j]
+    }
+}
+
+/// Splits tensor into matrices of batch, returns them along with the batch shape.
+fn matrices<T: Field>(tensor: &HostTensor<T>) -> (Vec<Matrix<T>>, Vec<usize>) {
+    let shape = tensor.shape();
+    let (rows, cols) = (shape[0], shape[1]);
+    let batch = (2..shape.len()).map(|i| shape[i]).collect::<Vec<_>>();
+    let count = batch.iter().product::<usize>();
+    let values = tensor.buffer().as_slice();
+    let matrices = (0..count).map(|k| {
+        Matrix { rows, cols, data: values[(k * rows * cols)..((k + 1) * rows * cols)].to_vec() }
+    }).collect();
+    (matrices, batch)
+}
+
+/// Joins items of the same shape `dims` into tensor with batch axes.
+fn join<U: Prm>(items: Vec<Vec<U>>, dims: &[usize], batch: &[usize]) -> HostTensor<U> {
+    let shape = Shape::from(dims.iter().chain(batch).cloned().collect::<Vec<_>>());
+    HostTensor::from_vec(&shape, items.into_iter().flatten().collect())
+}
+
+fn check_square(shape: &Shape) -> usize {
+    assert_eq!(shape[0], shape[1], "Matrix of shape {} is not square", shape);
+    shape[0]
+}
+
+/// LU decomposition with partial pivoting of square matrix.
+struct Lu<T> {
+    /// Strictly lower part contains `L` without unit diagonal, the rest is `U`.
+    lu: Matrix<T>,
+    /// Row `i` of `LU` is row `perm[i]` of the original matrix.
+    perm: Vec<usize>,
+    /// Permutation is odd.
+    odd: bool,
+    /// Some of pivots is negligible compared to the matrix elements.
+    singular: bool,
+}
+
+impl<T: Field> Lu<T> {
+    fn new(mut a: Matrix<T>) -> Self {
+        let n = a.rows;
+        let (mut perm, mut odd, mut singular) = ((0..n).collect::<Vec<_>>(), false, false);
+        // Pivot is treated as zero if it is below the rounding error of elimination.
+        let max = a.data.iter().fold(<T::Real as Zero>::zero(), |m, x| Float::max(m, x.abs()));
+        let tol = num_traits::cast::<_, T::Real>(n).unwrap() * <T::Real as Float>::epsilon() * max;
+        for k in 0..n {
+            let p = (k..n).fold(k, |p, i| if a[(i, k)].abs() > a[(p, k)].abs() { i } else { p });
+            if p != k {
+                a.swap_rows(p, k);
+                perm.swap(p, k);
+                odd = !odd;
+            }
+            let pivot = a[(k, k)];
+            if pivot.abs() <= tol {
+                singular = true;
+                if pivot == <T as Zero>::zero() {
+                    continue;
+                }
+            }
+            for i in (k + 1)..n {
+                let f = a[(i, k)] / pivot;
+                a[(i, k)] = f;
+                for j in (k + 1)..n {
+                    let x = a[(k, j)];
+                    a[(i, j)] = a[(i, j)] - f * x;
+                }
+            }
+        }
+        Self { lu: a, perm, odd, singular }
+    }
+
+    fn det(&self) -> T {
+        let det = (0..self.lu.rows).fold(<T as One>::one(), |d, i| d * self.lu[(i, i)]);
+        if self.odd { <T as Zero>::zero() - det } else { det }
+    }
+
+    /// Solves `A X = B` for non-singular matrix.
+    fn solve(&self, b: &Matrix<T>) -> Matrix<T> {
+        let n = self.lu.rows;
+        let mut x = Matrix::zeros(n, b.cols);
+        for j in 0..b.cols {
+            for i in 0..n {
+                let s = (0..i).fold(b[(self.perm[i], j)], |s, k| s - self.lu[(i, k)] * x[(k, j)]);
+                x[(i, j)] = s;
+            }
+            for i in (0..n).rev() {
+                let s = ((i + 1)..n).fold(x[(i, j)], |s, k| s - self.lu[(i, k)] * x[(k, j)]);
+                x[(i, j)] = s / self.lu[(i, i)];
+            }
+        }
+        x
+    }
+}
+
+/// Reduced QR decomposition by Householder reflections.
+fn qr<T: Field>(a: Matrix<T>) -> (Matrix<T>, Matrix<T>) {
+    let (m, n) = (a.rows, a.cols);
+    let (mut q, mut r) = (Matrix::<T>::identity(m), a);
+    let two = T::from_real(<T::Real as One>::one() + <T::Real as One>::one());
+    for k in 0..m.min(n) {
+        let norm = (k..m).map(|i| r[(i, k)].abs()).fold(<T::Real as Zero>::zero(), |s, x| s + x * x);
+        let norm = Float::sqrt(norm);
+        if norm == <T::Real as Zero>::zero() {
+            continue;
+        }
+        let x0 = r[(k, k)];
+        let phase = if x0 == <T as Zero>::zero() { <T as One>::one() } else { x0 / T::from_real(x0.abs()) };
+        let mut v = (k..m).map(|i| r[(i, k)]).collect::<Vec<_>>();
+        v[0] = v[0] + phase * T::from_real(norm);
+        let vnorm = v.iter().fold(<T::Real as Zero>::zero(), |s, x| s + x.abs() * x.abs());
+        let vnorm = T::from_real(Float::sqrt(vnorm));
+        v.iter_mut().for_each(|x| *x = *x / vnorm);
+        // Apply `H = I - 2 v v^H` to `R` from the left and to `Q` from the right.
+        for j in 0..n {
+            let w = v.iter().enumerate().fold(<T as Zero>::zero(), |s, (l, x)| s + x.conj() * r[(k + l, j)]);
+            for (l, x) in v.iter().enumerate() {
+                r[(k + l, j)] = r[(k + l, j)] - two * *x * w;
+            }
+        }
+        for i in 0..m {
+            let w = v.iter().enumerate().fold(<T as Zero>::zero(), |s, (l, x)| s + q[(i, k + l)] * *x);
+            for (l, x) in v.iter().enumerate() {
+                q[(i, k + l)] = q[(i, k + l)] - two * w * x.conj();
+            }
+        }
+        for i in (k + 1)..m {
+            r[(i, k)] = <T as Zero>::zero();
+        }
+    }
+    let p = m.min(n);
+    (q.top_left(m, p), r.top_left(p, n))
+}
+
+/// Cholesky decomposition, returns `None` if matrix is not positive-definite.
+fn cholesky<T: Field>(a: &Matrix<T>) -> Option<Matrix<T>> {
+    let n = a.rows;
+    let mut l = Matrix::<T>::zeros(n, n);
+    for j in 0..n {
+        let d = (0..j).fold(a[(j, j)].re(), |s, k| s - l[(j, k)].abs() * l[(j, k)].abs());
+        if Float::is_nan(d) || d <= <T::Real as Zero>::zero() {
+            return None;
+        }
+        let d = T::from_real(Float::sqrt(d));
+        l[(j, j)] = d;
+        for i in (j + 1)..n {
+            let s = (0..j).fold(a[(i, j)], |s, k| s - l[(i, k)] * l[(j, k)].conj());
+            l[(i, j)] = s / d;
+        }
+    }
+    Some(l)
+}
+
+/// Eigenvalues and eigenvectors of Hermitian matrix by cyclic Jacobi method, returns `None` if it did not converge.
+fn eigh<T: Field>(mut a: Matrix<T>) -> Option<(Vec<T::Real>, Matrix<T>)> {
+    let n = a.rows;
+    let (zero, one) = (<T::Real as Zero>::zero(), <T::Real as One>::one());
+    let mut v = Matrix::<T>::identity(n);
+    let norm = a.data.iter().fold(zero, |s, x| s + x.abs() * x.abs());
+    let mut converged = false;
+    for _ in 0..MAX_SWEEPS {
+        let off = (0..n).flat_map(|j| (0..n).map(move |i| (i, j))).filter(|(i, j)| i != j)
+            .fold(zero, |s, p| s + a[p].abs() * a[p].abs());
+        if off <= norm * <T::Real as Float>::epsilon() * <T::Real as Float>::epsilon() {
+            converged = true;
+            break;
+        }
+        for p in 0..n {
+            for q in (p + 1)..n {
+                let b = a[(p, q)];
+                let r = b.abs();
+                if r == zero {
+                    continue;
+                }
+                // Rotation `V = diag(1, conj(b)/|b|) [[c, s], [-s, c]]` zeroes `a[p, q]`.
+                let phase = (b / T::from_real(r)).conj();
+                let theta = (a[(q, q)].re() - a[(p, p)].re()) / (r + r);
+                let t = Float::signum(theta) / (Field::abs(theta) + Float::sqrt(theta * theta + one));
+                let c = one / Float::sqrt(t * t + one);
+                let s = t * c;
+                let (vpp, vpq) = (T::from_real(c), T::from_real(s));
+                let (vqp, vqq) = (T::from_real(-s) * phase, T::from_real(c) * phase);
+                for m in [&mut a, &mut v] {
+                    for k in 0..n {
+                        let (x, y) = (m[(k, p)], m[(k, q)]);
+                        m[(k, p)] = x * vpp + y * vqp;
+                        m[(k, q)] = x * vpq + y * vqq;
+                    }
+                }
+                for k in 0..n {
+                    let (x, y) = (a[(p, k)], a[(q, k)]);
+                    a[(p, k)] = vpp.conj() * x + vqp.conj() * y;
+                    a[(q, k)] = vpq.conj() * x + vqq.conj() * y;
+                }
+                a[(p, q)] = <T as Zero>::zero();
+                a[(q, p)] = <T as Zero>::zero();
+            }
+        }
+    }
+    if !converged {
+        return None;
+    }
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.sort_by(|&i, &j| a[(i, i)].re().partial_cmp(&a[(j, j)].re()).unwrap());
+    let values = order.iter().map(|&i| a[(i, i)].re()).collect();
+    let data = order.iter().flat_map(|&j| (0..n).map(move |i| (i, j))).map(|p| v[p]).collect();
+    Some((values, Matrix { rows: n, cols: n, data }))
+}
+
+impl<T: Field> HostTensor<T> {
+    /// LU decomposition with partial pivoting of square matrices.
+    ///
+    /// Matrices occupy the first two axes of tensor and the rest of axes are batch ones, see [`LinalgError`].
+    ///
+    /// Returns unit lower-triangular `L`, upper-triangular `U` and permutation `perm`
+    /// such that row `i` of `L U` is row `perm[i]` of the matrix.
+    pub fn lu(&self) -> (Self, Self, HostTensor<usize>) {
+        let n = check_square(self.shape());
+        let (matrices, batch) = matrices(self);
+        let (mut ls, mut us, mut perms) = (Vec::new(), Vec::new(), Vec::new());
+        for a in matrices {
+            let Lu { lu, perm, .. } = Lu::new(a);
+            let (mut l, mut u) = (Matrix::<T>::identity(n), Matrix::<T>::zeros(n, n));
+            for j in 0..n {
+                for i in 0..n {
+                    if i > j { l[(i, j)] = lu[(i, j)] } else { u[(i, j)] = lu[(i, j)] }
+                }
+            }
+            ls.push(l.data);
+            us.push(u.data);
+            perms.push(perm);
+        }
+        (join(ls, &[n, n], &batch), join(us, &[n, n], &batch), join(perms, &[n], &batch))
+    }
+
+    /// Determinant of square matrices, result has the batch shape.
+    pub fn det(&self) -> Self {
+        check_square(self.shape());
+        let (matrices, batch) = matrices(self);
+        join(matrices.into_iter().map(|a| vec![Lu::new(a).det()]).collect(), &[], &batch)
+    }
+
+    /// Solves `A X = B` where `self` is `A` of shape `(n, n, ..)` and `b` is of shape `(n, k, ..)` with the same batch axes.
+    ///
+    /// Matrix is considered singular if some of its pivots does not exceed `n * eps * max|A|`.
+    pub fn solve(&self, b: &Self) -> Result<Self, LinalgError> {
+        let n = check_square(self.shape());
+        let (matrices, batch) = matrices(self);
+        let (rhs, rhs_batch) = self::matrices(b);
+        assert!(b.shape()[0] == n && rhs_batch == batch, "Shape {} of right-hand side does not match matrix of shape {}", b.shape(), self.shape());
+        let k = b.shape()[1];
+        let xs = matrices.into_iter().zip(rhs.iter()).enumerate().map(|(i, (a, b))| {
+            let lu = Lu::new(a);
+            if lu.singular {
+                return Err(LinalgError::Singular { batch: i });
+            }
+            Ok(lu.solve(b).data)
+        }).collect::<Result<Vec<_>, _>>()?;
+        Ok(join(xs, &[n, k], &batch))
+    }
+
+    /// Inverse of square matrices, singular ones are detected the same way as in [`HostTensor::solve`].
+    pub fn inv(&self) -> Result<Self, LinalgError> {
+        let n = check_square(self.shape());
+        let (matrices, batch) = matrices(self);
+        let xs = matrices.into_iter().enumerate().map(|(i, a)| {
+            let lu = Lu::new(a);
+            if lu.singular {
+                return Err(LinalgError::Singular { batch: i });
+            }
+            Ok(lu.solve(&Matrix::identity(n)).data)
+        }).collect::<Result<Vec<_>, _>>()?;
+        Ok(join(xs, &[n, n], &batch))
+    }
+
+    /// Reduced QR decomposition of `(n, m)` matrices.
+    ///
+    /// Returns `Q` of shape `(n, k)` with orthonormal columns and upper-triangular `R` of shape `(k, m)` where `k = min(n, m)`.
+    pub fn qr(&self) -> (Self, Self) {
+        let (n, m) = (self.shape()[0], self.shape()[1]);
+        let (matrices, batch) = matrices(self);
+        let (qs, rs): (Vec<_>, Vec<_>) = matrices.into_iter().map(|a| {
+            let (q, r) = qr(a);
+            (q.data, r.data)
+        }).unzip();
+        (join(qs, &[n, n.min(m)], &batch), join(rs, &[n.min(m), m], &batch))
+    }
+
+    /// Cholesky decomposition of Hermitian positive-definite matrices.
+    ///
+    /// Returns lower-triangular `L` such that `A = L L^H`, only lower triangle of `A` is used.
+    pub fn cholesky(&self) -> Result<Self, LinalgError> {
+        let n = check_square(self.shape());
+        let (matrices, batch) = matrices(self);
+        let ls = matrices.iter().enumerate().map(|(i, a)| {
+            cholesky(a).map(|l| l.data).ok_or(LinalgError::NotPositiveDefinite { batch: i })
+        }).collect::<Result<Vec<_>, _>>()?;
+        Ok(join(ls, &[n, n], &batch))
+    }
+
+    /// Eigenvalues and eigenvectors of Hermitian (real symmetric) matrices.
+    ///
+    /// Returns eigenvalues in ascending order of shape `(n, ..)` and matrices which columns are the corresponding eigenvectors.
+    pub fn eigh(&self) -> Result<(HostTensor<T::Real>, Self), LinalgError> {
+        let n = check_square(self.shape());
+        let (matrices, batch) = matrices(self);
+        let (values, vectors): (Vec<_>, Vec<_>) = matrices.into_iter().enumerate().map(|(i, a)| {
+            eigh(a).map(|(w, v)| (w, v.data)).ok_or(LinalgError::NoConvergence { batch: i })
+        }).collect::<Result<Vec<_>, _>>()?.into_iter().unzip();
+        Ok((join(values, &[n], &batch), join(vectors, &[n, n], &batch)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex;
+    use crate::{shape, values, sample, assert_close};
+
+    /// Matrix product of batches.
+    fn matmul<T: Field>(a: &HostTensor<T>, b: &HostTensor<T>) -> HostTensor<T> {
+        let ((xs, batch), (ys, _)) = (matrices(a), matrices(b));
+        let (n, k, m) = (a.shape()[0], a.shape()[1], b.shape()[1]);
+        let zs = xs.iter().zip(ys.iter()).map(|(x, y)| {
+            (0..m).flat_map(|j| (0..n).map(move |i| (i, j)))
+                .map(|(i, j)| (0..k).fold(<T as Zero>::zero(), |s, l| s + x[(i, l)] * y[(l, j)])).collect()
+        }).collect();
+        join(zs, &[n, m], &batch)
+    }
+
+    /// Conjugate transpose of batch.
+    fn adjoint<T: Field>(a: &HostTensor<T>) -> HostTensor<T> {
+        let (xs, batch) = matrices(a);
+        let (n, m) = (a.shape()[0], a.shape()[1]);
+        let ys = xs.iter().map(|x| (0..n).flat_map(|j| (0..m).map(move |i| x[(j, i)].conj())).collect()).collect();
+        join(ys, &[m, n], &batch)
+    }
+
+    #[test]
+    fn lu_det_solve() {
+        let a = sample(&shape![4, 4, 3], 0);
+        let (l, u, perm) = a.lu();
+        let permuted = HostTensor::from_fn(a.shape(), |p| a.buffer().as_slice()[perm.buffer().as_slice()[p[0] + 4*p[2]] + 4*(p[1] + 4*p[2])]);
+        assert_close(&matmul(&l, &u), &permuted);
+
+        let d = a.det();
+        assert_eq!(d.shape(), &shape![3]);
+        let b = sample(&shape![4, 2, 3], 0);
+        let x = a.solve(&b).unwrap();
+        assert_close(&matmul(&a, &x), &b);
+        let inv = a.inv().unwrap();
+        assert_close(&matmul(&inv, &a), &HostTensor::from_fn(a.shape(), |p| if p[0] == p[1] { 1.0 } else { 0.0 }));
+        assert_close(&inv.det(), &HostTensor::from_vec(d.shape(), d.iter().map(|x| 1.0 / x).collect()));
+
+        let m = HostTensor::from_vec(&shape![2, 2], vec![2.0, 1.0, 5.0, 3.0]);
+        assert_close(&m.det(), &HostTensor::from_vec(&shape![], vec![1.0]));
+    }
+
+    #[test]
+    fn singular() {
+        let a = HostTensor::from_vec(&shape![2, 2, 2], vec![1.0, 0.0, 0.0, 1.0, 1.0, 2.0, 2.0, 4.0]);
+        assert_eq!(a.inv().err(), Some(LinalgError::Singular { batch: 1 }));
+        assert_eq!(a.solve(&HostTensor::new_ones(&shape![2, 1, 2])).err(), Some(LinalgError::Singular { batch: 1 }));
+        assert_eq!(values(&a.det()), [1.0, 0.0]);
+        assert_eq!(a.cholesky().err(), Some(LinalgError::NotPositiveDefinite { batch: 1 }));
+    }
+
+    #[test]
+    fn rank_deficient() {
+        // Third column is the sum of the first two, elimination leaves rounding error instead of zero pivot.
+        let (x, y) = ([0.1, 0.4, 0.7], [0.2, 0.5, 0.8]);
+        let data = x.iter().chain(y.iter()).cloned().chain(x.iter().zip(y.iter()).map(|(a, b)| a + b)).collect();
+        let a = HostTensor::from_vec(&shape![3, 3], data);
+        assert_ne!(a.det().buffer().as_slice()[0], 0.0);
+        assert_eq!(a.inv().err(), Some(LinalgError::Singular { batch: 0 }));
+        assert_eq!(a.solve(&HostTensor::new_ones(&shape![3, 1])).err(), Some(LinalgError::Singular { batch: 0 }));
+    }
+
+    #[test]
+    fn qr_cholesky() {
+        for shape in [shape![5, 3, 2], shape![3, 5]] {
+            let a = sample(&shape, 0);
+            let (q, r) = a.qr();
+            assert_close(&matmul(&q, &r), &a);
+            let k = q.shape()[1];
+            assert_close(&matmul(&adjoint(&q), &q), &HostTensor::from_fn(&shape![k, k, shape[2]], |p| if p[0] == p[1] { 1.0 } else { 0.0 }));
+            assert!(r.iter().enumerate().all(|(i, &x)| i % r.shape()[0] <= (i / r.shape()[0]) % r.shape()[1] || x == 0.0));
+        }
+
+        let a = sample(&shape![3, 3], 0);
+        let s = matmul(&a, &adjoint(&a));
+        let l = s.cholesky().unwrap();
+        assert_close(&matmul(&l, &adjoint(&l)), &s);
+    }
+
+    #[test]
+    fn complex() {
+        let a = HostTensor::from_fn(&shape![3, 3], |p| Complex::new((p[0] + 2*p[1]) as f64, (p[0] as f64 - p[1] as f64).cos()));
+        let x = a.solve(&HostTensor::new_ones(&shape![3])).unwrap();
+        assert_close(&matmul(&a, &x), &HostTensor::new_ones(&shape![3]));
+        let (q, r) = a.qr();
+        assert_close(&matmul(&q, &r), &a);
+
+        let h = matmul(&a, &adjoint(&a));
+        let (w, v) = h.eigh().unwrap();
+        let lambda = HostTensor::from_fn(&shape![3, 3], |p| if p[0] == p[1] { Complex::from(w.buffer().as_slice()[p[0]]) } else { Complex::from(0.0) });
+        assert_close(&matmul(&h, &v), &matmul(&v, &lambda));
+        assert!(w.buffer().as_slice().windows(2).all(|p| p[0] <= p[1]));
+        let l = h.cholesky().unwrap();
+        assert_close(&matmul(&l, &adjoint(&l)), &h);
+    }
+
+    #[test]
+    fn symmetric_eigen() {
+        let a = sample(&shape![4, 4, 2], 0);
+        let s = matmul(&a, &adjoint(&a));
+        let (w, v) = s.eigh().unwrap();
+        assert_eq!(w.shape(), &shape![4, 2]);
+        let lambda = HostTensor::from_fn(s.shape(), |p| if p[0] == p[1] { w.buffer().as_slice()[p[0] + 4*p[2]] } else { 0.0 });
+        assert_close(&matmul(&s, &v), &matmul(&v, &lambda));
+        assert_close(&matmul(&adjoint(&v), &v), &HostTensor::from_fn(s.shape(), |p| if p[0] == p[1] { 1.0 } else { 0.0 }));
+    }
+}
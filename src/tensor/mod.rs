@@ -14,3 +14,39 @@ pub use host::*;
 mod device;
 #[cfg(feature = "device")]
 pub use device::*;
+
+mod format;
+mod join;
+pub(crate) use join::axis_blocks;
+
+mod einsum;
+pub use einsum::EinsumError;
+
+mod conv;
+pub use conv::{ConvOptions, PoolOptions};
+#[cfg(feature = "device")]
+pub(crate) use conv::{Window, ConvGeometry};
+
+mod complex;
+
+#[cfg(any(feature = "rustfft", feature = "device"))]
+mod fft;
+#[cfg(any(feature = "rustfft", feature = "device"))]
+pub use fft::FftFloat;
+
+mod linalg;
+pub use linalg::LinalgError;
+
+mod pad;
+pub use pad::PadMode;
+
+mod sort;
+
+mod select;
+#[cfg(feature = "device")]
+pub(crate) use select::{with_axis, check_index, check_range};
+
+#[cfg(test)]
+mod testing;
+#[cfg(test)]
+pub(crate) use testing::*;
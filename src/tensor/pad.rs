@@ -0,0 +1,144 @@
+use crate::{
+    Prm, Shape, Tensor, HostTensor,
+};
+#[cfg(feature = "device")]
+use crate::{Interop, Buffer, DeviceBuffer, DeviceTensor, kernel};
+
+
+/// Mode of filling padded values.
+///
+/// Modes are named after PyTorch ones, e.g. for `[1 2 3]` padded by 2 at both ends:
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PadMode<T> {
+    /// Padded values are equal to specified one, `[0 0 1 2 3 0 0]` for zero.
+    Constant(T),
+    /// Values are reflected about the edge without repeating it, `[3 2 1 2 3 2 1]`.
+    Reflect,
+    /// Edge value is repeated, `[1 1 1 2 3 3 3]`.
+    Replicate,
+    /// Values are wrapped around, `[2 3 1 2 3 1 2]`.
+    Circular,
+}
+
+impl<T> PadMode<T> {
+    /// Index of value in axis of size `n` that corresponds to index `j` outside of it, `None` stands for constant.
+    fn source_index(&self, j: isize, n: usize) -> Option<usize> {
+        let n = n as isize;
+        match self {
+            PadMode::Constant(_) => if j >= 0 && j < n { Some(j) } else { None },
+            PadMode::Reflect if n == 1 => Some(0),
+            PadMode::Reflect => {
+                let m = 2*(n - 1);
+                let j = j.rem_euclid(m);
+                Some(if j < n { j } else { m - j })
+            }
+            PadMode::Replicate => Some(j.clamp(0, n - 1)),
+            PadMode::Circular => Some(j.rem_euclid(n)),
+        }.map(|j| j as usize)
+    }
+}
+
+/// Shape of the padded tensor.
+fn padded_shape<T>(shape: &Shape, pads: &[(usize, usize)], mode: &PadMode<T>) -> Shape {
+    let len = shape.len().max(pads.len());
+    Shape::from((0..len).map(|i| {
+        let (before, after) = pads.get(i).cloned().unwrap_or((0, 0));
+        let padded = before + after > 0;
+        assert!(
+            !padded || shape[i] > 0 || matches!(mode, PadMode::Constant(_)),
+            "Axis {} of size 0 can only be padded with constant", i,
+        );
+        shape[i] + before + after
+    }).collect::<Vec<_>>())
+}
+
+impl<T: Prm> HostTensor<T> {
+    /// Create a new tensor padded by `pads[i] = (before, after)` values at the ends of `i`-th axis.
+    ///
+    /// Axes beyond `pads` are not padded. Padding may be longer than the axis,
+    /// in that case reflected and circular values are repeated periodically.
+    pub fn pad(&self, pads: &[(usize, usize)], mode: PadMode<T>) -> Self {
+        let shape = padded_shape(self.shape(), pads, &mode);
+        let before = (0..shape.len()).map(|i| pads.get(i).map_or(0, |p| p.0)).collect::<Vec<_>>();
+        // Source indices and strides along each axis.
+        let mut stride = 1;
+        let maps = shape.iter().zip(before).enumerate().map(|(i, (&m, b))| {
+            let n = self.shape()[i];
+            let map = (0..m).map(|x| mode.source_index(x as isize - b as isize, n).map(|j| j * stride)).collect::<Vec<_>>();
+            stride *= n;
+            map
+        }).collect::<Vec<_>>();
+        let src = self.buffer().as_slice();
+        Self::from_fn(&shape, |p| {
+            let offset = p.iter().zip(maps.iter()).try_fold(0, |offset, (&x, map)| map[x].map(|j| offset + j));
+            match (offset, mode) {
+                (Some(j), _) => src[j],
+                (None, PadMode::Constant(value)) => value,
+                (None, _) => unreachable!(),
+            }
+        })
+    }
+}
+
+#[cfg(feature = "device")]
+impl<T: Prm + Interop> DeviceTensor<T> {
+    /// Create a new tensor padded by `pads[i] = (before, after)` values at the ends of `i`-th axis,
+    /// see [`HostTensor::pad`].
+    pub fn pad(&self, pads: &[(usize, usize)], mode: PadMode<T>) -> Self {
+        let context = self.context();
+        if !context.supports::<T>() {
+            return Self::from_host(context, &self.to_host().pad(pads, mode));
+        }
+        let shape = padded_shape(self.shape(), pads, &mode);
+        let dims = (0..shape.len()).map(|i| self.shape()[i]).collect::<Vec<_>>();
+        let before = (0..shape.len()).map(|i| pads.get(i).map_or(0, |p| p.0)).collect::<Vec<_>>();
+        let mut dst = unsafe { DeviceBuffer::new_uninit_in(context, shape.content()) };
+        kernel::pad(self.buffer(), &mut dst, &dims, &before, shape.as_slice(), &mode);
+        Self::from_buffer(dst, &shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shape, values};
+
+    fn padded(mode: PadMode<i32>, pad: usize) -> Vec<i32> {
+        values(&HostTensor::from_vec(&shape![3], vec![1, 2, 3]).pad(&[(pad, pad)], mode))
+    }
+
+    #[test]
+    fn modes() {
+        assert_eq!(padded(PadMode::Constant(0), 2), [0, 0, 1, 2, 3, 0, 0]);
+        assert_eq!(padded(PadMode::Reflect, 2), [3, 2, 1, 2, 3, 2, 1]);
+        assert_eq!(padded(PadMode::Replicate, 2), [1, 1, 1, 2, 3, 3, 3]);
+        assert_eq!(padded(PadMode::Circular, 2), [2, 3, 1, 2, 3, 1, 2]);
+        assert_eq!(padded(PadMode::Reflect, 5), [2, 1, 2, 3, 2, 1, 2, 3, 2, 1, 2, 3, 2]);
+        assert_eq!(padded(PadMode::Circular, 4), [3, 1, 2, 3, 1, 2, 3, 1, 2, 3, 1]);
+    }
+
+    #[test]
+    fn rank_2() {
+        let a = HostTensor::from_fn(&shape![2, 3], |p| (3*p[0] + p[1]) as i32);
+        let b = a.pad(&[(1, 0), (0, 2)], PadMode::Constant(-1));
+        assert_eq!(b.shape(), &shape![3, 5]);
+        assert_eq!(format!("{}", b), "[[-1 -1 -1 -1 -1]\n [ 0  1  2 -1 -1]\n [ 3  4  5 -1 -1]]");
+        let c = a.pad(&[(0, 1), (1, 1)], PadMode::Replicate);
+        assert_eq!(format!("{}", c), "[[0 0 1 2 2]\n [3 3 4 5 5]\n [3 3 4 5 5]]");
+        let d = a.pad(&[(0, 0), (0, 0), (1, 1)], PadMode::Circular);
+        assert_eq!(d.shape(), &shape![2, 3, 3]);
+        assert!(d.iter().eq(a.iter().chain(a.iter()).chain(a.iter())));
+    }
+
+    #[test]
+    fn empty() {
+        let a = HostTensor::<f32>::new_zeroed(&shape![0, 2]);
+        assert_eq!(values(&a.pad(&[(1, 1)], PadMode::Constant(1.0))), [1.0; 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_reflect() {
+        HostTensor::<f32>::new_zeroed(&shape![0, 2]).pad(&[(1, 1)], PadMode::Reflect);
+    }
+}
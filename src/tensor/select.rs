@@ -0,0 +1,161 @@
+use crate::{
+    Prm, Num, Shape, Tensor, HostTensor,
+    axis_blocks,
+};
+
+
+/// Shape of the tensor with the size of axis replaced.
+pub(crate) fn with_axis(shape: &Shape, axis: usize, size: usize) -> Shape {
+    let mut shape = shape.clone();
+    shape[axis] = size;
+    shape
+}
+
+/// Checks that index tensor has the same shape as the tensor except for the axis,
+/// returns the size of index along the axis.
+pub(crate) fn check_index(shape: &Shape, index: &Shape, axis: usize) -> usize {
+    let len = shape.len().max(index.len());
+    assert!(
+        (0..len).all(|i| i == axis || shape[i] == index[i]),
+        "Index of shape {} does not match tensor of shape {} along axes other than {}", index, shape, axis,
+    );
+    index[axis]
+}
+
+/// Checks that index is in range of axis of size `n`.
+pub(crate) fn check_range(j: usize, n: usize, axis: usize) -> usize {
+    assert!(j < n, "Index {} is out of range for axis {} of size {}", j, axis, n);
+    j
+}
+
+impl<T: Prm> HostTensor<T> {
+    /// Selects slices along the axis at specified indices.
+    ///
+    /// Indices are taken from flattened `indices` tensor,
+    /// result has the same shape as `self` except for the axis whose size is the number of indices.
+    /// Panics if some index is out of range.
+    pub fn index_select(&self, axis: usize, indices: &HostTensor<usize>) -> Self {
+        let (inner, n, outer) = axis_blocks(self.shape(), axis);
+        let (src, idx) = (self.buffer().as_slice(), indices.buffer().as_slice());
+        let mut data = Vec::with_capacity(inner * idx.len() * outer);
+        for o in 0..outer {
+            for &j in idx {
+                let start = inner * (check_range(j, n, axis) + n*o);
+                data.extend_from_slice(&src[start..(start + inner)]);
+            }
+        }
+        Self::from_vec(&with_axis(self.shape(), axis, idx.len()), data)
+    }
+
+    /// Gathers values along the axis, `result[i][j][k] = self[i][index[i][j][k]][k]` for `axis = 1`.
+    ///
+    /// Index must have the same shape as `self` except for the axis, result has the shape of index.
+    /// Panics if some index is out of range.
+    pub fn gather(&self, axis: usize, index: &HostTensor<usize>) -> Self {
+        let m = check_index(self.shape(), index.shape(), axis);
+        let (inner, n, outer) = axis_blocks(self.shape(), axis);
+        let (src, idx) = (self.buffer().as_slice(), index.buffer().as_slice());
+        let data = (0..(inner * m * outer)).map(|p| {
+            let (i, o) = (p % inner, p / (inner * m));
+            src[i + inner*(check_range(idx[p], n, axis) + n*o)]
+        }).collect();
+        Self::from_vec(index.shape(), data)
+    }
+
+    /// Writes values of `src` along the axis, `self[i][index[i][j][k]][k] = src[i][j][k]` for `axis = 1`.
+    ///
+    /// Index and `src` must have the same shape that differs from the `self` one only along the axis.
+    /// If index contains duplicates then the value with the greatest position along the axis is written.
+    /// Panics if some index is out of range.
+    pub fn scatter(&mut self, axis: usize, index: &HostTensor<usize>, src: &Self) {
+        self.scatter_with(axis, index, src, |_, y| y);
+    }
+
+    fn scatter_with<F: Fn(T, T) -> T>(&mut self, axis: usize, index: &HostTensor<usize>, src: &Self, f: F) {
+        let m = check_index(self.shape(), index.shape(), axis);
+        assert_eq!(src.shape(), index.shape(), "Source and index shapes must be equal");
+        let (inner, n, _) = axis_blocks(self.shape(), axis);
+        let (idx, values) = (index.buffer().as_slice(), src.buffer().as_slice());
+        let dst = self.buffer_mut().as_mut_slice();
+        for (p, (&j, &x)) in idx.iter().zip(values.iter()).enumerate() {
+            let (i, o) = (p % inner, p / (inner * m));
+            let d = &mut dst[i + inner*(check_range(j, n, axis) + n*o)];
+            *d = f(*d, x);
+        }
+    }
+
+    /// Selects elements where mask is `true` into tensor of rank 1.
+    ///
+    /// Mask must have the same shape as `self`, elements are taken in the flattened order.
+    pub fn masked_select(&self, mask: &HostTensor<bool>) -> Self {
+        assert_eq!(self.shape(), mask.shape(), "Mask shape must be equal to tensor shape");
+        let data = self.iter().zip(mask.iter()).filter(|(_, &m)| m).map(|(&x, _)| x).collect::<Vec<_>>();
+        Self::from_vec(&Shape::from(vec![data.len()]), data)
+    }
+}
+
+impl<T: Prm + Num> HostTensor<T> {
+    /// Adds values of `src` along the axis, `self[i][index[i][j][k]][k] += src[i][j][k]` for `axis = 1`.
+    ///
+    /// See [`HostTensor::scatter`], values for duplicate indices are all added.
+    /// Panics if some index is out of range.
+    pub fn scatter_add(&mut self, axis: usize, index: &HostTensor<usize>, src: &Self) {
+        self.scatter_with(axis, index, src, |x, y| x + y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shape, values};
+
+    #[test]
+    fn index_select() {
+        let a = HostTensor::from_fn(&shape![3, 4], |p| 10*p[0] + p[1]);
+        let rows = a.index_select(0, &HostTensor::from_vec(&shape![3], vec![2, 0, 2]));
+        assert_eq!(format!("{}", rows), "[[20 21 22 23]\n [ 0  1  2  3]\n [20 21 22 23]]");
+        let cols = a.index_select(1, &HostTensor::from_vec(&shape![2], vec![3, 1]));
+        assert_eq!(format!("{}", cols), "[[ 3  1]\n [13 11]\n [23 21]]");
+        assert_eq!(a.index_select(1, &HostTensor::from_vec(&shape![0], vec![])).shape(), &shape![3, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_range() {
+        HostTensor::<i32>::new_zeroed(&shape![3, 4]).index_select(0, &HostTensor::from_vec(&shape![1], vec![3]));
+    }
+
+    #[test]
+    fn gather() {
+        let a = HostTensor::from_fn(&shape![2, 3], |p| 10*p[0] + p[1]);
+        let index = HostTensor::from_vec(&shape![2, 2], vec![2, 0, 2, 1]);
+        let b = a.gather(1, &index);
+        assert_eq!(format!("{}", b), "[[ 2  2]\n [10 11]]");
+        let c = a.gather(0, &HostTensor::from_vec(&shape![1, 3], vec![1, 0, 1]));
+        assert_eq!(values(&c), [10, 1, 12]);
+    }
+
+    #[test]
+    fn scatter() {
+        let mut a = HostTensor::<i32>::new_zeroed(&shape![2, 4]);
+        let index = HostTensor::from_vec(&shape![2, 2], vec![3, 0, 3, 1]);
+        let src = HostTensor::from_vec(&shape![2, 2], vec![1, 2, 3, 4]);
+        a.scatter(1, &index, &src);
+        assert_eq!(format!("{}", a), "[[0 0 0 3]\n [2 4 0 0]]");
+
+        let mut b = HostTensor::<i32>::new_ones(&shape![2, 4]);
+        b.scatter_add(1, &index, &src);
+        assert_eq!(format!("{}", b), "[[1 1 1 5]\n [3 5 1 1]]");
+        assert_eq!(values(&b.gather(1, &index)), [5, 3, 5, 5]);
+    }
+
+    #[test]
+    fn masked_select() {
+        let a = HostTensor::from_fn(&shape![2, 3], |p| 10*p[0] + p[1]);
+        let mask = HostTensor::from_fn(&shape![2, 3], |p| (p[0] + p[1]) % 2 == 0);
+        let b = a.masked_select(&mask);
+        assert_eq!(b.shape(), &shape![3]);
+        assert_eq!(values(&b), [0, 11, 2]);
+        assert_eq!(a.masked_select(&HostTensor::new_filled(&shape![2, 3], false)).shape(), &shape![0]);
+    }
+}
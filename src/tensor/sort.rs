@@ -0,0 +1,225 @@
+use std::cmp::Ordering;
+use crate::{
+    Prm, Num, Shape, Tensor, HostTensor,
+};
+#[cfg(feature = "device")]
+use crate::{Interop, Buffer, DeviceBuffer, DeviceTensor, kernel};
+use super::axis_blocks;
+
+
+/// Value is not equal to itself, that is NaN.
+fn is_nan<T: PartialOrd>(x: &T) -> bool {
+    x.partial_cmp(x).is_none()
+}
+
+/// Total ordering of values in which NaN is greater than any other value.
+fn compare<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or_else(|| is_nan(a).cmp(&is_nan(b)))
+}
+
+impl<T: Prm + PartialOrd> HostTensor<T> {
+    /// Sorts each lane along the axis, returns sorted values and their original positions.
+    fn sort_lanes(&self, axis: usize, descending: bool) -> (Self, HostTensor<usize>) {
+        let (inner, n, outer) = axis_blocks(self.shape(), axis);
+        let src = self.buffer().as_slice();
+        let (mut values, mut indices) = (src.to_vec(), vec![0; src.len()]);
+        let mut order = Vec::with_capacity(n);
+        for o in 0..outer {
+            for x in 0..inner {
+                let offset = |k: usize| x + inner*(k + n*o);
+                order.clear();
+                order.extend(0..n);
+                // Sort is stable, so equal values keep their order in both directions.
+                if descending {
+                    order.sort_by(|&a, &b| compare(&src[offset(b)], &src[offset(a)]));
+                } else {
+                    order.sort_by(|&a, &b| compare(&src[offset(a)], &src[offset(b)]));
+                }
+                for (k, &j) in order.iter().enumerate() {
+                    values[offset(k)] = src[offset(j)];
+                    indices[offset(k)] = j;
+                }
+            }
+        }
+        (Self::from_vec(self.shape(), values), HostTensor::from_vec(self.shape(), indices))
+    }
+
+    /// Sorts values along the axis in ascending order.
+    ///
+    /// Sort is stable and NaN values go after all others.
+    pub fn sort(&self, axis: usize) -> Self {
+        self.sort_lanes(axis, false).0
+    }
+    /// Positions along the axis that sort values in ascending order, see [`HostTensor::sort`].
+    pub fn argsort(&self, axis: usize) -> HostTensor<usize> {
+        self.sort_lanes(axis, false).1
+    }
+    /// `k` largest values along the axis in descending order and their positions.
+    pub fn topk(&self, k: usize, axis: usize) -> (Self, HostTensor<usize>) {
+        let n = self.shape()[axis];
+        assert!(k <= n, "Cannot take {} values from axis {} of size {}", k, axis, n);
+        let (values, indices) = self.sort_lanes(axis, true);
+        (values.split(&[k, n - k], axis).swap_remove(0), indices.split(&[k, n - k], axis).swap_remove(0))
+    }
+
+    /// Distinct values of the tensor in ascending order and the number of occurrences of each of them.
+    pub fn unique(&self) -> (Self, HostTensor<usize>) {
+        let mut values = self.buffer().as_slice().to_vec();
+        values.sort_by(compare);
+        let (mut unique, mut counts) = (Vec::<T>::new(), Vec::new());
+        for x in values {
+            match unique.last() {
+                Some(y) if compare(&x, y) == Ordering::Equal => *counts.last_mut().unwrap() += 1,
+                _ => {
+                    unique.push(x);
+                    counts.push(1);
+                }
+            }
+        }
+        let shape = Shape::from(vec![unique.len()]);
+        (Self::from_vec(&shape, unique), HostTensor::from_vec(&shape, counts))
+    }
+
+    /// Positions at which `values` should be inserted into sorted rank-1 tensor to keep it sorted.
+    ///
+    /// Position is the first one where value is not greater than the element, result has the shape of `values`.
+    pub fn searchsorted(&self, values: &Self) -> HostTensor<usize> {
+        assert!(self.shape().len() <= 1, "Tensor of shape {} is not of rank 1", self.shape());
+        let sorted = self.buffer().as_slice();
+        let indices = values.buffer().as_slice().iter().map(|v| {
+            sorted.partition_point(|x| compare(x, v) == Ordering::Less)
+        }).collect();
+        HostTensor::from_vec(values.shape(), indices)
+    }
+}
+
+impl<T: Prm + Num> HostTensor<T> {
+    /// Applies cumulative operation along the axis.
+    fn scan<F: Fn(T, T) -> T>(&self, axis: usize, f: F) -> Self {
+        let (inner, n, outer) = axis_blocks(self.shape(), axis);
+        let mut values = self.buffer().as_slice().to_vec();
+        for o in 0..outer {
+            for k in 1..n {
+                for x in 0..inner {
+                    let i = x + inner*(k + n*o);
+                    values[i] = f(values[i - inner], values[i]);
+                }
+            }
+        }
+        Self::from_vec(self.shape(), values)
+    }
+
+    /// Cumulative sum along the axis.
+    pub fn cumsum(&self, axis: usize) -> Self {
+        self.scan(axis, |a, b| a + b)
+    }
+    /// Cumulative product along the axis.
+    pub fn cumprod(&self, axis: usize) -> Self {
+        self.scan(axis, |a, b| a * b)
+    }
+}
+
+#[cfg(feature = "device")]
+impl<T: Prm + Interop + PartialOrd> DeviceTensor<T> {
+    /// Sorts each lane along the axis on the device, returns sorted values and their original positions.
+    fn sort_lanes(&self, axis: usize, descending: bool) -> (Self, DeviceTensor<usize>) {
+        let context = self.context();
+        if !context.supports::<T>() {
+            let (values, indices) = self.to_host().sort_lanes(axis, descending);
+            return (Self::from_host(context, &values), DeviceTensor::from_host(context, &indices));
+        }
+        let len = self.buffer().len();
+        let mut values = unsafe { DeviceBuffer::new_uninit_in(context, len) };
+        let mut indices = unsafe { DeviceBuffer::new_uninit_in(context, len) };
+        kernel::sort(self.buffer(), &mut values, &mut indices, axis_blocks(self.shape(), axis), descending);
+        (Self::from_buffer(values, self.shape()), DeviceTensor::from_buffer(indices, self.shape()))
+    }
+
+    /// Sorts values along the axis in ascending order, see [`HostTensor::sort`].
+    pub fn sort(&self, axis: usize) -> Self {
+        self.sort_lanes(axis, false).0
+    }
+    /// Positions along the axis that sort values in ascending order, see [`HostTensor::sort`].
+    pub fn argsort(&self, axis: usize) -> DeviceTensor<usize> {
+        self.sort_lanes(axis, false).1
+    }
+    /// `k` largest values along the axis in descending order and their positions.
+    pub fn topk(&self, k: usize, axis: usize) -> (Self, DeviceTensor<usize>) {
+        let n = self.shape()[axis];
+        assert!(k <= n, "Cannot take {} values from axis {} of size {}", k, axis, n);
+        let (values, indices) = self.sort_lanes(axis, true);
+        (values.split(&[k, n - k], axis).swap_remove(0), indices.split(&[k, n - k], axis).swap_remove(0))
+    }
+}
+
+#[cfg(feature = "device")]
+impl<T: Prm + Interop + Num> DeviceTensor<T> {
+    /// Applies cumulative operation along the axis on the device.
+    fn scan(&self, axis: usize, product: bool) -> Self {
+        let context = self.context();
+        if !context.supports::<T>() {
+            let host = self.to_host();
+            return Self::from_host(context, &if product { host.cumprod(axis) } else { host.cumsum(axis) });
+        }
+        Self::from_buffer(kernel::scan(self.buffer(), axis_blocks(self.shape(), axis), product), self.shape())
+    }
+
+    /// Cumulative sum along the axis.
+    pub fn cumsum(&self, axis: usize) -> Self {
+        self.scan(axis, false)
+    }
+    /// Cumulative product along the axis.
+    pub fn cumprod(&self, axis: usize) -> Self {
+        self.scan(axis, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shape, values};
+
+    #[test]
+    fn sort() {
+        let a = HostTensor::from_vec(&shape![4, 2], vec![3, 1, 2, 1, 0, 5, 5, 0]);
+        assert_eq!(values(&a.sort(0)), [1, 1, 2, 3, 0, 0, 5, 5]);
+        assert_eq!(values(&a.argsort(0)), [1, 3, 2, 0, 0, 3, 1, 2]);
+        assert_eq!(values(&a.sort(1)), [0, 1, 2, 0, 3, 5, 5, 1]);
+
+        let b = HostTensor::from_vec(&shape![4], vec![1.0, f64::NAN, -1.0, 0.5]);
+        assert_eq!(values(&b.argsort(0)), [2, 3, 0, 1]);
+    }
+
+    #[test]
+    fn topk() {
+        let a = HostTensor::from_vec(&shape![5, 2], vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3]);
+        let (v, i) = a.topk(2, 0);
+        assert_eq!(v.shape(), &shape![2, 2]);
+        assert_eq!(values(&v), [5, 4, 9, 6]);
+        assert_eq!(values(&i), [4, 2, 0, 2]);
+        assert_eq!(a.topk(0, 0).0.shape(), &shape![0, 2]);
+    }
+
+    #[test]
+    fn unique() {
+        let (v, c) = HostTensor::from_vec(&shape![2, 3], vec![2, 7, 2, 1, 7, 2]).unique();
+        assert_eq!(values(&v), [1, 2, 7]);
+        assert_eq!(values(&c), [1, 3, 2]);
+    }
+
+    #[test]
+    fn searchsorted() {
+        let a = HostTensor::from_vec(&shape![4], vec![1, 3, 3, 7]);
+        let i = a.searchsorted(&HostTensor::from_vec(&shape![3, 2], vec![0, 1, 2, 3, 7, 8]));
+        assert_eq!(i.shape(), &shape![3, 2]);
+        assert_eq!(values(&i), [0, 0, 1, 1, 3, 4]);
+    }
+
+    #[test]
+    fn cumulative() {
+        let a = HostTensor::from_fn(&shape![3, 2], |p| (p[0] + 3*p[1] + 1) as i32);
+        assert_eq!(values(&a.cumsum(0)), [1, 3, 6, 4, 9, 15]);
+        assert_eq!(values(&a.cumsum(1)), [1, 2, 3, 5, 7, 9]);
+        assert_eq!(values(&a.cumprod(0)), [1, 2, 6, 4, 20, 120]);
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 use crate::{
     Prm, Buffer, Shape,
 };
@@ -7,6 +9,7 @@ use crate::{
 ///
 /// Both `begin` and `end` indices can be negative that means indexing from the end (e.g. `-1` means last element).
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Range {
     /// The *inclusive* start index of range.
     pub start: isize,
@@ -19,6 +22,7 @@ pub struct Range {
 
 /// Index for one dimension for tensor slicing operaion.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Index {
     /// Single index that extract a corresponding section of tensor.
     /// Removes corresponding dimension from shape.
@@ -41,6 +45,8 @@ pub trait Tensor<T: Prm>: Sized {
     fn new_filled_in(context: &<Self::Buffer as Buffer<T>>::Context, shape: &Shape, value: T) -> Self;
     /// Create tensor filled with zeros on the specified hardware
     fn new_zeroed_in(context: &<Self::Buffer as Buffer<T>>::Context, shape: &Shape) -> Self;
+    /// Create tensor filled with ones on the specified hardware
+    fn new_ones_in(context: &<Self::Buffer as Buffer<T>>::Context, shape: &Shape) -> Self;
 
     /// Shape of the tensor - a slice containing all tensor dimensions.
     fn shape(&self) -> &Shape;
@@ -0,0 +1,25 @@
+//! Helpers shared by unit tests.
+
+use std::fmt::Debug;
+use crate::{Prm, Field, Shape, Tensor, HostTensor};
+
+/// Values of host tensor in the order of elements.
+pub(crate) fn values<T: Prm>(tensor: &HostTensor<T>) -> Vec<T> {
+    tensor.iter().cloned().collect()
+}
+
+/// Tensor of irregular but reproducible values, different `seed`s give different tensors.
+pub(crate) fn sample(shape: &Shape, seed: usize) -> HostTensor<f64> {
+    HostTensor::from_fn(shape, |p| {
+        let i = p.iter().fold(seed, |i, &x| 5*i + x + 1) as f64;
+        (0.37 * i * i).sin()
+    })
+}
+
+/// Asserts that tensors have the same shape and their elements differ by less than `1e-9`.
+pub(crate) fn assert_close<T: Field + Debug>(a: &HostTensor<T>, b: &HostTensor<T>) {
+    assert_eq!(a.shape(), b.shape());
+    for (&x, &y) in a.iter().zip(b.iter()) {
+        assert!(num_traits::ToPrimitive::to_f64(&Field::abs(x - y)).unwrap() < 1e-9, "{:?} != {:?}", x, y);
+    }
+}